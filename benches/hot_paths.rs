@@ -0,0 +1,116 @@
+//! Benchmarks for the code that runs every frame or in bursts, to put numbers on
+//! changes like a spatial hash for collisions or pooled particles.
+//!
+//! The game is a binary, so the modules under test are compiled in from `src` directly.
+#![allow(dead_code)]
+
+#[path = "../src/bundles.rs"]
+mod bundles;
+#[path = "../src/collision.rs"]
+mod collision;
+#[path = "../src/components.rs"]
+mod components;
+#[path = "../src/constants.rs"]
+mod constants;
+#[path = "../src/resources.rs"]
+mod resources;
+#[path = "../src/utils.rs"]
+mod utils;
+
+use bevy::prelude::*;
+use bundles::SparkParticleBundle;
+use collision::{collision_detection_system, CollisionEvent};
+use components::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use resources::{Level, SpriteSheets};
+use utils::lerp;
+
+fn collision_shape(position: Vec2, radius: f32) -> CollisionShape {
+    CollisionShape::new(
+        Shape::Circle {
+            center: Vec2::ZERO,
+            radius,
+        },
+        Transform::from_translation(position.extend(0.0)),
+    )
+}
+
+/// A ship in the middle of the screen among `count` asteroids scattered around it
+fn collision_world(count: usize) -> World {
+    let mut rng = StdRng::seed_from_u64(count as u64);
+    let mut world = World::new();
+    world.init_resource::<Events<CollisionEvent>>();
+    world.spawn((Ship::default(), collision_shape(Vec2::ZERO, 16.0)));
+    for _ in 0..count {
+        let position = Vec2::new(rng.gen_range(-400.0..400.0), rng.gen_range(-240.0..240.0));
+        let radius = rng.gen_range(8.0..64.0);
+        world.spawn((
+            Asteroid {
+                size: AsteroidSize::Large,
+                variant: 0,
+            },
+            collision_shape(position, radius),
+        ));
+    }
+    world
+}
+
+fn collision_broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collision_detection");
+    for count in [50, 200, 800] {
+        let mut world = collision_world(count);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(collision_detection_system);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| {
+                schedule.run(&mut world);
+                world.resource_mut::<Events<CollisionEvent>>().clear();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn level_asteroids(c: &mut Criterion) {
+    let mut group = c.benchmark_group("level_asteroids");
+    for level in [0, 10, 50] {
+        group.bench_with_input(BenchmarkId::from_parameter(level), &level, |b, &level| {
+            b.iter(|| Level(black_box(level)).asteroids().count())
+        });
+    }
+    group.finish();
+}
+
+fn particle_spawning(c: &mut Criterion) {
+    let sprite_sheets = SpriteSheets::default();
+    let mut group = c.benchmark_group("particle_spawning");
+    // Roughly one small explosion, and a screen full of them
+    for count in [10, 200] {
+        let mut world = World::new();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter(|| {
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    let velocity = Vec2::from_angle(angle) * lerp(40.0, 120.0, 0.5);
+                    world.spawn(SparkParticleBundle::new(
+                        Vec2::ZERO,
+                        velocity,
+                        -velocity,
+                        &sprite_sheets.particles,
+                    ));
+                }
+                world.clear_entities();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    collision_broadphase,
+    level_asteroids,
+    particle_spawning
+);
+criterion_main!(benches);
@@ -1,9 +1,10 @@
-use crate::{components::*, constants::*, resources::*};
+use crate::{components::*, constants::*, lerp, resources::*};
 use bevy::prelude::*;
+use rand::random;
 
 #[derive(Bundle)]
 pub struct PowerupBundle {
-    pub sprite_bundle: SpriteBundle,
+    pub sprite_bundle: SpriteSheetBundle,
     pub powerup: Powerup,
     pub moving: Moving,
     pub collision_shape: CollisionShape,
@@ -20,21 +21,17 @@ impl PowerupBundle {
         life: f32,
         sprite_sheet: &PowerupImages,
     ) -> Self {
-        let texture = match powerup {
-            Powerup::Laser => &sprite_sheet.laser,
-            Powerup::Spread => &sprite_sheet.spread,
-            Powerup::Beam => &sprite_sheet.beam,
-            Powerup::Plasma => &sprite_sheet.plasma,
-            Powerup::ExtraLife => &sprite_sheet.extra_life,
-            Powerup::LoseLife => &sprite_sheet.lose_life,
-            Powerup::Shield => &sprite_sheet.shield,
-        }
-        .clone();
+        let (index, color) = powerup_sprite(powerup, sprite_sheet);
         let transform = Transform::from_translation(position.extend(-0.01));
         Self {
-            sprite_bundle: SpriteBundle {
-                texture,
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: sprite_sheet.atlas.clone(),
                 transform,
+                sprite: TextureAtlasSprite {
+                    index,
+                    color,
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             powerup,
@@ -56,15 +53,49 @@ impl PowerupBundle {
     }
 }
 
+/// Atlas index and tint of a powerup's icon, also used for its effect on the HUD
+pub fn powerup_sprite(powerup: Powerup, sprite_sheet: &PowerupImages) -> (usize, Color) {
+    let index = match powerup {
+        Powerup::Laser => sprite_sheet.laser,
+        Powerup::Spread => sprite_sheet.spread,
+        Powerup::Beam => sprite_sheet.beam,
+        Powerup::Plasma => sprite_sheet.plasma,
+        Powerup::Charge => sprite_sheet.charge,
+        Powerup::Railgun => sprite_sheet.railgun,
+        Powerup::Drone => sprite_sheet.drone,
+        Powerup::PlasmaAmmo => sprite_sheet.plasma_ammo,
+        Powerup::ExtraLife => sprite_sheet.extra_life,
+        Powerup::LoseLife => sprite_sheet.lose_life,
+        Powerup::Shield => sprite_sheet.shield,
+        Powerup::BulletTime => sprite_sheet.shield,
+        Powerup::DoubleScore => sprite_sheet.extra_life,
+        Powerup::Overdrive => sprite_sheet.laser,
+        Powerup::Repair => sprite_sheet.extra_life,
+    };
+    // Newer powerups reuse existing icons, tinted to tell them apart
+    let color = match powerup {
+        Powerup::Charge => Color::rgb(0.5, 0.8, 1.0),
+        Powerup::Railgun => Color::rgb(1.0, 0.5, 1.0),
+        Powerup::Drone => Color::rgb(0.6, 1.0, 0.6),
+        Powerup::PlasmaAmmo => Color::rgb(1.0, 0.7, 0.4),
+        Powerup::BulletTime => Color::rgb(0.7, 0.6, 1.0),
+        Powerup::DoubleScore => Color::rgb(1.0, 0.9, 0.3),
+        Powerup::Overdrive => Color::rgb(1.0, 0.4, 0.3),
+        Powerup::Repair => Color::rgb(0.4, 1.0, 0.8),
+        _ => Color::WHITE,
+    };
+    (index, color)
+}
+
 #[derive(Bundle)]
 pub struct LoadingTextBundle {
     text_bundle: TextBundle,
 }
 impl LoadingTextBundle {
-    pub fn new(font: Handle<Font>) -> Self {
+    pub fn new(text: &str, font: Handle<Font>) -> Self {
         let text_bundle = TextBundle {
             text: Text::from_section(
-                "Loading...",
+                text,
                 TextStyle {
                     font,
                     font_size: 100.0,
@@ -80,10 +111,92 @@ impl LoadingTextBundle {
     }
 }
 
+/// Track of the loading progress bar, filled in by a `LoadingBar` child
+#[derive(Bundle)]
+pub struct LoadingBarBundle {
+    node_bundle: NodeBundle,
+}
+impl Default for LoadingBarBundle {
+    fn default() -> Self {
+        let node_bundle = NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(10.0),
+                bottom: Val::Percent(10.0),
+                width: Val::Percent(80.0),
+                height: Val::Px(16.0),
+                ..Default::default()
+            },
+            background_color: Color::DARK_GRAY.into(),
+            ..Default::default()
+        };
+        LoadingBarBundle { node_bundle }
+    }
+}
+
 fn asteroid_texture_index(variant: usize, size: AsteroidSize) -> usize {
     variant * ASTEROID_SIZES + size as usize
 }
 
+#[derive(Bundle)]
+pub struct DebrisBundle {
+    sprite_sheet_bundle: SpriteSheetBundle,
+    debris: Debris,
+    moving: Moving,
+    spinning: Spinning,
+    expiring: Expiring,
+    collision_shape: CollisionShape,
+}
+impl DebrisBundle {
+    pub fn new(
+        sprite_sheets: &SpriteSheets,
+        asteroid_variant: usize,
+        position: Vec2,
+        velocity: Vec2,
+    ) -> Self {
+        let transform =
+            Transform::from_translation(position.extend(0.05)).with_scale(Vec3::splat(0.4));
+        DebrisBundle {
+            sprite_sheet_bundle: SpriteSheetBundle {
+                texture_atlas: sprite_sheets.asteroids.clone(),
+                sprite: TextureAtlasSprite::new(asteroid_texture_index(
+                    asteroid_variant,
+                    AsteroidSize::Tiny,
+                )),
+                transform,
+                ..Default::default()
+            },
+            debris: Debris,
+            moving: Moving {
+                velocity,
+                ..Default::default()
+            },
+            spinning: Spinning { speed: 4.0 },
+            expiring: Expiring { life: DEBRIS_LIFE },
+            collision_shape: CollisionShape::new(
+                Shape::Circle {
+                    center: Vec2::ZERO,
+                    radius: DEBRIS_RADIUS,
+                },
+                transform,
+            ),
+        }
+    }
+
+    /// A burst of debris flying out every which way
+    pub fn burst(
+        sprite_sheets: &SpriteSheets,
+        asteroid_variant: usize,
+        position: Vec2,
+    ) -> impl Iterator<Item = Self> + '_ {
+        (0..DEBRIS_COUNT).map(move |_| {
+            let velocity = Vec2::from_angle(random::<f32>() * std::f32::consts::TAU)
+                * lerp(80.0, 180.0, random());
+            DebrisBundle::new(sprite_sheets, asteroid_variant, position, velocity)
+        })
+    }
+}
+
 #[derive(Bundle)]
 pub struct AsteroidBundle {
     sprite_sheet_bundle: SpriteSheetBundle,
@@ -91,6 +204,8 @@ pub struct AsteroidBundle {
     spinning: Spinning,
     wrapping: Wrapping,
     asteroid: Asteroid,
+    health: Health,
+    mass: Mass,
     level_entity: LevelEntity,
     collision_shape: CollisionShape,
 }
@@ -118,7 +233,6 @@ impl AsteroidBundle {
         };
         let asteroid = Asteroid {
             size,
-            integrity: size as i32 * 4 + 1,
             variant: asteroid_variant,
         };
         AsteroidBundle {
@@ -126,6 +240,8 @@ impl AsteroidBundle {
             moving,
             spinning,
             asteroid,
+            health: Health::new((size as i32 * 4 + 1) as f32),
+            mass: Mass(size.mass()),
             wrapping: Wrapping,
             level_entity: LevelEntity,
             collision_shape: CollisionShape::new(
@@ -139,18 +255,73 @@ impl AsteroidBundle {
     }
 }
 
+#[derive(Bundle)]
+pub struct MineralBundle {
+    sprite_sheet_bundle: SpriteSheetBundle,
+    mineral: Mineral,
+    moving: Moving,
+    spinning: Spinning,
+    wrapping: Wrapping,
+    expiring: Expiring,
+    level_entity: LevelEntity,
+    collision_shape: CollisionShape,
+}
+impl MineralBundle {
+    pub fn new(
+        sprite_sheets: &SpriteSheets,
+        asteroid_variant: usize,
+        amount: u32,
+        position: Vec2,
+        velocity: Vec2,
+    ) -> Self {
+        let transform = Transform::from_translation(position.extend(-0.01));
+        MineralBundle {
+            sprite_sheet_bundle: SpriteSheetBundle {
+                texture_atlas: sprite_sheets.asteroids.clone(),
+                sprite: TextureAtlasSprite {
+                    index: asteroid_texture_index(asteroid_variant, AsteroidSize::Tiny),
+                    color: Color::rgb(1.0, 0.8, 0.3),
+                    ..Default::default()
+                },
+                transform,
+                ..Default::default()
+            },
+            mineral: Mineral { amount },
+            moving: Moving {
+                velocity,
+                ..Default::default()
+            },
+            spinning: Spinning { speed: 2.0 },
+            wrapping: Wrapping,
+            expiring: Expiring { life: MINERAL_LIFE },
+            level_entity: LevelEntity,
+            collision_shape: CollisionShape::new(
+                Shape::Circle {
+                    center: Vec2::ZERO,
+                    radius: 6.0,
+                },
+                transform,
+            ),
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct ShipBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     moving: Moving,
     wrapping: Wrapping,
     ship: Ship,
+    health: Health,
+    shield: ShieldEnergy,
+    mass: Mass,
     collision_shape: CollisionShape,
 }
 impl ShipBundle {
-    pub fn new(ship: Ship, sprite_sheets: &SpriteSheets) -> Self {
-        let sprite_bundle = SpriteBundle {
-            texture: sprite_sheets.ship.choose(&ship),
+    pub fn new(ship: Ship, shield: ShieldEnergy, sprite_sheets: &SpriteSheets) -> Self {
+        let sprite_bundle = SpriteSheetBundle {
+            texture_atlas: sprite_sheets.ship.atlas.clone(),
+            sprite: TextureAtlasSprite::new(sprite_sheets.ship.choose(&ship)),
             ..Default::default()
         };
         ShipBundle {
@@ -158,6 +329,9 @@ impl ShipBundle {
             moving: Moving::default(),
             wrapping: Wrapping,
             ship,
+            health: Health::new(SHIP_MAX_HEALTH),
+            shield,
+            mass: Mass(SHIP_MASS),
             collision_shape: CollisionShape::new(
                 Shape::Circle {
                     center: Vec2::ZERO,
@@ -171,15 +345,16 @@ impl ShipBundle {
 
 #[derive(Bundle)]
 pub struct ShipShieldBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     ship_shield: ShipShield,
 }
 impl ShipShieldBundle {
     pub fn new(ship_images: &ShipImages) -> Self {
         ShipShieldBundle {
-            sprite_bundle: SpriteBundle {
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: ship_images.atlas.clone(),
+                sprite: TextureAtlasSprite::new(ship_images.shield),
                 visibility: Visibility::Hidden,
-                texture: ship_images.shield.clone(),
                 ..Default::default()
             },
             ship_shield: ShipShield,
@@ -204,11 +379,16 @@ impl ShipProjectileBundle {
         transform: Transform,
         life: f32,
         radius: f32,
+        color: Color,
     ) -> Self {
         ShipProjectileBundle {
             sprite_bundle: SpriteBundle {
                 texture,
                 transform,
+                sprite: Sprite {
+                    color,
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             moving: Moving {
@@ -243,12 +423,14 @@ impl ShipBeamBundle {
         base: Vec2,
         length: f32,
         max_length: f32,
+        color: Color,
     ) -> Self {
         ShipBeamBundle {
             sprite_bundle: SpriteBundle {
                 texture,
                 transform,
                 sprite: Sprite {
+                    color,
                     anchor: bevy::sprite::Anchor::BottomCenter,
                     ..Default::default()
                 },
@@ -260,6 +442,8 @@ impl ShipBeamBundle {
                 sustained: 0.0,
                 cooldown: 0.0,
                 active: true,
+                width: 1.0,
+                damage: BEAM_DAMAGE_PER_HIT,
             },
             ship_projectile,
             collision_shape: CollisionShape::new(
@@ -274,11 +458,76 @@ impl ShipBeamBundle {
     }
 }
 
+#[derive(Bundle)]
+pub struct RailgunShotBundle {
+    spatial_bundle: SpatialBundle,
+    ship_projectile: ShipProjectile,
+    railgun_shot: RailgunShot,
+    expiring: Expiring,
+    collision_shape: CollisionShape,
+}
+impl RailgunShotBundle {
+    pub fn new(damage: i32, transform: Transform) -> Self {
+        RailgunShotBundle {
+            spatial_bundle: SpatialBundle {
+                transform,
+                ..Default::default()
+            },
+            ship_projectile: ShipProjectile::Railgun { damage },
+            railgun_shot: RailgunShot::default(),
+            expiring: Expiring {
+                life: RAILGUN_TRACER_LIFE,
+            },
+            collision_shape: CollisionShape::new(
+                Shape::Line {
+                    base: Vec2::ZERO,
+                    delta: Vec2::Y * RAILGUN_LENGTH,
+                    width: RAILGUN_WIDTH,
+                },
+                transform,
+            ),
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct RailgunTracerBundle {
+    sprite_bundle: SpriteBundle,
+    fading: Fading,
+}
+impl RailgunTracerBundle {
+    pub fn new(texture: Handle<Image>, color: Color) -> Self {
+        RailgunTracerBundle {
+            sprite_bundle: SpriteBundle {
+                texture,
+                transform: Transform::from_xyz(0.0, 0.0, -0.01).with_scale(Vec3::new(
+                    0.5,
+                    RAILGUN_LENGTH / 128.0,
+                    1.0,
+                )),
+                sprite: Sprite {
+                    color,
+                    anchor: bevy::sprite::Anchor::BottomCenter,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            fading: Fading {
+                from: 1.0,
+                to: 0.0,
+                duration: RAILGUN_TRACER_LIFE,
+                elapsed: 0.0,
+            },
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct ExplosionBundle {
     sprite_bundle: SpriteBundle,
     animated: Animated,
     expiring: Expiring,
+    explosion: Explosion,
 }
 impl ExplosionBundle {
     pub fn new(explosion_images: &ExplosionImages, position: Vec2) -> ExplosionBundle {
@@ -297,6 +546,7 @@ impl ExplosionBundle {
                 looping: false,
             },
             expiring: Expiring { life: 2.0 },
+            explosion: Explosion,
         }
     }
 }
@@ -307,6 +557,7 @@ pub struct GameNotificationBundle {
     scaling: Scaling,
     fading: Fading,
     expiring: Expiring,
+    game_notification: GameNotification,
 }
 impl GameNotificationBundle {
     pub fn new(
@@ -342,31 +593,69 @@ impl GameNotificationBundle {
                 elapsed: 0.0,
             },
             expiring: Expiring { life: duration },
+            game_notification: GameNotification,
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct ScorePopupBundle {
+    text_2d_bundle: Text2dBundle,
+    expiring: Expiring,
+    game_notification: GameNotification,
+    score_popup: ScorePopup,
+}
+impl ScorePopupBundle {
+    pub fn new(amount: u32, font: Handle<Font>, position: Vec2, size: f32) -> ScorePopupBundle {
+        ScorePopupBundle {
+            text_2d_bundle: Text2dBundle {
+                text: Text::from_section(
+                    format!("{}", amount),
+                    TextStyle {
+                        font,
+                        font_size: size,
+                        color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(0.1)),
+                ..Default::default()
+            },
+            expiring: Expiring {
+                life: SCORE_POPUP_HOLD + SCORE_POPUP_FLIGHT,
+            },
+            game_notification: GameNotification,
+            score_popup: ScorePopup {
+                amount,
+                from: position,
+                elapsed: 0.0,
+            },
         }
     }
 }
 
 #[derive(Bundle)]
 pub struct WaveParticleBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     expiring: Expiring,
     scaling: Scaling,
     fading: Fading,
+    particle: Particle,
 }
 impl WaveParticleBundle {
     pub fn new(position: Vec2, particle_images: &ParticleImages) -> WaveParticleBundle {
         WaveParticleBundle {
-            sprite_bundle: SpriteBundle {
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: particle_images.atlas.clone(),
                 transform: Transform {
                     translation: position.extend(0.),
                     scale: Vec3::splat(0.0),
                     ..Default::default()
                 },
-                sprite: Sprite {
+                sprite: TextureAtlasSprite {
+                    index: particle_images.wave,
                     color: Color::rgba(1.0, 1.0, 1.0, 0.1),
                     ..Default::default()
                 },
-                texture: particle_images.wave.clone(),
                 ..Default::default()
             },
             expiring: Expiring { life: 1.0 },
@@ -382,31 +671,34 @@ impl WaveParticleBundle {
                 duration: 1.0,
                 elapsed: 0.0,
             },
+            particle: Particle,
         }
     }
 }
 
 #[derive(Bundle)]
 pub struct RingParticleBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     expiring: Expiring,
     scaling: Scaling,
     fading: Fading,
+    particle: Particle,
 }
 impl RingParticleBundle {
     pub fn new(position: Vec2, particle_images: &ParticleImages) -> RingParticleBundle {
         RingParticleBundle {
-            sprite_bundle: SpriteBundle {
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: particle_images.atlas.clone(),
                 transform: Transform {
                     translation: position.extend(0.),
                     scale: Vec3::splat(0.0),
                     ..Default::default()
                 },
-                sprite: Sprite {
+                sprite: TextureAtlasSprite {
+                    index: particle_images.ring,
                     color: Color::rgba(1.0, 1.0, 1.0, 0.1),
                     ..Default::default()
                 },
-                texture: particle_images.ring.clone(),
                 ..Default::default()
             },
             expiring: Expiring { life: 1.0 },
@@ -422,14 +714,16 @@ impl RingParticleBundle {
                 duration: 1.0,
                 elapsed: 0.0,
             },
+            particle: Particle,
         }
     }
 }
 #[derive(Bundle)]
 pub struct CoronaParticleBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     expiring: Expiring,
     fading: Fading,
+    particle: Particle,
 }
 impl CoronaParticleBundle {
     pub fn new(
@@ -438,17 +732,18 @@ impl CoronaParticleBundle {
         particle_images: &ParticleImages,
     ) -> CoronaParticleBundle {
         CoronaParticleBundle {
-            sprite_bundle: SpriteBundle {
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: particle_images.atlas.clone(),
                 transform: Transform {
                     translation: position.extend(0.),
                     scale: Vec3::splat(size),
                     ..Default::default()
                 },
-                sprite: Sprite {
+                sprite: TextureAtlasSprite {
+                    index: particle_images.corona,
                     color: Color::rgba(1.0, 1.0, 1.0, 0.1),
                     ..Default::default()
                 },
-                texture: particle_images.corona.clone(),
                 ..Default::default()
             },
             expiring: Expiring { life: 1.0 },
@@ -458,17 +753,19 @@ impl CoronaParticleBundle {
                 duration: 1.0,
                 elapsed: 0.0,
             },
+            particle: Particle,
         }
     }
 }
 #[derive(Bundle)]
 pub struct SparkParticleBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     moving: Moving,
     expiring: Expiring,
     scaling: Scaling,
     spinning: Spinning,
     fading: Fading,
+    particle: Particle,
 }
 impl SparkParticleBundle {
     pub fn new(
@@ -478,17 +775,18 @@ impl SparkParticleBundle {
         particle_images: &ParticleImages,
     ) -> SparkParticleBundle {
         SparkParticleBundle {
-            sprite_bundle: SpriteBundle {
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: particle_images.atlas.clone(),
                 transform: Transform {
                     translation: position.extend(0.),
                     scale: Vec3::splat(0.0),
                     ..Default::default()
                 },
-                sprite: Sprite {
+                sprite: TextureAtlasSprite {
+                    index: particle_images.spark,
                     color: Color::rgba(1.0, 1.0, 1.0, 0.1),
                     ..Default::default()
                 },
-                texture: particle_images.spark.clone(),
                 ..Default::default()
             },
             moving: Moving {
@@ -509,6 +807,7 @@ impl SparkParticleBundle {
                 duration: 1.0,
                 elapsed: 0.0,
             },
+            particle: Particle,
         }
     }
 }
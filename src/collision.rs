@@ -0,0 +1,88 @@
+use crate::components::{Asteroid, CollisionShape, Enemy, Ship, ShipProjectile};
+use crate::constants::COLLISION_GRID_CELL;
+use bevy::{ecs::query::Has, prelude::*, utils::HashMap};
+
+/// Two collidable entities touching.
+/// `a` is the ship or one of its projectiles, `b` the other entity, and `normal` points from
+/// `b` towards `a`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    pub point: Vec2,
+    pub normal: Vec2,
+}
+
+/// Broadphase buckets of collider indices by the grid cells their bounds overlap
+#[derive(Default)]
+struct CollisionGrid {
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl CollisionGrid {
+    fn cells(bounds: Rect) -> impl Iterator<Item = IVec2> {
+        let min = (bounds.min / COLLISION_GRID_CELL).floor().as_ivec2();
+        let max = (bounds.max / COLLISION_GRID_CELL).floor().as_ivec2();
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+    }
+    fn insert(&mut self, index: usize, bounds: Rect) {
+        for cell in CollisionGrid::cells(bounds) {
+            self.cells.entry(cell).or_default().push(index);
+        }
+    }
+    /// Indices sharing a cell with `bounds`, each once and in insertion order
+    fn nearby(&self, bounds: Rect) -> Vec<usize> {
+        let mut indices: Vec<usize> = CollisionGrid::cells(bounds)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// Detects collisions of the ship and its projectiles once per frame so response systems
+/// only need to react to events. Projectiles only collide with what they can shoot down.
+pub fn collision_detection_system(
+    ships_query: Query<(Entity, &CollisionShape), With<Ship>>,
+    projectiles_query: Query<(Entity, &CollisionShape), With<ShipProjectile>>,
+    colliders_query: Query<
+        (Entity, &CollisionShape, Has<Asteroid>, Has<Enemy>),
+        (Without<Ship>, Without<ShipProjectile>),
+    >,
+    mut collision_events: EventWriter<CollisionEvent>,
+) {
+    let colliders: Vec<_> = colliders_query.iter().collect();
+    let mut grid = CollisionGrid::default();
+    for (index, (_, shape, _, _)) in colliders.iter().enumerate() {
+        grid.insert(index, shape.global_shape().bounds());
+    }
+
+    let ships = ships_query
+        .iter()
+        .map(|(entity, shape)| (entity, shape, false));
+    let projectiles = projectiles_query
+        .iter()
+        .map(|(entity, shape)| (entity, shape, true));
+    for (entity, shape, projectile) in ships.chain(projectiles) {
+        for index in grid.nearby(shape.global_shape().bounds()) {
+            let (other, other_shape, asteroid, enemy) = colliders[index];
+            if projectile && !asteroid && !enemy {
+                continue;
+            }
+            if shape.intersects(other_shape) {
+                let normal = (shape.transform.translation - other_shape.transform.translation)
+                    .truncate()
+                    .normalize_or_zero();
+                collision_events.send(CollisionEvent {
+                    a: entity,
+                    b: other,
+                    point: shape.collision_point(other_shape),
+                    normal,
+                });
+            }
+        }
+    }
+}
@@ -1,9 +1,18 @@
 use bevy::prelude::*;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    thread_rng,
+};
+use serde::Deserialize;
 
-use crate::constants::SHIP_RESPAWN_DELAY;
+use crate::constants::{
+    SHIELD_MAX_ENERGY, SHIELD_REGEN_DELAY, SHIELD_REGEN_RATE, SHIP_RESPAWN_DELAY,
+    WEAPON_COOLING_RATE, WEAPON_COUNT,
+};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Default, Debug, Deserialize, Reflect)]
 pub enum AsteroidSize {
+    #[default]
     Tiny = 0,
     Small,
     Medium,
@@ -11,6 +20,15 @@ pub enum AsteroidSize {
 }
 
 impl AsteroidSize {
+    /// Multiplier for collision damage dealt to the ship
+    pub fn collision_damage_factor(&self) -> f32 {
+        match self {
+            AsteroidSize::Tiny => 0.5,
+            AsteroidSize::Small => 1.0,
+            AsteroidSize::Medium => 1.5,
+            AsteroidSize::Large => 2.0,
+        }
+    }
     pub fn smaller(&self) -> Option<AsteroidSize> {
         match self {
             AsteroidSize::Tiny => None,
@@ -19,6 +37,14 @@ impl AsteroidSize {
             AsteroidSize::Large => Some(AsteroidSize::Medium),
         }
     }
+    pub fn mass(&self) -> f32 {
+        match self {
+            AsteroidSize::Tiny => 0.1,
+            AsteroidSize::Small => 0.5,
+            AsteroidSize::Medium => 2.0,
+            AsteroidSize::Large => 4.0,
+        }
+    }
     pub fn radius(&self) -> f32 {
         match self {
             AsteroidSize::Tiny => 4.0,
@@ -35,12 +61,75 @@ impl AsteroidSize {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Reflect)]
 pub enum ShipWeapon {
     Rapid,
     Spread,
     Beam,
     Plasma,
+    Charge,
+    Railgun,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Reflect)]
+pub enum WeaponUpgrade {
+    RapidPiercing,
+    RapidDualStream,
+    SpreadFocused,
+    SpreadScatter,
+    BeamWide,
+    BeamIntense,
+    PlasmaRepeater,
+    PlasmaHeavy,
+}
+
+impl WeaponUpgrade {
+    pub const ALL: [WeaponUpgrade; 8] = [
+        WeaponUpgrade::RapidPiercing,
+        WeaponUpgrade::RapidDualStream,
+        WeaponUpgrade::SpreadFocused,
+        WeaponUpgrade::SpreadScatter,
+        WeaponUpgrade::BeamWide,
+        WeaponUpgrade::BeamIntense,
+        WeaponUpgrade::PlasmaRepeater,
+        WeaponUpgrade::PlasmaHeavy,
+    ];
+    pub fn weapon(&self) -> ShipWeapon {
+        use WeaponUpgrade::*;
+        match self {
+            RapidPiercing | RapidDualStream => ShipWeapon::Rapid,
+            SpreadFocused | SpreadScatter => ShipWeapon::Spread,
+            BeamWide | BeamIntense => ShipWeapon::Beam,
+            PlasmaRepeater | PlasmaHeavy => ShipWeapon::Plasma,
+        }
+    }
+    /// The other branch of the same weapon, locked once this one is chosen
+    pub fn sibling(&self) -> WeaponUpgrade {
+        use WeaponUpgrade::*;
+        match self {
+            RapidPiercing => RapidDualStream,
+            RapidDualStream => RapidPiercing,
+            SpreadFocused => SpreadScatter,
+            SpreadScatter => SpreadFocused,
+            BeamWide => BeamIntense,
+            BeamIntense => BeamWide,
+            PlasmaRepeater => PlasmaHeavy,
+            PlasmaHeavy => PlasmaRepeater,
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        use WeaponUpgrade::*;
+        match self {
+            RapidPiercing => "Piercing rounds",
+            RapidDualStream => "Dual stream",
+            SpreadFocused => "Focused spread",
+            SpreadScatter => "Scatter shot",
+            BeamWide => "Wide beam",
+            BeamIntense => "Intense beam",
+            PlasmaRepeater => "Plasma repeater",
+            PlasmaHeavy => "Heavy plasma",
+        }
+    }
 }
 
 #[derive(Component, Clone, Copy)]
@@ -49,21 +138,57 @@ pub enum ShipProjectile {
     Spread,
     Beam { power: f32 },
     Plasma { power: f32 },
+    Railgun { damage: i32 },
 }
 
-#[derive(Component)]
+/// Weapon whose primary fire spawned a ship projectile, the shots counted for accuracy.
+/// Drone shots and secondary fire go without.
+#[derive(Component, Clone, Copy)]
+pub struct FiredFrom(pub ShipWeapon);
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Beam {
     pub length: f32,
     pub max_length: f32,
     pub sustained: f32,
     pub cooldown: f32,
     pub active: bool,
+    pub width: f32,
+    pub damage: i32,
 }
 
 #[derive(Component)]
 pub struct BeamTip;
 
-#[derive(Clone, Copy)]
+/// Entities already damaged by a railgun shot, so each is only hit once
+#[derive(Component, Default)]
+pub struct RailgunShot {
+    pub hits: Vec<Entity>,
+}
+
+/// An effect of a powerup that wears off, such as bullet time. Lives on its own entity
+/// so that any number of effects can be active at once.
+#[derive(Component)]
+pub struct TimedEffect {
+    pub powerup: Powerup,
+    pub duration: f32,
+    pub remaining: f32,
+}
+
+impl TimedEffect {
+    pub fn fraction(&self) -> f32 {
+        self.remaining / self.duration
+    }
+}
+
+#[derive(Component)]
+pub struct Piercing {
+    pub remaining: u32,
+    pub last_hit: Option<Entity>,
+}
+
+#[derive(Clone, Copy, Reflect)]
 pub enum ShipTurn {
     Neutral,
     Left,
@@ -81,37 +206,162 @@ pub struct Expiring {
     pub life: f32,
 }
 
+/// Purely cosmetic effect, the first thing to go when there are too many entities
+#[derive(Component)]
+pub struct Particle;
+
+/// Explosion animation, dimmed in the reduced flashing mode
+#[derive(Component)]
+pub struct Explosion;
+
+/// Text popping up in the play area
+#[derive(Component)]
+pub struct GameNotification;
+
+/// Score notification on its way to the HUD score, which counts it once it arrives
+#[derive(Component)]
+pub struct ScorePopup {
+    pub amount: u32,
+    pub from: Vec2,
+    pub elapsed: f32,
+}
+
 impl Default for ShipWeapon {
     fn default() -> Self {
         Self::Rapid
     }
 }
 
-#[derive(Component)]
+impl ShipWeapon {
+    pub const ALL: [ShipWeapon; 6] = [
+        ShipWeapon::Rapid,
+        ShipWeapon::Spread,
+        ShipWeapon::Beam,
+        ShipWeapon::Plasma,
+        ShipWeapon::Charge,
+        ShipWeapon::Railgun,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShipWeapon::Rapid => "Rapid",
+            ShipWeapon::Spread => "Spread",
+            ShipWeapon::Beam => "Beam",
+            ShipWeapon::Plasma => "Plasma",
+            ShipWeapon::Charge => "Charge",
+            ShipWeapon::Railgun => "Railgun",
+        }
+    }
+    /// Heat added per shot, or per second of firing for the Beam
+    pub fn heat_per_shot(&self) -> f32 {
+        match self {
+            ShipWeapon::Rapid => 0.04,
+            ShipWeapon::Spread => 0.08,
+            ShipWeapon::Beam => 0.5,
+            ShipWeapon::Plasma => 0.12,
+            ShipWeapon::Charge => 0.2,
+            ShipWeapon::Railgun => 0.3,
+        }
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Asteroid {
     pub size: AsteroidSize,
-    pub integrity: i32,
     pub variant: usize,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
 pub enum Powerup {
     Laser = 0,
     Spread,
     Beam,
     Plasma,
+    Charge,
+    Railgun,
+    Drone,
+    PlasmaAmmo,
     ExtraLife,
     LoseLife,
     Shield,
+    BulletTime,
+    DoubleScore,
+    Overdrive,
+    Repair,
 }
 
-#[derive(Component, Default)]
+impl Powerup {
+    pub const ALL: [Powerup; 15] = [
+        Powerup::Laser,
+        Powerup::Spread,
+        Powerup::Beam,
+        Powerup::Plasma,
+        Powerup::Charge,
+        Powerup::Railgun,
+        Powerup::Drone,
+        Powerup::PlasmaAmmo,
+        Powerup::ExtraLife,
+        Powerup::LoseLife,
+        Powerup::Shield,
+        Powerup::BulletTime,
+        Powerup::DoubleScore,
+        Powerup::Overdrive,
+        Powerup::Repair,
+    ];
+    /// Relative chance of dropping, before adjusting for the level and the ship
+    pub fn drop_weight(&self) -> f32 {
+        match self {
+            Powerup::ExtraLife | Powerup::LoseLife => 0.5,
+            Powerup::Repair => 0.25,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum DropEntry {
+    Powerup(Powerup),
+    /// Whatever `random_powerup` picks for the ship's situation
+    AnyPowerup,
+}
+
+/// What an entity leaves behind when it dies
+#[derive(Component, Clone, Debug)]
+pub struct DropTable {
+    /// Powerups to pick one from, with their relative weights
+    pub entries: Vec<(DropEntry, f32)>,
+    /// Weight of dropping no powerup at all, relative to the entries
+    pub nothing: f32,
+    /// Minerals dropped regardless of the powerup
+    pub minerals: u32,
+}
+
+impl DropTable {
+    pub fn pick(&self) -> Option<DropEntry> {
+        let weights = self
+            .entries
+            .iter()
+            .map(|(_, weight)| *weight)
+            .chain(std::iter::once(self.nothing));
+        let index = WeightedIndex::new(weights).ok()?.sample(&mut thread_rng());
+        self.entries.get(index).map(|(entry, _)| *entry)
+    }
+}
+
+#[derive(Component)]
+pub struct Mineral {
+    pub amount: u32,
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Moving {
     pub velocity: Vec2,
     pub acceleration: Vec2,
 }
 
-#[derive(Component)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Spinning {
     pub speed: f32,
 }
@@ -132,30 +382,246 @@ pub struct Fading {
     pub elapsed: f32,
 }
 
+/// Mass used for momentum exchange in collision responses
+#[derive(Component, Clone, Copy, Default, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Mass(pub f32);
+
+/// Hull health, depleted by collisions instead of dying on the first hit
+#[derive(Component, Clone, Copy, Default, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+    /// Applies damage, returns whether any health remains
+    pub fn damage(&mut self, amount: f32) -> bool {
+        self.current = (self.current - amount).max(0.0);
+        self.current > 0.0
+    }
+    pub fn restore(&mut self) {
+        self.current = self.max;
+    }
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
+/// Shield energy drained by hits and regenerated after a delay.
+/// As a component it takes the damage dealt to its entity until it runs out.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct ShieldEnergy {
+    pub current: f32,
+    pub max: f32,
+    pub regen_rate: f32,
+    pub regen_delay: f32,
+}
+
+impl Default for ShieldEnergy {
+    fn default() -> Self {
+        Self {
+            current: 0.0,
+            max: 0.0,
+            regen_rate: SHIELD_REGEN_RATE,
+            regen_delay: 0.0,
+        }
+    }
+}
+
+impl ShieldEnergy {
+    pub fn full(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            ..Default::default()
+        }
+    }
+    pub fn is_up(&self) -> bool {
+        self.current > 0.0
+    }
+    pub fn regenerate(&mut self, time_delta: f32) {
+        if self.regen_delay > 0.0 {
+            self.regen_delay -= time_delta;
+        } else {
+            self.current = (self.current + self.regen_rate * time_delta).min(self.max);
+        }
+    }
+    pub fn increase_max(&mut self, amount: f32) {
+        self.max = (self.max + amount).min(SHIELD_MAX_ENERGY);
+        self.current = (self.current + amount).min(self.max);
+    }
+    /// Drains energy for `amount` of damage, returns the part of it the shield couldn't take
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        if self.current <= 0.0 {
+            return amount;
+        }
+        let absorbed = amount.min(self.current);
+        self.current -= absorbed;
+        self.regen_delay = SHIELD_REGEN_DELAY;
+        amount - absorbed
+    }
+    /// Tops the shield up to at least `amount`, raising the maximum if needed
+    pub fn ensure(&mut self, amount: f32) {
+        self.max = self.max.max(amount);
+        self.current = self.current.max(amount);
+        self.regen_delay = 0.0;
+    }
+    pub fn deplete(&mut self) {
+        self.current = 0.0;
+        self.regen_delay = SHIELD_REGEN_DELAY;
+    }
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.current / self.max
+        } else {
+            0.0
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Wrapping;
 
 #[derive(Component)]
 pub struct LevelEntity;
 
-#[derive(Component, Default)]
+/// Anything shown only while loading
+#[derive(Component)]
+pub struct LoadingScreen;
+
+/// Fill of the loading progress bar
+#[derive(Component)]
+pub struct LoadingBar;
+
+/// Anything hostile that ship projectiles can hit and the ship can ram
+#[derive(Component)]
+pub struct Enemy;
+
+/// Pulls every moving thing along `acceleration`, like a planet off the screen edge
+#[derive(Component)]
+pub struct GravitySource {
+    pub acceleration: Vec2,
+}
+
+/// Chunk flung out of an explosion, chipping at the ship's shield
+#[derive(Component)]
+pub struct Debris;
+
+/// Explosive going off on contact, hurting everything within `blast_radius`
+#[derive(Component)]
+pub struct Mine {
+    pub damage: f32,
+    pub blast_radius: f32,
+}
+
+/// Damage an enemy and the ship deal each other when they collide,
+/// `UFO_COLLISION_DAMAGE` when left out
+#[derive(Component)]
+pub struct ContactDamage(pub f32);
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Ship {
     pub throttle: bool,
     pub turn: ShipTurn,
+    /// Fraction of the full turn rate to turn at, less than 1 with analog turning
+    pub turn_amount: f32,
     pub fire: bool,
+    pub secondary_fire: bool,
+    pub hyperspace: bool,
+    pub hyperspace_jumps: u8,
     pub weapon: ShipWeapon,
     pub weapon_rapid_level: u8,
     pub weapon_spread_level: u8,
     pub weapon_beam_level: u8,
     pub weapon_plasma_level: u8,
+    pub weapon_charge_level: u8,
+    pub weapon_railgun_level: u8,
+    pub plasma_ammo: u32,
     pub weapon_cooldown: f32,
-    pub shield_level: u8,
+    /// What `weapon_cooldown` last started counting down from
+    pub weapon_cooldown_duration: f32,
+    pub weapon_heat: [f32; WEAPON_COUNT],
+    pub weapon_overheated: [bool; WEAPON_COUNT],
+    pub charge: f32,
+    pub secondary_cooldown: f32,
+    pub drones: u8,
     pub lives: u8,
     pub invulnerability: f32,
     pub respawn_delay: f32,
+    pub upgrades: Vec<WeaponUpgrade>,
 }
 
 impl Ship {
+    /// How much of the weapon cooldown is still left, from 1 down to 0
+    pub fn cooldown_fraction(&self) -> f32 {
+        if self.weapon_cooldown_duration > 0.0 {
+            (self.weapon_cooldown / self.weapon_cooldown_duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+    pub fn has_upgrade(&self, upgrade: WeaponUpgrade) -> bool {
+        self.upgrades.contains(&upgrade)
+    }
+    pub fn weapon_level(&self, weapon: ShipWeapon) -> u8 {
+        match weapon {
+            ShipWeapon::Rapid => self.weapon_rapid_level,
+            ShipWeapon::Spread => self.weapon_spread_level,
+            ShipWeapon::Beam => self.weapon_beam_level,
+            ShipWeapon::Plasma => self.weapon_plasma_level,
+            ShipWeapon::Charge => self.weapon_charge_level,
+            ShipWeapon::Railgun => self.weapon_railgun_level,
+        }
+    }
+    pub fn has_ammo(&self, weapon: ShipWeapon) -> bool {
+        match weapon {
+            ShipWeapon::Plasma => self.plasma_ammo > 0,
+            _ => true,
+        }
+    }
+    pub fn heat(&self, weapon: ShipWeapon) -> f32 {
+        self.weapon_heat[weapon as usize]
+    }
+    pub fn is_overheated(&self, weapon: ShipWeapon) -> bool {
+        self.weapon_overheated[weapon as usize]
+    }
+    /// Overheating locks the weapon until it has fully cooled down
+    pub fn add_heat(&mut self, weapon: ShipWeapon, amount: f32) {
+        let heat = &mut self.weapon_heat[weapon as usize];
+        *heat = (*heat + amount).min(1.0);
+        if *heat >= 1.0 {
+            self.weapon_overheated[weapon as usize] = true;
+        }
+    }
+    pub fn cool_weapons(&mut self, time_delta: f32) {
+        for (heat, overheated) in self
+            .weapon_heat
+            .iter_mut()
+            .zip(self.weapon_overheated.iter_mut())
+        {
+            *heat = (*heat - WEAPON_COOLING_RATE * time_delta).max(0.0);
+            if *heat <= 0.0 {
+                *overheated = false;
+            }
+        }
+    }
+    /// Clears overheating and other ailments
+    pub fn cleanse(&mut self) {
+        self.weapon_heat = Default::default();
+        self.weapon_overheated = Default::default();
+    }
+    pub fn can_upgrade(&self, upgrade: WeaponUpgrade) -> bool {
+        self.weapon_level(upgrade.weapon()) > 0
+            && !self.has_upgrade(upgrade)
+            && !self.has_upgrade(upgrade.sibling())
+    }
     pub fn die(&mut self) {
         self.lives = self.lives.saturating_sub(1);
         self.respawn_delay = SHIP_RESPAWN_DELAY;
@@ -164,7 +630,11 @@ impl Ship {
         self.weapon_spread_level = self.weapon_spread_level.saturating_sub(1);
         self.weapon_beam_level = self.weapon_beam_level.saturating_sub(1);
         self.weapon_plasma_level = self.weapon_plasma_level.saturating_sub(1);
-        self.shield_level = 0;
+        self.weapon_charge_level = self.weapon_charge_level.saturating_sub(1);
+        self.weapon_railgun_level = self.weapon_railgun_level.saturating_sub(1);
+        self.charge = 0.0;
+        self.cleanse();
+        self.drones = 0;
     }
     pub fn next_weapon(&mut self) {
         use ShipWeapon::*;
@@ -173,18 +643,22 @@ impl Ship {
             (Spread, self.weapon_spread_level),
             (Beam, self.weapon_beam_level),
             (Plasma, self.weapon_plasma_level),
+            (Charge, self.weapon_charge_level),
+            (Railgun, self.weapon_railgun_level),
         ];
         self.weapon = levels
             .iter()
             .cycle()
             .skip_while(|(w, _)| *w != self.weapon)
             .skip(1)
-            .find_map(|(w, l)| (*l > 0).then_some(*w))
+            .find_map(|(w, l)| (*l > 0 && self.has_ammo(*w)).then_some(*w))
             .unwrap_or(Rapid);
     }
     pub fn prev_weapon(&mut self) {
         use ShipWeapon::*;
         let levels = [
+            (Railgun, self.weapon_railgun_level),
+            (Charge, self.weapon_charge_level),
             (Plasma, self.weapon_plasma_level),
             (Beam, self.weapon_beam_level),
             (Spread, self.weapon_spread_level),
@@ -195,7 +669,7 @@ impl Ship {
             .cycle()
             .skip_while(|(w, _)| *w != self.weapon)
             .skip(1)
-            .find_map(|(w, l)| (*l > 0).then_some(*w))
+            .find_map(|(w, l)| (*l > 0 && self.has_ammo(*w)).then_some(*w))
             .unwrap_or(Rapid);
     }
 }
@@ -240,7 +714,7 @@ impl Shape {
                 let a = *center - *base;
                 let b = *center - *base - *delta;
                 if norm.perp_dot(a) * norm.perp_dot(b) < 0.0 {
-                    a.project_onto(norm).length_squared() < (radius + width).powi(2)
+                    a.project_onto(norm).length_squared() <= (radius + width).powi(2)
                 } else if a.length_squared() < b.length_squared() {
                     a.length_squared() <= (radius + width).powi(2)
                 } else {
@@ -273,7 +747,7 @@ impl Shape {
                 // Use pythagorean theorem to find distance squared from halfway point to circle edge
                 let s2 = (radius + width).powi(2) - (*center - q).length_squared();
                 // Calculate relative distance from base to circle edge along l1q
-                let t = 1.0 - s2 / l1q.length_squared();
+                let t = 1.0 - s2.sqrt() / l1q.length();
                 // Distance to edge
                 (l1q * t).length()
             }
@@ -309,7 +783,7 @@ impl Shape {
                 // Use pythagorean theorem to find distance squared from halfway point to circle edge
                 let s2 = (radius + width).powi(2) - (*center - q).length_squared();
                 // Calculate relative distance from base to circle edge along l1q
-                let t = 1.0 - s2 / l1q.length_squared();
+                let t = 1.0 - s2.sqrt() / l1q.length();
                 // Distance to edge
                 *base + l1q * t
             }
@@ -317,6 +791,18 @@ impl Shape {
         }
     }
 
+    /// Axis-aligned box around the shape
+    pub fn bounds(&self) -> Rect {
+        use Shape::*;
+        match self {
+            Circle { center, radius } => Rect::from_center_half_size(*center, Vec2::splat(*radius)),
+            Line { base, delta, width } => {
+                let line = Rect::from_corners(*base, *base + *delta);
+                Rect::from_corners(line.min - *width, line.max + *width)
+            }
+        }
+    }
+
     pub fn transformed(&self, transform: &Transform) -> Shape {
         use Shape::*;
         match self {
@@ -327,7 +813,8 @@ impl Shape {
             Line { base, delta, width } => Line {
                 base: *base + transform.translation.truncate(),
                 delta: transform.rotation.mul_vec3(delta.extend(0.)).truncate(),
-                width: width * transform.scale.max_element(), // TODO
+                // Lines run along local Y, so X scales how wide they are
+                width: width * transform.scale.x,
             },
         }
     }
@@ -342,7 +829,7 @@ impl CollisionShape {
     pub fn new(shape: Shape, transform: Transform) -> Self {
         CollisionShape { shape, transform }
     }
-    fn global_shape(&self) -> Shape {
+    pub fn global_shape(&self) -> Shape {
         self.shape.transformed(&self.transform)
     }
     pub fn intersects(&self, other: &CollisionShape) -> bool {
@@ -355,3 +842,261 @@ impl CollisionShape {
         self.global_shape().collision_point(&other.global_shape())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn circle(x: f32, y: f32, radius: f32) -> Shape {
+        Shape::Circle {
+            center: Vec2::new(x, y),
+            radius,
+        }
+    }
+
+    /// Vertical line from the origin, 100 long
+    fn line(width: f32) -> Shape {
+        Shape::Line {
+            base: Vec2::ZERO,
+            delta: Vec2::new(0.0, 100.0),
+            width,
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn circles_intersect_when_overlapping() {
+        assert!(circle(0.0, 0.0, 1.0).intersects(&circle(2.0, 0.0, 2.0)));
+        assert!(!circle(0.0, 0.0, 1.0).intersects(&circle(4.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn touching_circles_intersect() {
+        assert!(circle(0.0, 0.0, 1.0).intersects(&circle(3.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn circle_distance_is_gap_between_edges() {
+        assert_close(circle(0.0, 0.0, 1.0).distance(&circle(5.0, 0.0, 2.0)), 2.0);
+        assert_close(circle(0.0, 0.0, 2.0).distance(&circle(3.0, 0.0, 2.0)), -1.0);
+    }
+
+    #[test]
+    fn circle_collision_point_is_on_larger_circle() {
+        let big = circle(0.0, 0.0, 3.0);
+        let small = circle(4.0, 0.0, 1.0);
+        let expected = Vec2::new(3.0, 0.0);
+        assert!(big.collision_point(&small).abs_diff_eq(expected, 1e-4));
+        assert!(small.collision_point(&big).abs_diff_eq(expected, 1e-4));
+    }
+
+    #[test]
+    fn circle_beside_line() {
+        assert!(circle(5.0, 50.0, 4.0).intersects(&line(2.0)));
+        assert!(!circle(8.0, 50.0, 4.0).intersects(&line(2.0)));
+        assert!(!circle(-8.0, 50.0, 4.0).intersects(&line(2.0)));
+    }
+
+    #[test]
+    fn circle_past_line_ends() {
+        assert!(circle(0.0, 105.0, 4.0).intersects(&line(2.0)));
+        assert!(!circle(0.0, 107.0, 4.0).intersects(&line(2.0)));
+        assert!(circle(0.0, -5.0, 4.0).intersects(&line(2.0)));
+        assert!(!circle(0.0, -7.0, 4.0).intersects(&line(2.0)));
+        // Off to the side past the end is measured from the end point
+        assert!(!circle(5.0, 105.0, 4.0).intersects(&line(2.0)));
+    }
+
+    #[test]
+    fn circle_tangent_to_line_intersects() {
+        // Along the side
+        assert!(circle(6.0, 50.0, 4.0).intersects(&line(2.0)));
+        // Past the end
+        assert!(circle(0.0, 106.0, 4.0).intersects(&line(2.0)));
+    }
+
+    #[test]
+    fn circle_line_intersection_is_symmetric() {
+        for circle in [
+            circle(5.0, 50.0, 4.0),
+            circle(8.0, 50.0, 4.0),
+            circle(0.0, 105.0, 4.0),
+        ] {
+            assert_eq!(circle.intersects(&line(2.0)), line(2.0).intersects(&circle));
+        }
+    }
+
+    #[test]
+    fn line_distance_reaches_circle_edge() {
+        assert_close(line(0.0).distance(&circle(0.0, 50.0, 10.0)), 40.0);
+        assert_close(circle(0.0, 50.0, 10.0).distance(&line(0.0)), 40.0);
+        // Off center, the edge is closer to the middle of the circle
+        assert_close(line(0.0).distance(&circle(6.0, 50.0, 10.0)), 42.0);
+        // The line's width reaches the circle sooner
+        assert_close(line(2.0).distance(&circle(0.0, 50.0, 8.0)), 40.0);
+    }
+
+    #[test]
+    fn line_collision_point_is_on_line_at_circle_edge() {
+        let point = line(0.0).collision_point(&circle(6.0, 50.0, 10.0));
+        assert!(point.abs_diff_eq(Vec2::new(0.0, 42.0), 1e-4));
+    }
+
+    #[test]
+    fn transformed_circle_moves_and_scales() {
+        let transform = Transform::from_xyz(10.0, 20.0, 0.0).with_scale(Vec3::splat(2.0));
+        let Shape::Circle { center, radius } = circle(1.0, 0.0, 5.0).transformed(&transform) else {
+            panic!("circle should stay a circle");
+        };
+        assert!(center.abs_diff_eq(Vec2::new(11.0, 20.0), 1e-4));
+        assert_close(radius, 10.0);
+    }
+
+    #[test]
+    fn transformed_line_moves_rotates_and_widens() {
+        let transform = Transform::from_xyz(5.0, 5.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+            .with_scale(Vec3::splat(3.0));
+        let Shape::Line { base, delta, width } = line(1.0).transformed(&transform) else {
+            panic!("line should stay a line");
+        };
+        assert!(base.abs_diff_eq(Vec2::new(5.0, 5.0), 1e-4));
+        assert!(delta.abs_diff_eq(Vec2::new(-100.0, 0.0), 1e-3));
+        assert_close(width, 3.0);
+    }
+
+    #[test]
+    fn transformed_line_widens_only_across() {
+        let wide_beam = Transform::from_scale(Vec3::new(2.0, 0.5, 1.0));
+        let Shape::Line { width, .. } = line(8.0).transformed(&wide_beam) else {
+            panic!("line should stay a line");
+        };
+        assert_close(width, 16.0);
+
+        let long_beam = Transform::from_scale(Vec3::new(1.0, 1.5, 1.0));
+        let Shape::Line { width, .. } = line(8.0).transformed(&long_beam) else {
+            panic!("line should stay a line");
+        };
+        assert_close(width, 8.0);
+    }
+
+    #[test]
+    fn shield_passes_on_damage_it_cannot_take() {
+        let mut shield = ShieldEnergy::full(3.0);
+        assert_close(shield.absorb(2.0), 0.0);
+        assert_close(shield.absorb(5.0), 4.0);
+        assert_close(shield.current, 0.0);
+        assert_close(shield.absorb(1.0), 1.0);
+    }
+
+    #[test]
+    fn shield_is_up_while_any_energy_is_left() {
+        let mut shield = ShieldEnergy::full(3.0);
+        shield.absorb(2.9);
+        assert!(shield.is_up());
+        shield.absorb(0.1);
+        assert!(!shield.is_up());
+    }
+
+    #[test]
+    fn bounds_cover_circles_and_wide_lines() {
+        let bounds = circle(10.0, 0.0, 5.0).bounds();
+        assert_eq!(bounds.min, Vec2::new(5.0, -5.0));
+        assert_eq!(bounds.max, Vec2::new(15.0, 5.0));
+        let bounds = line(2.0).bounds();
+        assert_eq!(bounds.min, Vec2::new(-2.0, -2.0));
+        assert_eq!(bounds.max, Vec2::new(2.0, 102.0));
+    }
+
+    #[test]
+    fn collision_shapes_collide_through_their_transforms() {
+        let unit = || circle(0.0, 0.0, 1.0);
+        let a = CollisionShape::new(unit(), Transform::from_xyz(0.0, 0.0, 0.0));
+        let near = CollisionShape::new(unit(), Transform::from_xyz(1.5, 0.0, 0.0));
+        let far = CollisionShape::new(unit(), Transform::from_xyz(3.0, 0.0, 0.0));
+        let scaled = CollisionShape::new(
+            unit(),
+            Transform::from_xyz(3.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)),
+        );
+        assert!(a.intersects(&near));
+        assert!(!a.intersects(&far));
+        assert!(a.intersects(&scaled));
+    }
+
+    // Whole-number coordinates keep the exact boundary cases from hiding behind rounding
+    fn arb_circle() -> impl Strategy<Value = Shape> {
+        (-500i32..500, -500i32..500, 1i32..100)
+            .prop_map(|(x, y, radius)| circle(x as f32, y as f32, radius as f32))
+    }
+
+    fn arb_line() -> impl Strategy<Value = Shape> {
+        (
+            -500i32..500,
+            -500i32..500,
+            -300i32..300,
+            -300i32..300,
+            0i32..10,
+        )
+            .prop_filter("line needs a direction", |(_, _, dx, dy, _)| {
+                (*dx, *dy) != (0, 0)
+            })
+            .prop_map(|(x, y, dx, dy, width)| Shape::Line {
+                base: Vec2::new(x as f32, y as f32),
+                delta: Vec2::new(dx as f32, dy as f32),
+                width: width as f32,
+            })
+    }
+
+    /// Two shapes of any kinds that can be tested against each other, which excludes two lines
+    fn arb_shape_pair() -> impl Strategy<Value = (Shape, Shape)> {
+        prop_oneof![
+            (arb_circle(), arb_circle()),
+            (arb_circle(), arb_line()),
+            (arb_line(), arb_circle()),
+        ]
+    }
+
+    fn bounds_overlap(a: &Shape, b: &Shape) -> bool {
+        let (a, b) = (a.bounds(), b.bounds());
+        a.min.cmple(b.max).all() && b.min.cmple(a.max).all()
+    }
+
+    proptest! {
+        #[test]
+        fn intersecting_shapes_have_overlapping_bounds((a, b) in arb_shape_pair()) {
+            if a.intersects(&b) {
+                prop_assert!(bounds_overlap(&a, &b));
+            }
+        }
+
+        #[test]
+        fn intersection_is_symmetric((a, b) in arb_shape_pair()) {
+            prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+        }
+
+        #[test]
+        fn collision_shapes_intersect_symmetrically(
+            (a, b) in arb_shape_pair(),
+            x in -100i32..100,
+            y in -100i32..100,
+        ) {
+            let a = CollisionShape::new(a, Transform::from_xyz(x as f32, y as f32, 0.0));
+            let b = CollisionShape::new(b, Transform::IDENTITY);
+            prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+        }
+
+        #[test]
+        fn touching_or_overlapping_circles_intersect(a in arb_circle(), b in arb_circle()) {
+            if a.distance(&b) <= 0.0 {
+                prop_assert!(a.intersects(&b));
+            } else {
+                prop_assert!(!a.intersects(&b));
+            }
+        }
+    }
+}
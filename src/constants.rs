@@ -3,10 +3,13 @@ pub const GAME_HEIGHT: u32 = 480;
 pub const LEVEL_START_DELAY: f32 = 3.0;
 pub const ASTEROID_SIZES: usize = 4;
 pub const ASTEROID_VARIANTS: usize = 12;
+/// Asteroids alive at once before splits start leaving fragments out
+pub const MAX_LIVE_ASTEROIDS: usize = 60;
 pub const BACKGROUND_IMAGES: usize = 11;
-pub const EXPLOSION_IMAGES: usize = 90;
 pub const SHIP_RESPAWN_DELAY: f32 = 3.0;
 pub const SHIP_INVULNERABILITY: f32 = 3.0;
+/// Full turning speed of the ship in radians per second, scaled by the turn sensitivity
+pub const SHIP_TURN_RATE: f32 = 3.0;
 pub const BEAM_EXTEND_TIME: f32 = 0.125;
 pub const BEAM_BASE_LENGTH: f32 = 24.0;
 pub const BEAM_LENGTH_PER_LEVEL: f32 = 16.0;
@@ -17,5 +20,211 @@ pub const BEAM_HIT_INTERVAL: f32 = 1.0 / 3.0;
 pub const BEAM_DAMAGE_PER_HIT: i32 = 1;
 pub const MIN_UFO_SCORE_INTERVAL: f32 = 400.0;
 pub const MAX_UFO_SCORE_INTERVAL: f32 = 800.0;
-pub const MAX_HIGH_SCORE_ENTRIES: usize = 10;
-pub const NUM_HIGH_SCORE_ENTRY_LETTERS: i32 = 3;
+pub const MAX_HIGH_SCORE_ENTRIES: usize = 100;
+pub const HIGH_SCORE_PAGE_ENTRIES: usize = 10;
+pub const MAX_NAME_LENGTH: usize = 8;
+pub const MINERAL_DROP_CHANCE: f32 = 0.3;
+pub const MINERAL_LIFE: f32 = 8.0;
+pub const MINERAL_MAGNET_RADIUS: f32 = 80.0;
+pub const MINERAL_MAGNET_SPEED: f32 = 200.0;
+pub const UPGRADE_COST: u32 = 20;
+pub const RAPID_PIERCE_COUNT: u32 = 2;
+pub const SECONDARY_COOLDOWN_RAPID: f32 = 1.5;
+pub const SECONDARY_COOLDOWN_SPREAD: f32 = 2.5;
+pub const SECONDARY_COOLDOWN_BEAM: f32 = 2.0;
+pub const SECONDARY_COOLDOWN_PLASMA: f32 = 3.0;
+pub const SECONDARY_COOLDOWN_RAILGUN: f32 = 4.0;
+pub const CHARGE_MAX_TIME: f32 = 1.5;
+pub const CHARGE_MIN_POWER: f32 = 4.0;
+pub const RAILGUN_LENGTH: f32 = 500.0;
+pub const RAILGUN_WIDTH: f32 = 4.0;
+pub const RAILGUN_TRACER_LIFE: f32 = 0.3;
+pub const MAX_DRONES: u8 = 2;
+pub const DRONE_ORBIT_RADIUS: f32 = 28.0;
+pub const DRONE_ORBIT_SPEED: f32 = 3.0;
+pub const DRONE_SCALE: f32 = 0.4;
+pub const DRONE_RADIUS: f32 = 5.0;
+pub const DRONE_RANGE: f32 = 250.0;
+pub const DRONE_SHOOT_INTERVAL: f32 = 0.8;
+pub const DRONE_SHOT_SPEED: f32 = 600.0;
+/// Shield energy shown as one pip on the HUD, about what a hit takes
+pub const SHIELD_PIP_ENERGY: f32 = 25.0;
+pub const SHIELD_REGEN_RATE: f32 = 2.5;
+pub const SHIELD_REGEN_DELAY: f32 = 4.0;
+pub const SHIELD_MAX_ENERGY: f32 = 5.0 * SHIELD_PIP_ENERGY;
+pub const SHIP_MAX_HEALTH: f32 = 100.0;
+pub const SHIP_HIT_INVULNERABILITY: f32 = 0.75;
+pub const COLLISION_DAMAGE_SCALE: f32 = 0.15;
+pub const COLLISION_MIN_DAMAGE: f32 = 5.0;
+pub const UFO_COLLISION_DAMAGE: f32 = 50.0;
+pub const UFO_LASER_DAMAGE: f32 = 25.0;
+pub const WEAPON_COUNT: usize = 6;
+pub const WEAPON_COOLING_RATE: f32 = 0.25;
+pub const PLASMA_AMMO_PICKUP: u32 = 10;
+pub const BULLET_TIME_DURATION: f32 = 8.0;
+pub const BULLET_TIME_SCALE: f32 = 0.5;
+pub const DOUBLE_SCORE_DURATION: f32 = 20.0;
+pub const TIMED_EFFECT_WARNING: f32 = 3.0;
+pub const OVERDRIVE_DURATION: f32 = 10.0;
+/// Extra drop weight of repairs when the hull is gone, scaled by the damage taken
+pub const REPAIR_DAMAGED_WEIGHT: f32 = 10.0;
+pub const PLASMA_MAX_AMMO: u32 = 40;
+pub const PLASMA_CLUSTER_AMMO_COST: u32 = 3;
+pub const HYPERSPACE_JUMPS_PER_LEVEL: u8 = 3;
+pub const HYPERSPACE_ATTEMPTS: usize = 10;
+pub const HYPERSPACE_MALFUNCTION_CHANCE: f32 = 0.1;
+pub const HYPERSPACE_MALFUNCTION_DAMAGE: f32 = 30.0;
+pub const SHIP_MASS: f32 = 1.0;
+pub const UFO_LASER_MASS: f32 = 0.05;
+pub const COLLISION_RESTITUTION: f32 = 0.8;
+/// Side of a collision broadphase grid cell, around the size of a large asteroid
+pub const COLLISION_GRID_CELL: f32 = 64.0;
+pub const UFO_HEALTH: f32 = 20.0;
+pub const UFO_RADIUS: f32 = 16.0;
+pub const STEALTH_UFO_MIN_SCORE: u32 = 20000;
+pub const STEALTH_UFO_CHANCE: f32 = 0.25;
+/// Seconds a stealth UFO stays visible, then cloaked, per cycle
+pub const CLOAK_VISIBLE_TIME: f32 = 2.0;
+pub const CLOAK_HIDDEN_TIME: f32 = 3.0;
+pub const CLOAK_FADE_TIME: f32 = 0.5;
+/// Seconds a shot gives away a cloaked UFO for
+pub const CLOAK_REVEAL_TIME: f32 = 0.6;
+pub const CLOAK_MIN_ALPHA: f32 = 0.05;
+pub const SMALL_UFO_MIN_SCORE: u32 = 10000;
+pub const UFO_MAX_SQUADRON: u32 = 4;
+pub const UFO_SQUADRON_SPACING: f32 = 40.0;
+pub const SMALL_UFO_MAX_CHANCE_SCORE: u32 = 50000;
+/// How far ahead UFOs look for shots coming their way, in seconds
+pub const UFO_EVASION_LOOKAHEAD: f32 = 0.6;
+pub const UFO_EVASION_COOLDOWN: f32 = 0.5;
+pub const UFO_DODGE_SPEED: f32 = 150.0;
+pub const UFO_DODGE_DURATION: f32 = 0.4;
+pub const FIGHTER_HEALTH: f32 = 8.0;
+pub const FIGHTER_SCORE: u32 = 250;
+pub const FIGHTER_SPEED: f32 = 140.0;
+pub const FIGHTER_TURN_RATE: f32 = 2.5;
+pub const FIGHTER_SCALE: f32 = 0.7;
+pub const FIGHTER_RADIUS: f32 = 12.0;
+pub const FIGHTER_AVOID_DISTANCE: f32 = 60.0;
+pub const FIGHTER_FIRE_ANGLE: f32 = 0.2;
+pub const FIGHTER_FIRE_RANGE: f32 = 350.0;
+pub const FIGHTER_SHOOT_INTERVAL: f32 = 1.2;
+pub const FIGHTER_SHOT_SPEED: f32 = 450.0;
+pub const TURRET_SHIELD: f32 = 10.0;
+pub const TURRET_SHIELD_REGEN_RATE: f32 = 1.0;
+pub const TURRET_HEALTH: f32 = 10.0;
+pub const TURRET_SCORE: u32 = 300;
+pub const TURRET_TURN_RATE: f32 = 1.2;
+pub const TURRET_FIRE_ANGLE: f32 = 0.1;
+pub const TURRET_SHOOT_INTERVAL: f32 = 1.5;
+pub const TURRET_SHOT_SPEED: f32 = 400.0;
+pub const TURRET_RADIUS: f32 = 16.0;
+pub const CARRIER_HEALTH: f32 = 80.0;
+pub const CARRIER_SCORE: u32 = 1000;
+pub const CARRIER_SPEED: f32 = 25.0;
+pub const CARRIER_SCALE: f32 = 2.5;
+pub const CARRIER_RADIUS: f32 = 16.0;
+pub const CARRIER_LAUNCH_INTERVAL: f32 = 4.0;
+pub const CARRIER_MAX_MINIONS: usize = 6;
+pub const CARRIER_EXPLOSION_STAGES: u32 = 6;
+pub const CARRIER_EXPLOSION_INTERVAL: f32 = 0.2;
+pub const CARRIER_BLAST_SHAKE: f32 = 0.3;
+
+pub const CAMERA_PUNCH_DURATION: f32 = 0.4;
+/// How much of the view the zoom punch cuts away at its strongest
+pub const CAMERA_PUNCH_ZOOM: f32 = 0.15;
+/// Fraction of the ship's distance from the center that the camera drifts towards it
+pub const CAMERA_FOLLOW_AMOUNT: f32 = 0.05;
+pub const CAMERA_FOLLOW_SPEED: f32 = 3.0;
+pub const CAMERA_SHAKE_DISTANCE: f32 = 12.0;
+/// Shake trauma lost per second
+pub const CAMERA_SHAKE_DECAY: f32 = 1.5;
+
+/// Opacity of explosions in the reduced flashing mode
+pub const REDUCED_FLASHING_EXPLOSION_ALPHA: f32 = 0.5;
+/// Seconds per pulse of blinking warnings in the reduced flashing mode
+pub const REDUCED_FLASHING_PULSE: f32 = 2.0;
+
+/// Seconds per pulse of the red border shown on the last life
+pub const LAST_LIFE_PULSE: f32 = 1.0;
+pub const LAST_LIFE_BORDER_WIDTH: f32 = 6.0;
+
+/// Speed of the world with the slower world assist on
+pub const ASSIST_WORLD_SPEED: f32 = 0.8;
+/// Shield energy every life starts with when the shield assist is on
+pub const ASSIST_START_SHIELD: f32 = SHIELD_PIP_ENERGY;
+
+pub const TURN_SENSITIVITY_MIN: f32 = 0.5;
+pub const TURN_SENSITIVITY_MAX: f32 = 2.0;
+pub const TURN_SENSITIVITY_STEP: f32 = 0.1;
+pub const MINION_HEALTH: f32 = 2.0;
+pub const MINION_SCORE: u32 = 50;
+pub const MINION_SPEED: f32 = 110.0;
+pub const MINION_TURN_RATE: f32 = 3.0;
+pub const MINION_SCALE: f32 = 0.4;
+pub const MINION_RADIUS: f32 = 6.0;
+pub const MINION_CONTACT_DAMAGE: f32 = 15.0;
+pub const KAMIKAZE_SCORE: u32 = 150;
+pub const KAMIKAZE_ACCELERATION: f32 = 200.0;
+pub const KAMIKAZE_MAX_SPEED: f32 = 260.0;
+pub const KAMIKAZE_SCALE: f32 = 0.5;
+pub const KAMIKAZE_RADIUS: f32 = 8.0;
+pub const KAMIKAZE_TRIGGER_DISTANCE: f32 = 30.0;
+pub const KAMIKAZE_BLAST_RADIUS: f32 = 60.0;
+pub const KAMIKAZE_DAMAGE: f32 = 30.0;
+pub const EVENT_WARNING_TIME: f32 = 3.0;
+pub const METEOR_SHOWER_DURATION: f32 = 4.0;
+pub const METEOR_INTERVAL: f32 = 0.15;
+pub const METEOR_SPEED: f32 = 250.0;
+pub const PLANET_GRAVITY: f32 = 15.0;
+pub const LOW_GRAVITY_FACTOR: f32 = 0.4;
+pub const BOT_LOOKAHEAD: f32 = 1.0;
+pub const BOT_SAFE_DISTANCE: f32 = 30.0;
+pub const BOT_HYPERSPACE_TIME: f32 = 0.15;
+pub const BOT_PROJECTILE_SPEED: f32 = 1000.0;
+pub const BOT_FIRE_RANGE: f32 = 300.0;
+pub const BOT_FIRE_ANGLE: f32 = 0.15;
+pub const BOT_TURN_TOLERANCE: f32 = 0.05;
+pub const BOT_MAX_SPEED: f32 = 120.0;
+pub const DEBRIS_COUNT: usize = 6;
+pub const DEBRIS_LIFE: f32 = 0.8;
+pub const DEBRIS_RADIUS: f32 = 3.0;
+pub const DEBRIS_DAMAGE: f32 = 3.0;
+pub const MINE_DAMAGE: f32 = 40.0;
+pub const MINE_BLAST_RADIUS: f32 = 50.0;
+pub const MINE_RADIUS: f32 = 6.0;
+pub const MINE_SCORE: u32 = 75;
+pub const WORMHOLE_RADIUS: f32 = 20.0;
+/// Seconds before something that came through a wormhole can enter one again
+pub const WORMHOLE_COOLDOWN: f32 = 0.5;
+pub const SOLAR_FLARE_DURATION: f32 = 3.0;
+/// How far into the play area a flare reaches, as a fraction of the screen
+pub const SOLAR_FLARE_REACH: f32 = 0.6;
+pub const SOLAR_FLARE_WIDTH: f32 = 80.0;
+pub const SOLAR_FLARE_DAMAGE: f32 = 20.0;
+/// Damage per second dealt to enemies caught in a flare
+pub const SOLAR_FLARE_ENEMY_DPS: f32 = 10.0;
+pub const LEVEL_LIFE_BONUS: u32 = 500;
+pub const LEVEL_ACCURACY_BONUS: u32 = 2000;
+pub const LEVEL_SUMMARY_DURATION: f32 = 5.0;
+/// Seconds before the level summary can be skipped
+pub const LEVEL_SUMMARY_INPUT_DELAY: f32 = 1.0;
+pub const ENDLESS_SPAWN_INTERVAL_START: f32 = 4.0;
+pub const ENDLESS_SPAWN_INTERVAL_MIN: f32 = 0.75;
+pub const ENDLESS_RAMP_TIME: f32 = 300.0;
+pub const ENDLESS_SCORE_PER_SECOND: u32 = 10;
+pub const TIME_ATTACK_LEVELS: u32 = 5;
+pub const TIME_ATTACK_DEATH_PENALTY: f32 = 10.0;
+pub const SURVIVAL_INTERMISSION: f32 = 8.0;
+pub const SURVIVAL_MAX_UFOS: u32 = 4;
+pub const CHAIN_WINDOW: f32 = 0.75;
+pub const CHAIN_BONUS: u32 = 100;
+/// Score popups stay put this long before flying to the HUD score
+pub const SCORE_POPUP_HOLD: f32 = 0.4;
+pub const SCORE_POPUP_FLIGHT: f32 = 0.6;
+/// Fraction of the way the HUD score rolls towards the actual score per second
+pub const SCORE_ROLL_RATE: f32 = 8.0;
+/// Default caps on entities alive at once, overridable in `settings.txt`
+pub const MAX_PARTICLES: usize = 500;
+pub const MAX_PROJECTILES: usize = 300;
+pub const MAX_NOTIFICATIONS: usize = 6;
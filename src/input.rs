@@ -1,69 +1,318 @@
-use bevy::prelude::*;
+use crate::plugins::Settings;
+use bevy::{
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant},
+    utils::HashMap,
+};
 
-#[derive(Resource, Default)]
+/// How far the stick must be tilted to count as a menu move
+const STICK_MENU_THRESHOLD: f32 = 0.5;
+/// How far the stick must be tilted to turn the ship at all
+const STICK_TURN_DEADZONE: f32 = 0.1;
+
+/// Controls read this frame. As a component on a ship it is used instead of
+/// the player's input for that ship.
+#[derive(Resource, Component, Default)]
 pub struct InputState {
     pub left: bool,
     pub right: bool,
+    /// How hard to turn, from 0 to 1, when turning with the stick. `None` turns at full
+    /// speed, as the keyboard and d-pad do.
+    pub analog_turn: Option<f32>,
     pub up: bool,
     pub down: bool,
+    /// Left and right for moving around menus, unlike `left` and `right` which are held to turn
+    pub menu_left: bool,
+    pub menu_right: bool,
+    pub back: bool,
+    pub pause: bool,
     pub throttle: bool,
+    /// Throttle pressed down this frame, for latching it on and off
+    pub throttle_toggle: bool,
     pub fire: bool,
+    pub secondary_fire: bool,
+    pub hyperspace: bool,
     pub ok: bool,
     pub weapon_1: bool,
     pub weapon_2: bool,
     pub weapon_3: bool,
     pub weapon_4: bool,
+    pub weapon_5: bool,
+    pub weapon_6: bool,
     pub weapon_next: bool,
     pub weapon_prev: bool,
+    pub mute: bool,
+}
+
+/// Gameplay actions that can be bound to keyboard keys
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Throttle,
+    TurnLeft,
+    TurnRight,
+    Fire,
+    SecondaryFire,
+    Hyperspace,
+    Weapon1,
+    Weapon2,
+    Weapon3,
+    Weapon4,
+    Weapon5,
+    Weapon6,
+    WeaponNext,
+    WeaponPrev,
+    Pause,
+    Mute,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::Throttle,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::Fire,
+        Action::SecondaryFire,
+        Action::Hyperspace,
+        Action::Weapon1,
+        Action::Weapon2,
+        Action::Weapon3,
+        Action::Weapon4,
+        Action::Weapon5,
+        Action::Weapon6,
+        Action::WeaponNext,
+        Action::WeaponPrev,
+        Action::Pause,
+        Action::Mute,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Throttle => "Throttle",
+            Action::TurnLeft => "Turn left",
+            Action::TurnRight => "Turn right",
+            Action::Fire => "Fire",
+            Action::SecondaryFire => "Secondary fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::Weapon1 => "Weapon 1",
+            Action::Weapon2 => "Weapon 2",
+            Action::Weapon3 => "Weapon 3",
+            Action::Weapon4 => "Weapon 4",
+            Action::Weapon5 => "Weapon 5",
+            Action::Weapon6 => "Weapon 6",
+            Action::WeaponNext => "Next weapon",
+            Action::WeaponPrev => "Previous weapon",
+            Action::Pause => "Pause",
+            Action::Mute => "Mute",
+        }
+    }
+    /// Name of the action in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::Throttle => "throttle",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::Fire => "fire",
+            Action::SecondaryFire => "secondary_fire",
+            Action::Hyperspace => "hyperspace",
+            Action::Weapon1 => "weapon_1",
+            Action::Weapon2 => "weapon_2",
+            Action::Weapon3 => "weapon_3",
+            Action::Weapon4 => "weapon_4",
+            Action::Weapon5 => "weapon_5",
+            Action::Weapon6 => "weapon_6",
+            Action::WeaponNext => "weapon_next",
+            Action::WeaponPrev => "weapon_prev",
+            Action::Pause => "pause",
+            Action::Mute => "mute",
+        }
+    }
+}
+
+/// Keyboard key of every action. Menus keep their own fixed keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings(HashMap<Action, KeyCode>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        BindingPreset::Wasd.bindings()
+    }
+}
+
+/// Built-in sets of bindings to start customizing from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingPreset {
+    Wasd,
+    /// Arrows for steering with the fire keys on the right side of the keyboard
+    Arrows,
+    /// WASD mirrored over to IJKL
+    LeftHanded,
+    Esdf,
+}
+
+impl BindingPreset {
+    pub const ALL: [BindingPreset; 4] = [
+        BindingPreset::Wasd,
+        BindingPreset::Arrows,
+        BindingPreset::LeftHanded,
+        BindingPreset::Esdf,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            BindingPreset::Wasd => "WASD",
+            BindingPreset::Arrows => "Arrows",
+            BindingPreset::LeftHanded => "Left-handed",
+            BindingPreset::Esdf => "ESDF",
+        }
+    }
+    /// Keys of the preset in the order of `Action::ALL`
+    fn keys(&self) -> [KeyCode; 16] {
+        use KeyCode::*;
+        match self {
+            BindingPreset::Wasd => [
+                W, A, D, Space, ShiftLeft, H, Key1, Key2, Key3, Key4, Key5, Key6, E, Q, Escape, M,
+            ],
+            BindingPreset::Arrows => [
+                Up,
+                Left,
+                Right,
+                ControlRight,
+                ShiftRight,
+                Down,
+                Key1,
+                Key2,
+                Key3,
+                Key4,
+                Key5,
+                Key6,
+                Period,
+                Comma,
+                Escape,
+                M,
+            ],
+            BindingPreset::LeftHanded => [
+                I, J, L, Space, ShiftRight, Semicolon, Key7, Key8, Key9, Key0, Minus, Equals, O, U,
+                Escape, M,
+            ],
+            BindingPreset::Esdf => [
+                E, S, F, Space, ShiftLeft, J, Key1, Key2, Key3, Key4, Key5, Key6, R, W, Escape, M,
+            ],
+        }
+    }
+    pub fn bindings(&self) -> KeyBindings {
+        KeyBindings(Action::ALL.into_iter().zip(self.keys()).collect())
+    }
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.0[&action]
+    }
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.0.insert(action, key);
+    }
+    /// Other actions bound to the same key as `action`
+    pub fn conflicts(&self, action: Action) -> impl Iterator<Item = Action> + '_ {
+        let key = self.key(action);
+        Action::ALL
+            .into_iter()
+            .filter(move |other| *other != action && self.key(*other) == key)
+    }
+}
+
+/// Name of a key as written in the settings file
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+/// Reads a key name written by `key_name`
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    KeyCode::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
 }
 
 pub fn update_input_state(
     mut state: ResMut<InputState>,
+    settings: Res<Settings>,
     keyboard: Res<Input<KeyCode>>,
     gamepads: Res<Gamepads>,
     buttons: Res<Input<GamepadButton>>,
     axes: Res<Axis<GamepadAxis>>,
+    mut previous_stick: Local<HashMap<Gamepad, Vec2>>,
 ) {
-    state.left = keyboard.pressed(KeyCode::A);
-    state.right = keyboard.pressed(KeyCode::D);
-    state.up = keyboard.just_pressed(KeyCode::W);
-    state.down = keyboard.just_pressed(KeyCode::S);
-    state.throttle = keyboard.pressed(KeyCode::W);
-    state.fire = keyboard.pressed(KeyCode::Space);
+    let bindings = &settings.bindings;
+    let action_pressed = |action| keyboard.pressed(bindings.key(action));
+    let action_just_pressed = |action| keyboard.just_pressed(bindings.key(action));
+    state.left = action_pressed(Action::TurnLeft);
+    state.right = action_pressed(Action::TurnRight);
+    state.up = keyboard.any_just_pressed([KeyCode::W, KeyCode::Up]);
+    state.down = keyboard.any_just_pressed([KeyCode::S, KeyCode::Down]);
+    state.menu_left = keyboard.any_just_pressed([KeyCode::A, KeyCode::Left]);
+    state.menu_right = keyboard.any_just_pressed([KeyCode::D, KeyCode::Right]);
+    state.back = keyboard.any_just_pressed([KeyCode::Back, KeyCode::Escape]);
+    state.pause = action_just_pressed(Action::Pause);
+    state.throttle = action_pressed(Action::Throttle);
+    state.throttle_toggle = action_just_pressed(Action::Throttle);
+    state.fire = action_pressed(Action::Fire);
+    state.secondary_fire = action_pressed(Action::SecondaryFire);
+    state.hyperspace = action_just_pressed(Action::Hyperspace);
     state.ok = keyboard.just_pressed(KeyCode::Space);
-    state.weapon_1 = keyboard.just_pressed(KeyCode::Key1);
-    state.weapon_2 = keyboard.just_pressed(KeyCode::Key2);
-    state.weapon_3 = keyboard.just_pressed(KeyCode::Key3);
-    state.weapon_4 = keyboard.just_pressed(KeyCode::Key4);
-    state.weapon_next = keyboard.just_pressed(KeyCode::E);
-    state.weapon_prev = keyboard.just_pressed(KeyCode::Q);
+    state.weapon_1 = action_just_pressed(Action::Weapon1);
+    state.weapon_2 = action_just_pressed(Action::Weapon2);
+    state.weapon_3 = action_just_pressed(Action::Weapon3);
+    state.weapon_4 = action_just_pressed(Action::Weapon4);
+    state.weapon_5 = action_just_pressed(Action::Weapon5);
+    state.weapon_6 = action_just_pressed(Action::Weapon6);
+    state.weapon_next = action_just_pressed(Action::WeaponNext);
+    state.weapon_prev = action_just_pressed(Action::WeaponPrev);
+    state.mute = action_just_pressed(Action::Mute);
+    state.analog_turn = None;
+    let mut digital_turn = state.left || state.right;
 
     for gamepad in gamepads.iter() {
-        let left_stick_x = axes
-            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
-            .unwrap_or(0.0);
-        state.left |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadLeft))
-            || left_stick_x < 0.1;
-        state.right |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadRight))
-            || left_stick_x > 0.1;
-        let left_stick_y = axes
-            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
-            .unwrap_or(0.0);
-        state.up |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadUp))
-            || left_stick_y > 0.1;
-        state.down |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadDown))
-            || left_stick_y < 0.1;
-        state.throttle |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::South))
-            || buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger));
-        state.fire |= buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::West))
-            || buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger));
-        state.ok |= buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::West))
-            || buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger));
-        state.weapon_next |= buttons.pressed(GamepadButton::new(
-            gamepad,
-            GamepadButtonType::RightTrigger2,
-        ));
-        state.weapon_prev |=
-            buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2));
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                .unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                .unwrap_or(0.0),
+        );
+        let previous = previous_stick.insert(gamepad, stick).unwrap_or_default();
+        // Menus move once per tilt of the stick, like they do once per key press
+        let tilted = |value: f32, previous: f32, direction: f32| {
+            value * direction >= STICK_MENU_THRESHOLD && previous * direction < STICK_MENU_THRESHOLD
+        };
+        let pressed = |button| buttons.pressed(GamepadButton::new(gamepad, button));
+        let just_pressed = |button| buttons.just_pressed(GamepadButton::new(gamepad, button));
+
+        let dpad_left = pressed(GamepadButtonType::DPadLeft);
+        let dpad_right = pressed(GamepadButtonType::DPadRight);
+        digital_turn |= dpad_left || dpad_right;
+        if stick.x.abs() > STICK_TURN_DEADZONE {
+            let amount = (stick.x.abs() - STICK_TURN_DEADZONE) / (1.0 - STICK_TURN_DEADZONE);
+            state.analog_turn = Some(state.analog_turn.map_or(amount, |other| other.max(amount)));
+        }
+        state.left |= dpad_left || stick.x < -STICK_TURN_DEADZONE;
+        state.right |= dpad_right || stick.x > STICK_TURN_DEADZONE;
+        state.up |= just_pressed(GamepadButtonType::DPadUp) || tilted(stick.y, previous.y, 1.0);
+        state.down |=
+            just_pressed(GamepadButtonType::DPadDown) || tilted(stick.y, previous.y, -1.0);
+        state.menu_left |=
+            just_pressed(GamepadButtonType::DPadLeft) || tilted(stick.x, previous.x, -1.0);
+        state.menu_right |=
+            just_pressed(GamepadButtonType::DPadRight) || tilted(stick.x, previous.x, 1.0);
+        state.throttle |=
+            pressed(GamepadButtonType::South) || pressed(GamepadButtonType::LeftTrigger);
+        state.throttle_toggle |=
+            just_pressed(GamepadButtonType::South) || just_pressed(GamepadButtonType::LeftTrigger);
+        state.fire |= pressed(GamepadButtonType::West) || pressed(GamepadButtonType::RightTrigger);
+        state.secondary_fire |= pressed(GamepadButtonType::North);
+        state.hyperspace |= just_pressed(GamepadButtonType::East);
+        state.back |= just_pressed(GamepadButtonType::East);
+        state.pause |= just_pressed(GamepadButtonType::Start);
+        state.ok |=
+            just_pressed(GamepadButtonType::West) || just_pressed(GamepadButtonType::RightTrigger);
+        state.weapon_next |= pressed(GamepadButtonType::RightTrigger2);
+        state.weapon_prev |= pressed(GamepadButtonType::LeftTrigger2);
+    }
+    // Anything digital turning as well wins over a gentle tilt
+    if digital_turn {
+        state.analog_turn = None;
     }
 }
@@ -1,9 +1,8 @@
-use std::f32::consts::TAU;
-
-use bevy::{asset::LoadState, prelude::*};
+use bevy::{asset::LoadState, ecs::query::Has, prelude::*};
 use rand::{random, thread_rng, Rng};
 
 mod bundles;
+mod collision;
 mod components;
 mod constants;
 mod input;
@@ -11,91 +10,163 @@ mod plugins;
 mod resources;
 mod utils;
 
-use crate::{bundles::*, components::*, constants::*, resources::*, utils::*};
+use crate::{bundles::*, collision::*, components::*, constants::*, resources::*, utils::*};
+
+/// Ordering of the in-game systems within a frame
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, SystemSet)]
+enum GameSet {
+    /// Turning input into ship controls and steering
+    Control,
+    /// Movement, firing and other per-frame simulation
+    Simulation,
+    /// Updating collision shapes and detecting collisions
+    Collision,
+    /// Reacting to hits, collisions and deaths
+    Resolution,
+}
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 enum AppState {
     #[default]
     Loading,
     Title,
+    ModeSelect,
+    Hangar,
+    Stats,
+    Options,
+    Controls,
+    Credits,
+    ShipSelect,
     NewGame,
     LoadLevel,
     InGame,
+    LevelSummary,
+    GameOver,
+    Shop,
     HighScore,
     HighScoreEntry,
 }
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
+        .init_resource::<GameAssets>()
         .insert_resource(input::InputState::default())
         .insert_resource(SpriteSheets::default())
         .insert_resource(Level(0))
         .insert_resource(Score(0))
+        .insert_resource(Minerals(0))
+        .insert_resource(TimeScale::default())
+        .insert_resource(ScoreMultiplier::default())
+        .insert_resource(RunStats::default())
+        .insert_resource(GameMode::default())
+        .insert_resource(Modifiers::default())
+        .insert_resource(LevelStartStats::default())
         .insert_resource(LevelStartDelayTimer::default())
-        .add_systems(Startup, init)
+        .add_event::<CollisionEvent>()
         .add_state::<AppState>()
         .add_plugins((
             plugins::CameraPlugin,
             plugins::TitleScreenPlugin,
             plugins::HighScorePlugin,
+            plugins::GameOverPlugin,
+            plugins::ModeSelectPlugin,
+            plugins::LevelSummaryPlugin,
+            plugins::ShopPlugin,
+            plugins::ProfilePlugin,
+            plugins::HangarPlugin,
+            plugins::StatsPlugin,
+            plugins::ShipSelectPlugin,
         ))
-        .add_systems(
+        .add_plugins((
+            plugins::EndlessPlugin,
+            plugins::TimeAttackPlugin,
+            plugins::SurvivalPlugin,
+            plugins::DamagePlugin,
+            plugins::LevelsPlugin,
+            plugins::ManifestPlugin,
+            plugins::EffectsPlugin,
+            plugins::ShipPlugin,
+            plugins::WeaponPlugin,
+            plugins::AsteroidPlugin,
+            plugins::PowerupPlugin,
+        ))
+        .configure_sets(
             Update,
             (
-                input::update_input_state,
-                spinning_system,
-                wrapping_system,
-                expiring_system,
-                scaling_system,
-                fading_system,
-                animation_system,
-            ),
+                GameSet::Control,
+                GameSet::Simulation,
+                GameSet::Collision,
+                GameSet::Resolution,
+            )
+                .chain(),
         )
+        .add_systems(Update, (input::update_input_state, wrapping_system))
         .add_systems(Update, loading.run_if(in_state(AppState::Loading)))
+        .add_systems(OnExit(AppState::Loading), despawn_tagged::<LoadingScreen>)
         .add_systems(OnEnter(AppState::NewGame), new_game)
         .add_systems(OnEnter(AppState::LoadLevel), load_level)
         .add_systems(
             Update,
-            (
-                level_start_delay_system,
-                scaling_system,
-                expiring_system,
-                fading_system,
-            )
-                .run_if(in_state(AppState::LoadLevel)),
+            level_start_delay_system.run_if(in_state(AppState::LoadLevel)),
         )
         .add_systems(
             Update,
             (
+                gravity_system.before(moving_system),
                 moving_system,
-                ship_control_system,
-                ship_physics,
-                ship_sprite,
-                ship_respawn_system,
-                shield_sprite,
-                collision_shape_system,
-                beam_sprite_system,
+                play_time_system,
             )
+                .in_set(GameSet::Simulation)
                 .run_if(in_state(AppState::InGame)),
         )
         .add_systems(
             Update,
             (
-                asteroid_hit_system,
-                asteroid_split_system,
-                ship_projectile_asteroid_hit_system.after(ship_physics),
-                ship_powerup_collision_system,
-                ship_asteroid_collision_system,
-                level_finished_system,
-                gameover_system,
-                cheat_system,
+                collision_shape_system,
+                collision_detection_system.after(collision_shape_system),
             )
+                .in_set(GameSet::Collision)
+                .run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(
+            Update,
+            (level_finished_system, gameover_system)
+                .in_set(GameSet::Resolution)
                 .run_if(in_state(AppState::InGame)),
         )
         .add_systems(OnExit(AppState::InGame), despawn_tagged::<LevelEntity>)
-        .add_plugins((plugins::HudPlugin, plugins::UfoPlugin))
-        .run();
+        .add_plugins((
+            plugins::HudPlugin,
+            plugins::UfoPlugin,
+            plugins::EnemyFighterPlugin,
+            plugins::TurretPlugin,
+            plugins::CarrierPlugin,
+            plugins::KamikazePlugin,
+            plugins::EventsPlugin,
+            plugins::WormholePlugin,
+            plugins::DronePlugin,
+            plugins::LocalizationPlugin,
+            plugins::OptionsPlugin,
+            plugins::FontsPlugin,
+            plugins::MenuPlugin,
+            plugins::CreditsPlugin,
+            plugins::QuitPlugin,
+        ))
+        .add_plugins((
+            plugins::MinefieldPlugin,
+            plugins::BotPlugin,
+            plugins::InspectorPlugin,
+            plugins::AccessibilityPlugin,
+            plugins::ControlsPlugin,
+        ));
+    #[cfg(feature = "dev")]
+    app.add_plugins((
+        plugins::ConsolePlugin,
+        plugins::CollisionDebugPlugin,
+        plugins::DebugOverlayPlugin,
+    ));
+    app.run();
 }
 
 fn despawn_tagged<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
@@ -104,24 +175,73 @@ fn despawn_tagged<T: Component>(mut commands: Commands, query: Query<Entity, Wit
     }
 }
 
-fn init(asset_server: Res<AssetServer>, mut sprite_sheets: ResMut<SpriteSheets>) {
-    sprite_sheets.images = asset_server.load_folder("img").unwrap();
-}
 fn loading(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
     mut sprite_sheets: ResMut<SpriteSheets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+    level_definitions: Res<plugins::LevelDefinitions>,
+    translations: Res<plugins::Translations>,
+    font_fallback: Res<plugins::FontFallback>,
+    localization: Res<plugins::Localization>,
+    manifest_assets: Res<plugins::ManifestAssets>,
+    manifests: Res<Assets<plugins::AssetManifest>>,
+    mut bar_query: Query<&mut Style, With<LoadingBar>>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut loading_text: Local<Option<Entity>>,
+    mut loading_screen_spawned: Local<bool>,
 ) {
-    if loading_text.is_none() {
-        let font = asset_server.load("fonts/DejaVuSans.ttf");
-        *loading_text = Some(commands.spawn(LoadingTextBundle::new(font)).id());
+    if !*loading_screen_spawned {
+        let font = game_assets.font.clone();
+        let text = localization.get("Loading...");
+        commands
+            .spawn(LoadingTextBundle::new(text, font))
+            .insert(LoadingScreen);
+        commands
+            .spawn(LoadingBarBundle::default())
+            .insert(LoadingScreen)
+            .with_children(|bar| {
+                bar.spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                })
+                .insert(LoadingBar);
+            });
+        *loading_screen_spawned = true;
+    }
+
+    let progress = manifest_assets.progress(&asset_server);
+    for mut style in bar_query.iter_mut() {
+        style.width = Val::Percent(progress * 100.0);
     }
 
-    let handles = sprite_sheets.images.iter().map(|h| h.id());
-    if let LoadState::Loaded = asset_server.get_group_load_state(handles) {
+    let font_handles = font_fallback.fonts.iter().map(|h| h.id());
+    if let LoadState::Loading = asset_server.get_group_load_state(font_handles) {
+        return;
+    }
+
+    // Translations that fail to load are left out of the language options
+    let translation_handles = translations.handles.iter().map(|h| h.id());
+    if let LoadState::Loading = asset_server.get_group_load_state(translation_handles) {
+        return;
+    }
+
+    // Level definitions that fail to load are skipped in favor of generated levels
+    let level_handles = level_definitions.handles.iter().map(|h| h.id());
+    if let LoadState::Loading = asset_server.get_group_load_state(level_handles) {
+        return;
+    }
+
+    let Some(manifest) = manifests.get(&manifest_assets.manifest) else {
+        return;
+    };
+    if manifest_assets.handles.is_some() && progress >= 1.0 {
         // Initialize texture atlases
         let asteroid_texture = asset_server.load("img/asteroids.png");
         let mut asteroid_atlas = TextureAtlas::new_empty(asteroid_texture, Vec2::new(512.0, 256.0));
@@ -159,69 +279,98 @@ fn loading(
 
         sprite_sheets.asteroids = texture_atlases.add(asteroid_atlas);
 
+        // Pack the small sprites into one atlas so that they share a texture
+        let mut sprite_atlas_builder = TextureAtlasBuilder::default();
+        for path in &manifest.atlas {
+            let handle = asset_server.load(path.as_str());
+            let image = images
+                .get(&handle)
+                .expect("sprite atlas images should be loaded");
+            sprite_atlas_builder.add_texture(handle, image);
+        }
+        let sprite_atlas = sprite_atlas_builder
+            .finish(&mut images)
+            .expect("sprite atlas images should fit in one texture");
+        let sprites = texture_atlases.add(sprite_atlas);
+        let sprite_atlas = texture_atlases
+            .get(&sprites)
+            .expect("sprite atlas was just added");
+        let sprite = |path: &str| {
+            sprite_atlas
+                .get_texture_index(&asset_server.get_handle(path))
+                .expect("image should be listed in the manifest atlas")
+        };
+
         sprite_sheets.ship = ShipImages {
-            rapid: asset_server.load("img/ship-rapid.png"),
-            rapid_accelerating: asset_server.load("img/ship-rapid_accelerating.png"),
-            rapid_left: asset_server.load("img/ship-rapid_left.png"),
-            rapid_left_accelerating: asset_server.load("img/ship-rapid_left_accelerating.png"),
-            rapid_right: asset_server.load("img/ship-rapid_right.png"),
-            rapid_right_accelerating: asset_server.load("img/ship-rapid_right_accelerating.png"),
-            spread: asset_server.load("img/ship-spread.png"),
-            spread_accelerating: asset_server.load("img/ship-spread_accelerating.png"),
-            spread_left: asset_server.load("img/ship-spread_left.png"),
-            spread_left_accelerating: asset_server.load("img/ship-spread_left_accelerating.png"),
-            spread_right: asset_server.load("img/ship-spread_right.png"),
-            spread_right_accelerating: asset_server.load("img/ship-spread_right_accelerating.png"),
-            beam: asset_server.load("img/ship-beam.png"),
-            beam_accelerating: asset_server.load("img/ship-beam_accelerating.png"),
-            beam_left: asset_server.load("img/ship-beam_left.png"),
-            beam_left_accelerating: asset_server.load("img/ship-beam_left_accelerating.png"),
-            beam_right: asset_server.load("img/ship-beam_right.png"),
-            beam_right_accelerating: asset_server.load("img/ship-beam_right_accelerating.png"),
-            plasma: asset_server.load("img/ship-plasma.png"),
-            plasma_accelerating: asset_server.load("img/ship-plasma_accelerating.png"),
-            plasma_left: asset_server.load("img/ship-plasma_left.png"),
-            plasma_left_accelerating: asset_server.load("img/ship-plasma_left_accelerating.png"),
-            plasma_right: asset_server.load("img/ship-plasma_right.png"),
-            plasma_right_accelerating: asset_server.load("img/ship-plasma_right_accelerating.png"),
-            shield: asset_server.load("img/shield.png"),
+            atlas: sprites.clone(),
+            rapid: sprite("img/ship-rapid.png"),
+            rapid_accelerating: sprite("img/ship-rapid_accelerating.png"),
+            rapid_left: sprite("img/ship-rapid_left.png"),
+            rapid_left_accelerating: sprite("img/ship-rapid_left_accelerating.png"),
+            rapid_right: sprite("img/ship-rapid_right.png"),
+            rapid_right_accelerating: sprite("img/ship-rapid_right_accelerating.png"),
+            spread: sprite("img/ship-spread.png"),
+            spread_accelerating: sprite("img/ship-spread_accelerating.png"),
+            spread_left: sprite("img/ship-spread_left.png"),
+            spread_left_accelerating: sprite("img/ship-spread_left_accelerating.png"),
+            spread_right: sprite("img/ship-spread_right.png"),
+            spread_right_accelerating: sprite("img/ship-spread_right_accelerating.png"),
+            beam: sprite("img/ship-beam.png"),
+            beam_accelerating: sprite("img/ship-beam_accelerating.png"),
+            beam_left: sprite("img/ship-beam_left.png"),
+            beam_left_accelerating: sprite("img/ship-beam_left_accelerating.png"),
+            beam_right: sprite("img/ship-beam_right.png"),
+            beam_right_accelerating: sprite("img/ship-beam_right_accelerating.png"),
+            plasma: sprite("img/ship-plasma.png"),
+            plasma_accelerating: sprite("img/ship-plasma_accelerating.png"),
+            plasma_left: sprite("img/ship-plasma_left.png"),
+            plasma_left_accelerating: sprite("img/ship-plasma_left_accelerating.png"),
+            plasma_right: sprite("img/ship-plasma_right.png"),
+            plasma_right_accelerating: sprite("img/ship-plasma_right_accelerating.png"),
+            shield: sprite("img/shield.png"),
         };
 
         sprite_sheets.ufo = UfoImages {
+            atlas: sprites.clone(),
             ship: vec![
-                asset_server.load("img/ufo_1.png"),
-                asset_server.load("img/ufo_2.png"),
-                asset_server.load("img/ufo_3.png"),
-                asset_server.load("img/ufo_4.png"),
+                sprite("img/ufo_1.png"),
+                sprite("img/ufo_2.png"),
+                sprite("img/ufo_3.png"),
+                sprite("img/ufo_4.png"),
             ],
             laser: asset_server.load("img/ufolaser.png"),
         };
 
         sprite_sheets.powerup = PowerupImages {
-            laser: asset_server.load("img/powerup_laser.png"),
-            spread: asset_server.load("img/powerup_spread.png"),
-            beam: asset_server.load("img/powerup_beam.png"),
-            plasma: asset_server.load("img/powerup_plasma.png"),
-            extra_life: asset_server.load("img/powerup_extralife.png"),
-            lose_life: asset_server.load("img/powerup_loselife.png"),
-            shield: asset_server.load("img/powerup_shield.png"),
+            atlas: sprites.clone(),
+            laser: sprite("img/powerup_laser.png"),
+            spread: sprite("img/powerup_spread.png"),
+            beam: sprite("img/powerup_beam.png"),
+            plasma: sprite("img/powerup_plasma.png"),
+            charge: sprite("img/powerup_plasma.png"),
+            railgun: sprite("img/powerup_beam.png"),
+            drone: sprite("img/powerup_laser.png"),
+            plasma_ammo: sprite("img/powerup_plasma.png"),
+            extra_life: sprite("img/powerup_extralife.png"),
+            lose_life: sprite("img/powerup_loselife.png"),
+            shield: sprite("img/powerup_shield.png"),
         };
 
-        sprite_sheets.explosion.normal = (1..=EXPLOSION_IMAGES)
-            .map(|i| format!("img/explosion/explosion1_{i:04}.png"))
-            .map(|path| asset_server.load(&path))
-            .collect();
-
         sprite_sheets.particles = ParticleImages {
-            spark: asset_server.load("img/spark.png"),
-            corona: asset_server.load("img/flares/corona.png"),
-            ring: asset_server.load("img/flares/tunelring-alpha.png"),
-            wave: asset_server.load("img/flares/wave.png"),
+            atlas: sprites.clone(),
+            spark: sprite("img/spark.png"),
+            corona: sprite("img/flares/corona.png"),
+            ring: sprite("img/flares/tunelring-alpha.png"),
+            wave: sprite("img/flares/wave.png"),
         };
+
+        sprite_sheets.explosion.normal = manifest
+            .explosion
+            .iter()
+            .map(|path| asset_server.load(path.as_str()))
+            .collect();
+
         // Loading finished
-        if let Some(entity) = *loading_text {
-            commands.entity(entity).despawn();
-        }
         next_state.set(AppState::Title);
     }
 }
@@ -229,12 +378,21 @@ fn loading(
 fn new_game(
     mut level: ResMut<Level>,
     mut score: ResMut<Score>,
+    mut minerals: ResMut<Minerals>,
+    mut run_stats: ResMut<RunStats>,
+    profile: Res<plugins::Profile>,
+    settings: Res<plugins::Settings>,
     ships_query: Query<Entity, With<Ship>>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     *level = Level(0);
     *score = Score(0);
+    *run_stats = RunStats {
+        assisted: settings.assisted(),
+        ..default()
+    };
+    *minerals = Minerals(profile.starting_minerals as u32 * 10);
     for ship_entity in ships_query.iter() {
         commands.entity(ship_entity).despawn_recursive();
     }
@@ -244,17 +402,33 @@ fn new_game(
 fn load_level(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
     sprite_sheets: Res<SpriteSheets>,
     level: Res<Level>,
-    mut ships_query: Query<(&mut Transform, &mut Moving), With<Ship>>,
+    profile: Res<plugins::Profile>,
+    mut ships_query: Query<(&mut Ship, &mut Transform, &mut Moving)>,
     mut level_start_delay_timer: ResMut<LevelStartDelayTimer>,
+    run_stats: Res<RunStats>,
+    mut level_start_stats: ResMut<LevelStartStats>,
+    level_definitions: Res<plugins::LevelDefinitions>,
+    localization: Res<plugins::Localization>,
+    level_definition_assets: Res<Assets<plugins::LevelDefinition>>,
+    mut active_level_definition: ResMut<plugins::ActiveLevelDefinition>,
+    mode: Res<GameMode>,
+    modifiers: Res<Modifiers>,
 ) {
     println!("setup level {}", level.number());
+    level_start_stats.0 = run_stats.clone();
+
+    let definition = level_definitions.find(level.number(), &level_definition_assets);
+    active_level_definition.0 = definition.cloned();
 
     let asteroid_variant = level.asteroid_variant();
 
-    let background_texture =
-        asset_server.load(&format!("img/background-{}.png", level.background_image()));
+    let background_image = definition
+        .and_then(|definition| definition.background)
+        .unwrap_or(level.background_image());
+    let background_texture = asset_server.load(&format!("img/background-{}.png", background_image));
     commands
         .spawn(SpriteBundle {
             texture: background_texture,
@@ -263,14 +437,46 @@ fn load_level(
         })
         .insert(LevelEntity);
 
+    if let Some(music) = definition.and_then(|definition| definition.music.as_ref()) {
+        commands
+            .spawn(AudioBundle {
+                source: asset_server.load(music),
+                settings: PlaybackSettings::LOOP,
+            })
+            .insert(LevelEntity);
+    }
+
     let mut rng = thread_rng();
-    for size in level.asteroids() {
-        let distance: f32 = rng.gen_range(level.asteroid_distance_bounds());
-        let direction = random::<f32>() * std::f32::consts::TAU;
-        let position: Vec2 = Vec2::from_angle(direction) * distance;
-        let heading = random::<f32>() * std::f32::consts::TAU;
-        let speed = rng.gen_range(level.asteroid_speed_bounds());
-        let velocity = Vec2::from_angle(heading) * speed;
+    let asteroids: Vec<_> = match definition {
+        // Endless and survival modes bring in asteroids from off-screen instead
+        _ if matches!(*mode, GameMode::Endless | GameMode::Survival) => Vec::new(),
+        Some(definition) => definition
+            .asteroids
+            .iter()
+            .flat_map(|group| (0..group.count).map(move |_| group))
+            .map(|group| {
+                (
+                    group.size,
+                    group.position.map(Vec2::from),
+                    group.velocity.map(Vec2::from),
+                )
+            })
+            .collect(),
+        None => level.asteroids().map(|size| (size, None, None)).collect(),
+    };
+    let speed_modifier = definition.map_or(1.0, |definition| definition.modifiers.asteroid_speed)
+        * modifiers.asteroid_speed();
+    for (size, position, velocity) in asteroids {
+        let position = position.unwrap_or_else(|| {
+            let distance: f32 = rng.gen_range(level.asteroid_distance_bounds());
+            let direction = random::<f32>() * std::f32::consts::TAU;
+            Vec2::from_angle(direction) * distance
+        });
+        let velocity = velocity.unwrap_or_else(|| {
+            let heading = random::<f32>() * std::f32::consts::TAU;
+            let speed = rng.gen_range(level.asteroid_speed_bounds());
+            Vec2::from_angle(heading) * speed
+        }) * speed_modifier;
         let spinning_speed = random::<f32>() - 0.5;
         commands.spawn(AsteroidBundle::new(
             sprite_sheets.as_ref(),
@@ -282,30 +488,116 @@ fn load_level(
         ));
     }
 
+    if let Some(planet) = definition.and_then(|definition| definition.planet.as_ref()) {
+        let direction = planet.edge.direction();
+        let edge = Vec2::new(GAME_WIDTH as f32, GAME_HEIGHT as f32) / 2.0 * direction.abs();
+        // Mostly off-screen, peeking in over the edge
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: sprite_sheets.particles.atlas.clone(),
+                transform: Transform::from_translation(
+                    (direction * (edge.length() + 100.0)).extend(-0.08),
+                )
+                .with_scale(Vec3::splat(8.0)),
+                sprite: TextureAtlasSprite {
+                    index: sprite_sheets.particles.corona,
+                    color: Color::rgba(0.4, 0.6, 1.0, 0.8),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            GravitySource {
+                acceleration: direction * planet.gravity,
+            },
+            LevelEntity,
+        ));
+    }
+
+    if let Some(nebula) = definition.and_then(|definition| definition.nebula.as_ref()) {
+        for _ in 0..nebula.clouds {
+            let position = Vec2::new(rng.gen_range(-400.0..400.0), rng.gen_range(-240.0..240.0));
+            let velocity = Vec2::from_angle(random::<f32>() * std::f32::consts::TAU)
+                * rng.gen_range(5.0..15.0);
+            // Above everything in play but below the HUD
+            commands.spawn((
+                SpriteSheetBundle {
+                    texture_atlas: sprite_sheets.particles.atlas.clone(),
+                    transform: Transform::from_translation(position.extend(0.3))
+                        .with_scale(Vec3::splat(rng.gen_range(3.0..6.0))),
+                    sprite: TextureAtlasSprite {
+                        index: sprite_sheets.particles.corona,
+                        color: Color::rgba(0.6, 0.4, 0.9, nebula.opacity),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Moving {
+                    velocity,
+                    ..Default::default()
+                },
+                Wrapping,
+                LevelEntity,
+            ));
+        }
+    }
+
+    for pair in definition
+        .iter()
+        .flat_map(|definition| &definition.wormholes)
+    {
+        plugins::spawn_wormholes(&mut commands, &sprite_sheets, pair);
+    }
+
+    for minefield in definition
+        .iter()
+        .flat_map(|definition| &definition.minefields)
+    {
+        plugins::spawn_minefield(&mut commands, &sprite_sheets, minefield);
+    }
+
+    for turret in definition.iter().flat_map(|definition| &definition.turrets) {
+        plugins::spawn_turret(&mut commands, &sprite_sheets, turret);
+    }
+
     if ships_query.is_empty() {
-        let ship = Ship {
+        let mut ship = Ship {
             weapon_rapid_level: 1,
-            shield_level: 0,
-            lives: 3,
+            lives: 3 + profile.extra_lives,
+            hyperspace_jumps: HYPERSPACE_JUMPS_PER_LEVEL,
             ..Ship::default()
         };
+        let shield = if modifiers.contains(Modifier::NoShields) {
+            ShieldEnergy::default()
+        } else {
+            ShieldEnergy::full(profile.starting_shield as f32 * SHIELD_PIP_ENERGY)
+        };
+        if modifiers.contains(Modifier::BeamOnly) {
+            ship.weapon_beam_level = 1;
+            ship.weapon = ShipWeapon::Beam;
+        }
         commands
-            .spawn(ShipBundle::new(ship, sprite_sheets.as_ref()))
+            .spawn(ShipBundle::new(ship, shield, sprite_sheets.as_ref()))
             .with_children(|ship| {
                 ship.spawn(ShipShieldBundle::new(&sprite_sheets.ship));
                 let projectile = ShipProjectile::Beam { power: 20.0 };
                 let beam_from = Vec2::ZERO;
                 let length = 0.0;
                 let max_length = 0.0;
-                let texture = asset_server.load("img/continuous_beam.png");
+                let texture = game_assets.beam.clone();
                 let mut transform = Transform::from_xyz(0.0, 0.0, -0.01);
                 transform.scale.y = length / 128.0;
                 ship.spawn(ShipBeamBundle::new(
-                    projectile, texture, transform, beam_from, length, max_length,
+                    projectile,
+                    texture,
+                    transform,
+                    beam_from,
+                    length,
+                    max_length,
+                    profile.projectile_palette.color(),
                 ))
                 .with_children(|beam| {
                     beam.spawn(SpriteBundle {
-                        texture: asset_server.load("img/continuous_tip.png"),
+                        texture: game_assets.beam_tip.clone(),
                         transform: Transform::from_xyz(0.0, 128.0, 0.0),
                         ..Default::default()
                     })
@@ -313,7 +605,8 @@ fn load_level(
                 });
             });
     } else {
-        for (mut transform, mut moving) in ships_query.iter_mut() {
+        for (mut ship, mut transform, mut moving) in ships_query.iter_mut() {
+            ship.hyperspace_jumps = HYPERSPACE_JUMPS_PER_LEVEL;
             transform.translation = Vec3::ZERO;
             moving.velocity = Vec2::ZERO;
             moving.acceleration = Vec2::ZERO;
@@ -321,8 +614,8 @@ fn load_level(
     }
 
     commands.spawn(GameNotificationBundle::new(
-        format!("Level {}", level.number()),
-        asset_server.load("fonts/DejaVuSans.ttf"),
+        localization.format("Level {n}", &[("n", &level.number())]),
+        game_assets.font.clone(),
         Vec2::ZERO,
         60.0,
         3.0,
@@ -340,476 +633,80 @@ fn level_start_delay_system(
         app_state.set(AppState::InGame);
     }
 }
-fn moving_system(mut moving_query: Query<(&mut Moving, &mut Transform)>, time: Res<Time>) {
-    for (mut moving, mut transform) in moving_query.iter_mut() {
-        let velocity_delta = moving.acceleration * time.delta().as_secs_f32();
-        moving.velocity += velocity_delta;
-        transform.translation += (moving.velocity * time.delta().as_secs_f32()).extend(0.0);
-    }
-}
-fn spinning_system(mut spinning_query: Query<(&Spinning, &mut Transform)>, time: Res<Time>) {
-    for (spinning, mut transform) in spinning_query.iter_mut() {
-        transform.rotation *= Quat::from_rotation_z(spinning.speed * time.delta().as_secs_f32());
-    }
-}
-fn scaling_system(mut scaling_query: Query<(&mut Scaling, &mut Transform)>, time: Res<Time>) {
-    for (mut scaling, mut transform) in scaling_query.iter_mut() {
-        scaling.elapsed += time.delta_seconds();
-        let scale = lerp(scaling.from, scaling.to, scaling.elapsed / scaling.duration);
-        transform.scale = Vec3::splat(scale);
-    }
-}
-fn fading_system(
-    mut fading_query: Query<(&mut Fading, Option<&mut Text>, Option<&mut Sprite>)>,
+fn moving_system(
+    mut moving_query: Query<(&mut Moving, &mut Transform, Has<Ship>)>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
 ) {
-    for (mut fading, text, sprite) in fading_query.iter_mut() {
-        fading.elapsed += time.delta_seconds();
-        let alpha = lerp(fading.from, fading.to, fading.elapsed / fading.duration);
-        if let Some(mut text) = text {
-            for section in text.sections.iter_mut() {
-                section.style.color.set_a(alpha);
-            }
-        }
-        if let Some(mut sprite) = sprite {
-            sprite.color.set_a(alpha);
-        }
+    for (mut moving, mut transform, is_ship) in moving_query.iter_mut() {
+        // Bullet time slows down the world but never the ship
+        let delta = time.delta().as_secs_f32() * if is_ship { 1.0 } else { time_scale.0 };
+        let velocity_delta = moving.acceleration * delta;
+        moving.velocity += velocity_delta;
+        transform.translation += (moving.velocity * delta).extend(0.0);
     }
 }
 
-fn expiring_system(
-    mut commands: Commands,
-    mut expiring_query: Query<(Entity, &mut Expiring)>,
+fn gravity_system(
+    sources_query: Query<&GravitySource>,
+    mut moving_query: Query<(&mut Moving, Has<Ship>)>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    modifiers: Res<Modifiers>,
 ) {
-    for (entity, mut expiring) in expiring_query.iter_mut() {
-        expiring.life -= time.delta().as_secs_f32();
-        if expiring.life < 0.0 {
-            commands.entity(entity).despawn_recursive()
-        }
+    let gravity: Vec2 = sources_query
+        .iter()
+        .map(|source| source.acceleration)
+        .sum::<Vec2>()
+        * modifiers.gravity();
+    if gravity == Vec2::ZERO {
+        return;
+    }
+    for (mut moving, is_ship) in moving_query.iter_mut() {
+        let delta = time.delta_seconds() * if is_ship { 1.0 } else { time_scale.0 };
+        moving.velocity += gravity * delta;
     }
 }
 
 fn wrapping_system(mut wrapping_query: Query<&mut Transform, With<Wrapping>>) {
     for mut transform in wrapping_query.iter_mut() {
-        if transform.translation.x > 400.0 {
-            transform.translation.x -= 800.0;
-        } else if transform.translation.x < -400.0 {
-            transform.translation.x += 800.0;
-        }
-
-        if transform.translation.y > 240.0 {
-            transform.translation.y -= 480.0;
-        } else if transform.translation.y < -240.0 {
-            transform.translation.y += 480.0;
-        }
+        wrap(&mut transform.translation);
     }
 }
-fn ship_respawn_system(
-    mut ships_query: Query<(&mut Ship, &mut Transform, &mut Moving, &mut Visibility)>,
-    time: Res<Time>,
-) {
-    for (mut ship, mut transform, mut moving, mut visibility) in ships_query.iter_mut() {
-        if ship.lives > 0 && ship.respawn_delay > 0.0 {
-            ship.respawn_delay -= time.delta_seconds();
-            if ship.respawn_delay > 0.0 {
-                *visibility = Visibility::Hidden;
-                ship.invulnerability = 100.0;
-            } else {
-                *visibility = Visibility::Visible;
-                ship.invulnerability = SHIP_INVULNERABILITY;
-                transform.translation = Vec3::ZERO;
-                moving.velocity = Vec2::ZERO;
-            }
-        } else if ship.lives <= 0 {
-            *visibility = Visibility::Hidden;
-        }
-    }
-}
-fn ship_control_system(mut ship_query: Query<&mut Ship>, input: Res<input::InputState>) {
-    for mut ship in ship_query.iter_mut() {
-        if ship.respawn_delay > 0.0 {
-            ship.fire = false;
-            continue;
-        }
-        ship.throttle = input.throttle;
-        ship.turn = match (input.left, input.right) {
-            (true, false) => ShipTurn::Left,
-            (false, true) => ShipTurn::Right,
-            _ => ShipTurn::Neutral,
-        };
-        ship.fire = input.fire;
-        if input.weapon_1 {
-            ship.weapon = ShipWeapon::Rapid;
-        } else if input.weapon_2 {
-            ship.weapon = ShipWeapon::Spread;
-        } else if input.weapon_3 {
-            ship.weapon = ShipWeapon::Beam;
-        } else if input.weapon_4 {
-            ship.weapon = ShipWeapon::Plasma;
-        }
 
-        if input.weapon_next {
-            ship.next_weapon();
-        }
-        if input.weapon_prev {
-            ship.prev_weapon();
-        }
+/// Brings a position that has left the screen back in from the opposite edge
+fn wrap(translation: &mut Vec3) {
+    if translation.x > 400.0 {
+        translation.x -= 800.0;
+    } else if translation.x < -400.0 {
+        translation.x += 800.0;
     }
-}
 
-fn ship_physics(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut ship_query: Query<(&mut Ship, &mut Moving, &mut Transform)>,
-    mut beam_query: Query<&mut Beam, Without<Ship>>,
-    time: Res<Time>,
-) {
-    let time_delta = time.delta().as_secs_f32();
-
-    for (mut ship, mut moving, mut transform) in ship_query.iter_mut() {
-        ship.invulnerability = (ship.invulnerability - time_delta).max(0.);
-        let angular_velocity = match ship.turn {
-            ShipTurn::Neutral => 0.0,
-            ShipTurn::Left => 3.0,
-            ShipTurn::Right => -3.0,
-        };
-        let acceleration = if ship.throttle { 50.0 } else { 0.0 };
-        transform.rotation *= Quat::from_rotation_z(angular_velocity * time_delta);
-        moving.acceleration = (transform.rotation * Vec3::Y * acceleration).truncate();
-
-        if ship.weapon_cooldown > 0.0 {
-            ship.weapon_cooldown -= time_delta;
-        }
-
-        if ship.fire && ship.weapon_cooldown <= 0.0 {
-            match ship.weapon {
-                ShipWeapon::Rapid => {
-                    let projectile = ShipProjectile::Rapid;
-                    let texture = asset_server.load("img/laser.png");
-                    let left_turret = transform.translation
-                        + transform.rotation * Quat::from_rotation_z(1.55) * Vec3::Y * 8.0;
-                    let right_turret = transform.translation
-                        + transform.rotation * Quat::from_rotation_z(-1.55) * Vec3::Y * 8.0;
-                    let velocity = (transform.rotation * Vec3::Y * 1200.0).truncate();
-                    let left_transform = Transform {
-                        translation: left_turret,
-                        rotation: transform.rotation.clone(),
-                        ..Default::default()
-                    };
-                    let right_transform = Transform {
-                        translation: right_turret,
-                        rotation: transform.rotation.clone(),
-                        ..Default::default()
-                    };
-                    commands.spawn(ShipProjectileBundle::new(
-                        projectile,
-                        texture.clone(),
-                        velocity.clone(),
-                        left_transform,
-                        0.25,
-                        1.0,
-                    ));
-                    commands.spawn(ShipProjectileBundle::new(
-                        projectile,
-                        texture,
-                        velocity,
-                        right_transform,
-                        0.25,
-                        1.0,
-                    ));
-                    ship.weapon_cooldown =
-                        lerp(0.3, 0.05, (ship.weapon_rapid_level - 1) as f32 / 8.0);
-                }
-                ShipWeapon::Spread => {
-                    let projectile = ShipProjectile::Spread;
-                    let texture = asset_server.load("img/shot.png");
-                    let spread_angle =
-                        lerp(0.314, 3.0, (ship.weapon_spread_level - 1) as f32 / 8.0);
-                    let shots = 2 * ship.weapon_spread_level + 1;
-                    for i in 0..shots {
-                        let rotation = transform.rotation
-                            * Quat::from_rotation_z(
-                                spread_angle * i as f32 / (shots - 1) as f32 - spread_angle / 2.0,
-                            );
-                        let velocity = (rotation * Vec3::Y).truncate() * 1200.0;
-                        let transform = Transform {
-                            translation: transform.translation,
-                            ..Default::default()
-                        };
-                        commands.spawn(ShipProjectileBundle::new(
-                            projectile,
-                            texture.clone(),
-                            velocity,
-                            transform,
-                            0.20,
-                            1.0,
-                        ));
-                    }
-                    ship.weapon_cooldown =
-                        lerp(0.8, 0.3, (ship.weapon_spread_level - 1) as f32 / 8.0);
-                }
-                ShipWeapon::Plasma => {
-                    let projectile = ShipProjectile::Plasma {
-                        power: lerp(4.0, 20.0, (ship.weapon_plasma_level - 1) as f32 / 8.0),
-                    };
-                    let texture = asset_server.load("img/plasma.png");
-                    let power = lerp(4.0, 20.0, (ship.weapon_plasma_level - 1) as f32 / 8.0);
-                    let velocity = (transform.rotation * Vec3::Y * 1000.0).truncate();
-                    let translation = transform.translation.clone();
-                    let rotation = Quat::from_rotation_z(1.57) * transform.rotation;
-                    let scale = Vec3::splat(power / 16.0);
-                    let transform = Transform {
-                        translation,
-                        rotation,
-                        scale,
-                    };
-                    commands.spawn(ShipProjectileBundle::new(
-                        projectile, texture, velocity, transform, 0.5, power,
-                    ));
-                    ship.weapon_cooldown =
-                        lerp(1.2, 0.8, (ship.weapon_plasma_level - 1) as f32 / 8.0);
-                }
-                ShipWeapon::Beam => {
-                    for mut beam in beam_query.iter_mut() {
-                        beam.active = true;
-                        beam.sustained += time_delta;
-                        if beam.sustained > BEAM_EXTEND_TIME {
-                            beam.max_length =
-                                (beam.max_length - time_delta * BEAM_SHRINK_RATE).max(0.);
-                        }
-                        if beam.cooldown <= 0.0 {
-                            beam.length = beam
-                                .max_length
-                                .min(beam.length + beam.max_length * time_delta / BEAM_EXTEND_TIME);
-                        } else {
-                            beam.cooldown -= time_delta;
-                        }
-                    }
-                }
-            }
-        } else if matches!(ship.weapon, ShipWeapon::Beam) {
-            for mut beam in beam_query.iter_mut() {
-                beam.active = false;
-                if beam.length > 0.0 {
-                    beam.length = (beam.length - time_delta * BEAM_RETRACT_RATE).max(0.0);
-                } else {
-                    beam.sustained = 0.0;
-                    let max_length =
-                        BEAM_BASE_LENGTH + BEAM_LENGTH_PER_LEVEL * ship.weapon_beam_level as f32;
-                    beam.max_length =
-                        (beam.max_length + time_delta * BEAM_RECHARGE_RATE).min(max_length);
-                }
-            }
-        }
-    }
-}
-
-fn beam_sprite_system(
-    mut beam_query: Query<(&Beam, &mut Transform, &Children), Without<BeamTip>>,
-    mut tip_query: Query<&mut Transform, With<BeamTip>>,
-) {
-    for (beam, mut transform, children) in beam_query.iter_mut() {
-        transform.scale.y = beam.length / 128.0;
-        for child in children.iter() {
-            if let Ok(mut tip_transform) = tip_query.get_mut(*child) {
-                tip_transform.scale.y = 1.0 / transform.scale.y;
-            }
-        }
-    }
-}
-fn ship_sprite(
-    mut ship_query: Query<(&Ship, &mut Sprite, &mut Handle<Image>)>,
-    sprite_sheets: Res<SpriteSheets>,
-) {
-    for (ship, mut sprite, mut image) in ship_query.iter_mut() {
-        *image = sprite_sheets.ship.choose(&ship);
-        let alpha = if ship.invulnerability > 0.0 { 0.5 } else { 1.0 };
-        sprite.color.set_a(alpha);
-    }
-}
-
-fn shield_sprite(
-    mut shield_query: Query<(&Parent, &mut Visibility), With<ShipShield>>,
-    ship_query: Query<&Ship>,
-) {
-    for (parent, mut visibility) in shield_query.iter_mut() {
-        let ship = ship_query.get(parent.get());
-        if ship
-            .expect("ShipShield should have a Ship parent")
-            .shield_level
-            > 0
-        {
-            *visibility = Visibility::Visible;
-        } else {
-            *visibility = Visibility::Hidden;
-        }
-    }
-}
-
-fn ship_projectile_asteroid_hit_system(
-    mut commands: Commands,
-    mut projectiles: Query<(
-        Entity,
-        &mut ShipProjectile,
-        &mut Transform,
-        &mut CollisionShape,
-        Option<&mut Beam>,
-    )>,
-    mut asteroids: Query<(&mut Asteroid, &CollisionShape, &Transform), Without<ShipProjectile>>,
-    sprite_sheets: Res<SpriteSheets>,
-) {
-    for (
-        projectile_entity,
-        projectile,
-        mut projectile_transform,
-        mut projectile_shape,
-        mut maybe_beam,
-    ) in projectiles.iter_mut()
-    {
-        for (mut asteroid, asteroid_shape, asteroid_transform) in asteroids.iter_mut() {
-            if projectile_shape.intersects(asteroid_shape) {
-                match *projectile {
-                    ShipProjectile::Rapid | ShipProjectile::Spread => {
-                        commands.entity(projectile_entity).despawn();
-                        if asteroid.integrity > 0 {
-                            asteroid.integrity -= 1;
-                        }
-                    }
-                    ShipProjectile::Plasma { mut power } => {
-                        let overlap = -projectile_shape.distance(asteroid_shape).min(0.0);
-                        let effect = overlap.min(asteroid.integrity as f32);
-                        power -= effect;
-                        *projectile_shape = CollisionShape::new(
-                            Shape::Circle {
-                                center: Vec2::ZERO,
-                                radius: power,
-                            },
-                            *projectile_transform,
-                        );
-                        if power <= 0.0 {
-                            commands.entity(projectile_entity).despawn();
-                        } else {
-                            projectile_transform.scale = Vec3::splat(power / 16.0);
-                        }
-                        if asteroid.integrity > 0 {
-                            asteroid.integrity -= effect.ceil() as i32;
-                        }
-                    }
-                    ShipProjectile::Beam { .. } => {
-                        if let Some(ref mut beam) = maybe_beam {
-                            if beam.active {
-                                beam.length = projectile_shape
-                                    .distance(asteroid_shape)
-                                    .min(beam.max_length);
-                                if beam.cooldown <= 0.0 {
-                                    asteroid.integrity -= BEAM_DAMAGE_PER_HIT;
-                                    beam.cooldown = BEAM_HIT_INTERVAL;
-                                }
-                            }
-                        }
-                    }
-                }
-                let point = projectile_shape.collision_point(asteroid_shape);
-                let direction = (point - asteroid_transform.translation.truncate()).normalize();
-                for _ in 0..10 {
-                    let speed = lerp(10.0, 100.0, random());
-                    let velocity =
-                        (direction + (direction.perp() * lerp(-0.5, 0.5, random()))) * speed;
-                    let acceleration = Vec2::ZERO;
-                    commands.spawn(SparkParticleBundle::new(
-                        point,
-                        velocity,
-                        acceleration,
-                        &sprite_sheets.particles,
-                    ));
-                }
-            }
-        }
-    }
-}
-
-fn asteroid_hit_system(
-    mut asteroids_query: Query<(&mut Moving, &CollisionShape, &Transform), With<Asteroid>>,
-) {
-    let mut pairs = asteroids_query.iter_combinations_mut();
-    while let Some([(mut a_moving, a_shape, a_transform), (mut b_moving, b_shape, b_transform)]) =
-        pairs.fetch_next()
-    {
-        if a_shape.intersects(b_shape) {
-            let a_position = a_transform.translation.truncate();
-            let b_position = b_transform.translation.truncate();
-            let diff = a_position - b_position;
-            let epsilon = (a_moving.velocity - b_moving.velocity) * 0.01;
-            if diff.length_squared() >= (diff + epsilon).length_squared() {
-                let direction = diff.normalize();
-                a_moving.velocity = direction * a_moving.velocity.length();
-                b_moving.velocity = -direction * b_moving.velocity.length();
-            }
-        }
-    }
-}
-fn asteroid_split_system(
-    mut commands: Commands,
-    asteroids: Query<(Entity, &Asteroid, &Transform)>,
-    sprite_sheets: Res<SpriteSheets>,
-    mut score: ResMut<Score>,
-    level: Res<Level>,
-    asset_server: Res<AssetServer>,
-) {
-    for (asteroid_entity, asteroid, transform) in asteroids.iter() {
-        if asteroid.integrity <= 0 {
-            let score_delta = asteroid_score(asteroid.size);
-            score.increase(score_delta);
-            commands.spawn(GameNotificationBundle::new(
-                format!("{}", score_delta),
-                asset_server.load("fonts/DejaVuSans.ttf"),
-                transform.translation.truncate(),
-                20.0,
-                1.0,
-            ));
-            commands.spawn(CoronaParticleBundle::new(
-                transform.translation.truncate(),
-                asteroid.size.radius() / AsteroidSize::Large.radius(),
-                &sprite_sheets.particles,
-            ));
-            commands.entity(asteroid_entity).despawn();
-            if let Some(size) = asteroid.size.smaller() {
-                let direction = (transform.rotation * transform.translation)
-                    .truncate()
-                    .normalize();
-                let n = level.asteroid_frag_count();
-                let data = (0..n)
-                    .map(|i| i as f32 * TAU / n as f32)
-                    .map(|angle| direction.rotate(Vec2::from_angle(angle)));
-
-                let parent_position = transform.translation.truncate();
-                let spinning_speed = random::<f32>() - 0.5;
-                for dir in data {
-                    let position = parent_position + dir * 5.0;
-                    let velocity = dir * 30.0;
-                    commands.spawn(AsteroidBundle::new(
-                        sprite_sheets.as_ref(),
-                        asteroid.variant,
-                        size,
-                        position,
-                        velocity,
-                        spinning_speed,
-                    ));
-                }
-            }
-        }
+    if translation.y > 240.0 {
+        translation.y -= 480.0;
+    } else if translation.y < -240.0 {
+        translation.y += 480.0;
     }
 }
 
 fn level_finished_system(
     asteroids_query: Query<Entity, With<Asteroid>>,
+    turrets_query: Query<(), With<plugins::Turret>>,
+    mode: Res<GameMode>,
     mut level: ResMut<Level>,
+    mut run_stats: ResMut<RunStats>,
     mut state: ResMut<NextState<AppState>>,
 ) {
-    if asteroids_query.is_empty() {
+    // Endless and survival runs have no levels to finish
+    let has_levels = matches!(*mode, GameMode::Classic | GameMode::TimeAttack);
+    if asteroids_query.is_empty() && turrets_query.is_empty() && has_levels {
         level.increment();
-        state.set(AppState::LoadLevel);
+        run_stats.levels_cleared += 1;
+        if *mode == GameMode::TimeAttack && plugins::time_attack_finished(&run_stats) {
+            state.set(AppState::GameOver);
+        } else {
+            state.set(AppState::LevelSummary);
+        }
     }
 }
 
@@ -824,7 +721,7 @@ fn gameover_system(
         if let Some(timer) = maybe_timer.as_mut() {
             if timer.tick(time.delta()).just_finished() {
                 *maybe_timer = None;
-                state.set(AppState::HighScoreEntry);
+                state.set(AppState::GameOver);
             }
         } else {
             *maybe_timer = Some(Timer::from_seconds(3.0, TimerMode::Once))
@@ -832,174 +729,250 @@ fn gameover_system(
     }
 }
 
-fn asteroid_score(size: AsteroidSize) -> u32 {
-    match size {
-        AsteroidSize::Tiny => 50,
-        AsteroidSize::Small => 100,
-        AsteroidSize::Medium => 150,
-        AsteroidSize::Large => 200,
-    }
+fn play_time_system(mut run_stats: ResMut<RunStats>, time: Res<Time>) {
+    run_stats.time_played += time.delta_seconds();
 }
 
-impl rand::distributions::Distribution<Powerup> for rand::distributions::Standard {
-    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Powerup {
-        use Powerup::*;
-        match rng.gen_range(0..7) {
-            0 => Laser,
-            1 => Spread,
-            2 => Beam,
-            3 => Plasma,
-            4 => ExtraLife,
-            5 => LoseLife,
-            6 => Shield,
-            _ => unreachable!(),
-        }
+fn collision_shape_system(mut query: Query<(&mut CollisionShape, &GlobalTransform)>) {
+    for (mut shape, transform) in query.iter_mut() {
+        shape.transform = transform.compute_transform();
     }
 }
 
-fn ship_powerup_collision_system(
-    mut commands: Commands,
-    mut ships_query: Query<(&mut Ship, &CollisionShape, &Transform)>,
-    powerups_query: Query<(Entity, &Powerup, &CollisionShape)>,
-    asset_server: Res<AssetServer>,
-    sprite_sheets: Res<SpriteSheets>,
-) {
-    for (mut ship, ship_shape, transform) in ships_query.iter_mut() {
-        for (powerup_entity, powerup, powerup_shape) in powerups_query.iter() {
-            if ship_shape.intersects(powerup_shape) {
-                let text = match powerup {
-                    Powerup::Laser => {
-                        ship.weapon_rapid_level = (ship.weapon_rapid_level + 1).min(8);
-                        "Laser +1"
-                    }
-                    Powerup::Spread => {
-                        ship.weapon_spread_level = (ship.weapon_spread_level + 1).min(8);
-                        "Spread +1"
-                    }
-                    Powerup::Beam => {
-                        ship.weapon_beam_level = (ship.weapon_beam_level + 1).min(8);
-                        "Beam +1"
-                    }
-                    Powerup::Plasma => {
-                        ship.weapon_plasma_level = (ship.weapon_plasma_level + 1).min(8);
-                        "Plasma +1"
-                    }
-                    Powerup::ExtraLife => {
-                        ship.lives += 1;
-                        "1up"
-                    }
-                    Powerup::LoseLife => {
-                        ship.lives = ship.lives.max(1) - 1;
-                        "-1up"
-                    }
-                    Powerup::Shield => {
-                        ship.shield_level += 1;
-                        "Shield +1"
-                    }
-                };
-                commands.entity(powerup_entity).despawn();
-                let position = transform.translation.truncate();
-                commands.spawn(GameNotificationBundle::new(
-                    text.to_owned(),
-                    asset_server.load("fonts/DejaVuSans.ttf"),
-                    position,
-                    20.0,
-                    1.0,
-                ));
-                commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
-            }
-        }
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{asset::AssetPlugin, time::TimeUpdateStrategy, utils::Duration};
+    use proptest::prelude::*;
 
-fn ship_asteroid_collision_system(
-    mut commands: Commands,
-    sprite_sheets: Res<SpriteSheets>,
-    mut ships_query: Query<(&mut Ship, &Transform, &mut Moving, &CollisionShape)>,
-    asteroids_query: Query<(&Transform, &Moving, &CollisionShape), (With<Asteroid>, Without<Ship>)>,
-) {
-    for (mut ship, ship_transform, mut ship_moving, ship_shape) in ships_query.iter_mut() {
-        if ship.invulnerability > 0.0 {
-            continue;
-        }
-        let ship_position = ship_transform.translation.truncate();
-        for (asteroid_transform, asteroid_moving, asteroid_shape) in asteroids_query.iter() {
-            let asteroid_position = asteroid_transform.translation.truncate();
-            if ship_shape.intersects(asteroid_shape) {
-                let diff = ship_position - asteroid_position;
-                let epsilon = (ship_moving.velocity - asteroid_moving.velocity) * 0.01;
-                if diff.length_squared() < (diff + epsilon).length_squared() {
-                    continue;
-                }
-                if ship.shield_level > 0 {
-                    ship.shield_level -= 1;
-                    let diff = (ship_position - asteroid_position).normalize();
-                    let speed = asteroid_moving
-                        .velocity
-                        .project_onto_normalized(diff)
-                        .length()
-                        + ship_moving.velocity.project_onto_normalized(-diff).length();
-                    ship_moving.velocity = diff * speed;
-                } else {
-                    ship.die();
-                    commands.spawn(ExplosionBundle::new(
-                        &sprite_sheets.explosion,
-                        ship_position,
-                    ));
-                    commands.spawn(WaveParticleBundle::new(
-                        ship_position,
-                        &sprite_sheets.particles,
-                    ));
-                }
-            }
+    /// Seconds every frame of the test app takes
+    const FRAME_TIME: f32 = 0.1;
+
+    /// Handles that point nowhere, the game runs without its images and font
+    fn stub_game_assets() -> GameAssets {
+        GameAssets {
+            font: default(),
+            laser: default(),
+            shot: default(),
+            plasma: default(),
+            beam: default(),
+            beam_tip: default(),
         }
     }
-}
 
-fn animation_system(
-    mut animated_query: Query<(&mut Animated, &mut Handle<Image>)>,
-    time: Res<Time>,
-) {
-    let delta = time.delta_seconds();
-    for (mut animated, mut image) in animated_query.iter_mut() {
-        animated.elapsed += delta;
-        let position = if animated.looping {
-            animated.elapsed.rem_euclid(animated.animation.duration)
-        } else {
-            animated.elapsed.min(animated.animation.duration)
-        };
-        let frame = ((animated.animation.frames.len() - 1) as f32 * position
-            / animated.animation.duration)
-            .floor() as usize;
-
-        *image = animated.animation.frames[frame].clone()
+    /// Marks loading done right away, as there is nothing for the stubbed sprite sheets to wait on
+    fn stub_loading(mut next_state: ResMut<NextState<AppState>>) {
+        next_state.set(AppState::Title);
     }
-}
 
-fn collision_shape_system(mut query: Query<(&mut CollisionShape, &GlobalTransform)>) {
-    for (mut shape, transform) in query.iter_mut() {
-        shape.transform = transform.compute_transform();
+    /// The game's state machine with the real systems moving it along and the ship systems,
+    /// without rendering and with stubbed assets. Frames take `FRAME_TIME` and input is
+    /// injected into the keyboard state.
+    fn test_app() -> App {
+        let mut sprite_sheets = SpriteSheets::default();
+        sprite_sheets.explosion.normal = vec![default()];
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_asset::<Image>()
+            .add_asset::<plugins::LevelDefinition>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                FRAME_TIME,
+            )))
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .init_resource::<Gamepads>()
+            .insert_resource(stub_game_assets())
+            .insert_resource(sprite_sheets)
+            .insert_resource(input::InputState::default())
+            .insert_resource(Level(0))
+            .insert_resource(Score(0))
+            .insert_resource(Minerals(0))
+            .insert_resource(TimeScale::default())
+            .insert_resource(RunStats::default())
+            .insert_resource(GameMode::default())
+            .insert_resource(Modifiers::default())
+            .insert_resource(LevelStartStats::default())
+            .insert_resource(LevelStartDelayTimer::default())
+            .insert_resource(plugins::Profile::default())
+            .insert_resource(plugins::Settings::default())
+            .init_resource::<plugins::Localization>()
+            .init_resource::<plugins::LevelDefinitions>()
+            .init_resource::<plugins::ActiveLevelDefinition>()
+            .add_event::<CollisionEvent>()
+            .add_state::<AppState>()
+            .add_plugins((
+                plugins::MenuPlugin,
+                plugins::TitleScreenPlugin,
+                plugins::DamagePlugin,
+                plugins::ShipPlugin,
+            ))
+            .configure_sets(
+                Update,
+                (
+                    GameSet::Control,
+                    GameSet::Simulation,
+                    GameSet::Collision,
+                    GameSet::Resolution,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, input::update_input_state)
+            .add_systems(Update, stub_loading.run_if(in_state(AppState::Loading)))
+            .add_systems(OnExit(AppState::Loading), despawn_tagged::<LoadingScreen>)
+            .add_systems(OnEnter(AppState::NewGame), new_game)
+            .add_systems(OnEnter(AppState::LoadLevel), load_level)
+            .add_systems(
+                Update,
+                level_start_delay_system.run_if(in_state(AppState::LoadLevel)),
+            )
+            .add_systems(OnExit(AppState::InGame), despawn_tagged::<LevelEntity>);
+        app
     }
-}
 
-fn cheat_system(keyboard_input: Res<Input<KeyCode>>, mut ship_query: Query<&mut Ship>) {
-    let mut ship = ship_query.single_mut();
-    if keyboard_input.just_pressed(KeyCode::F1) {
-        ship.weapon_rapid_level = ship.weapon_rapid_level.min(7) + 1;
-    }
-    if keyboard_input.just_pressed(KeyCode::F2) {
-        ship.weapon_spread_level = ship.weapon_spread_level.min(7) + 1;
+    fn state(app: &App) -> AppState {
+        *app.world.resource::<State<AppState>>().get()
     }
-    if keyboard_input.just_pressed(KeyCode::F3) {
-        ship.weapon_beam_level = ship.weapon_beam_level.min(7) + 1;
+
+    fn go_to(app: &mut App, state: AppState) {
+        app.world.resource_mut::<NextState<AppState>>().set(state);
+        app.update();
     }
-    if keyboard_input.just_pressed(KeyCode::F4) {
-        ship.weapon_plasma_level = ship.weapon_plasma_level.min(7) + 1;
+
+    /// Runs frames until the app reaches `target`, failing after ten seconds of them
+    fn update_until(app: &mut App, target: AppState) {
+        for _ in 0..(10.0 / FRAME_TIME) as usize {
+            if state(app) == target {
+                return;
+            }
+            app.update();
+        }
+        assert_eq!(state(app), target);
     }
-    if keyboard_input.just_pressed(KeyCode::F5) {
-        ship.shield_level += 1;
+
+    /// Runs frames for `seconds`
+    fn run_for(app: &mut App, seconds: f32) {
+        for _ in 0..(seconds / FRAME_TIME).ceil() as usize {
+            app.update();
+        }
     }
-    if keyboard_input.just_pressed(KeyCode::F6) {
-        ship.lives += 1;
+
+    /// Presses and releases `key` over one frame
+    fn press(app: &mut App, key: KeyCode) {
+        app.world.resource_mut::<Input<KeyCode>>().press(key);
+        app.update();
+        let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+        keyboard.release(key);
+        keyboard.clear();
+    }
+
+    fn ships(app: &mut App) -> Vec<Entity> {
+        app.world
+            .query_filtered::<Entity, With<Ship>>()
+            .iter(&app.world)
+            .collect()
+    }
+
+    #[test]
+    fn new_game_goes_through_load_level_into_game() {
+        let mut app = test_app();
+        update_until(&mut app, AppState::Title);
+
+        press(&mut app, KeyCode::Space);
+        update_until(&mut app, AppState::ShipSelect);
+
+        // Launching from the ship select saves the profile, so the test launches by itself
+        go_to(&mut app, AppState::NewGame);
+        update_until(&mut app, AppState::LoadLevel);
+        update_until(&mut app, AppState::InGame);
+
+        let ships = ships(&mut app);
+        assert_eq!(ships.len(), 1);
+        let ship = app.world.entity(ships[0]);
+        assert_eq!(ship.get::<Ship>().unwrap().lives, 3);
+        assert_eq!(ship.get::<Transform>().unwrap().translation, Vec3::ZERO);
+    }
+
+    #[test]
+    fn destroyed_ship_respawns_with_a_life_less() {
+        let mut app = test_app();
+        go_to(&mut app, AppState::NewGame);
+        update_until(&mut app, AppState::InGame);
+        let ship_entity = ships(&mut app)[0];
+        app.world
+            .get_mut::<Transform>(ship_entity)
+            .unwrap()
+            .translation = Vec3::new(100.0, 50.0, 0.0);
+
+        app.world.send_event(plugins::DamageEvent {
+            target: ship_entity,
+            source: None,
+            amount: SHIP_MAX_HEALTH,
+            cause: plugins::DamageCause::Collision,
+        });
+        run_for(&mut app, SHIP_RESPAWN_DELAY / 2.0);
+        let ship = app.world.entity(ship_entity);
+        assert_eq!(ship.get::<Ship>().unwrap().lives, 2);
+        assert_eq!(ship.get::<Visibility>(), Some(&Visibility::Hidden));
+
+        run_for(&mut app, SHIP_RESPAWN_DELAY);
+        let ship = app.world.entity(ship_entity);
+        assert_eq!(ship.get::<Ship>().unwrap().lives, 2);
+        assert_eq!(ship.get::<Visibility>(), Some(&Visibility::Visible));
+        assert_eq!(ship.get::<Transform>().unwrap().translation, Vec3::ZERO);
+        let health = ship.get::<Health>().unwrap();
+        assert_eq!(health.current, health.max);
+    }
+
+    #[test]
+    fn new_game_resets_the_run() {
+        let mut app = test_app();
+        app.insert_resource(Score(1200))
+            .insert_resource(Level(7))
+            .insert_resource(Minerals(55));
+        app.world.resource_mut::<RunStats>().levels_cleared = 7;
+        let old_ship = app.world.spawn(Ship::default()).id();
+
+        go_to(&mut app, AppState::NewGame);
+        app.update();
+
+        assert_eq!(app.world.resource::<Score>().0, 0);
+        assert_eq!(app.world.resource::<Level>().0, 0);
+        assert_eq!(app.world.resource::<Minerals>().0, 0);
+        assert_eq!(app.world.resource::<RunStats>().levels_cleared, 0);
+        // Loading the first level spawns a new ship in place of the old one
+        assert!(app.world.get_entity(old_ship).is_none());
+        assert_eq!(ships(&mut app).len(), 1);
+    }
+
+    #[test]
+    fn level_entities_despawn_on_leaving_the_game() {
+        let mut app = test_app();
+        go_to(&mut app, AppState::InGame);
+        let level_entity = app.world.spawn(LevelEntity).id();
+        let other_entity = app.world.spawn(SpatialBundle::default()).id();
+
+        go_to(&mut app, AppState::LevelSummary);
+
+        assert!(app.world.get_entity(level_entity).is_none());
+        assert!(app.world.get_entity(other_entity).is_some());
+    }
+
+    proptest! {
+        /// Nothing moves more than a screen in a frame, so that is as far out as things get
+        #[test]
+        fn wrapped_positions_stay_on_screen(x in -1200.0f32..1200.0, y in -720.0f32..720.0) {
+            let mut translation = Vec3::new(x, y, 0.0);
+            wrap(&mut translation);
+            prop_assert!((-400.0..=400.0).contains(&translation.x));
+            prop_assert!((-240.0..=240.0).contains(&translation.y));
+        }
+
+        #[test]
+        fn wrapping_keeps_on_screen_positions(x in -400.0f32..=400.0, y in -240.0f32..=240.0) {
+            let mut translation = Vec3::new(x, y, 0.0);
+            wrap(&mut translation);
+            prop_assert_eq!(translation, Vec3::new(x, y, 0.0));
+        }
     }
 }
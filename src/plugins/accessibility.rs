@@ -0,0 +1,122 @@
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, Settings};
+use crate::resources::Level;
+use crate::AppState;
+use bevy::{
+    a11y::{
+        accesskit::{Live, NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    prelude::*,
+};
+
+/// Something worth telling a screen reader user about, mirroring text shown on screen
+#[derive(Event, Clone, Debug)]
+pub struct Announcement(pub String);
+
+/// Live region that screen readers read out whenever its text changes
+#[derive(Component)]
+struct Announcer;
+
+/// Hands the selected menu entry and announcements over to the platform's accessibility
+/// API when narration is turned on in the settings
+pub struct AccessibilityPlugin;
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Announcement>()
+            .add_systems(Startup, add_announcer)
+            .add_systems(OnEnter(AppState::LoadLevel), announce_level)
+            .add_systems(OnEnter(AppState::GameOver), announce_game_over)
+            // After the menus have updated their texts for this frame
+            .add_systems(PostUpdate, (menu_focus_system, announcement_system));
+    }
+}
+
+fn add_announcer(mut commands: Commands) {
+    let mut node = NodeBuilder::new(Role::StaticText);
+    node.set_live(Live::Assertive);
+    commands.spawn((AccessibilityNode(node), Announcer));
+}
+
+fn announce_level(
+    level: Res<Level>,
+    localization: Res<Localization>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    announcements.send(Announcement(
+        localization.format("Level {n}", &[("n", &level.number())]),
+    ));
+}
+
+fn announce_game_over(
+    localization: Res<Localization>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    announcements.send(Announcement(localization.get("Game over").to_string()));
+}
+
+fn announcement_system(
+    settings: Res<Settings>,
+    mut announcements: EventReader<Announcement>,
+    mut announcer_query: Query<&mut AccessibilityNode, With<Announcer>>,
+) {
+    if !settings.narration {
+        announcements.clear();
+        return;
+    }
+    for Announcement(text) in announcements.iter() {
+        info!("Narration: {text}");
+        for mut node in announcer_query.iter_mut() {
+            node.set_name(text.as_str());
+        }
+    }
+}
+
+/// Moves the accessibility focus to the selected menu entry, labeled with its current text
+fn menu_focus_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut focus: ResMut<Focus>,
+    menu_query: Query<&MenuNavigation>,
+    entries_query: Query<(Entity, &MenuEntry, &Text)>,
+    mut last_label: Local<Option<(Entity, String)>>,
+) {
+    // Focus can't be left on an entry that went away with its menu
+    if let Some(entity) = last_label.as_ref().map(|(entity, _)| *entity) {
+        if !settings.narration || !entries_query.contains(entity) {
+            if **focus == Some(entity) {
+                **focus = None;
+            }
+            *last_label = None;
+        }
+    }
+    if !settings.narration {
+        return;
+    }
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    let Some((entity, _, text)) = entries_query
+        .iter()
+        .find(|(_, entry, _)| menu.is_selected(entry.0))
+    else {
+        return;
+    };
+    let text: String = text
+        .sections
+        .iter()
+        .map(|section| section.value.as_str())
+        .collect();
+    // Leaves out the selection marker and the shop's tree lines
+    let label = text
+        .trim_start_matches(|c: char| c.is_whitespace() || "├└>".contains(c))
+        .to_string();
+    if last_label.as_ref() == Some(&(entity, label.clone())) {
+        return;
+    }
+    info!("Narration: {label}");
+    let mut node = NodeBuilder::new(Role::MenuItem);
+    node.set_name(label.as_str());
+    commands.entity(entity).insert(AccessibilityNode(node));
+    **focus = Some(entity);
+    *last_label = Some((entity, label));
+}
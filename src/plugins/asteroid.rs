@@ -0,0 +1,180 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    plugins::{Died, Localization},
+    resources::*,
+    utils::*,
+    AppState, GameSet,
+};
+use bevy::prelude::*;
+use rand::random;
+
+/// Destructions that followed each other closely enough to count as a chain reaction
+#[derive(Default, Resource)]
+struct Chain {
+    length: u32,
+    last_destroyed: f32,
+}
+
+pub struct AsteroidPlugin;
+impl Plugin for AsteroidPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Chain>()
+            .add_systems(
+                Update,
+                asteroid_hit_system
+                    .in_set(GameSet::Simulation)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                asteroid_split_system
+                    .in_set(GameSet::Resolution)
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+fn asteroid_hit_system(
+    mut asteroids_query: Query<(&mut Moving, &CollisionShape, &Transform), With<Asteroid>>,
+) {
+    let mut pairs = asteroids_query.iter_combinations_mut();
+    while let Some([(mut a_moving, a_shape, a_transform), (mut b_moving, b_shape, b_transform)]) =
+        pairs.fetch_next()
+    {
+        if a_shape.intersects(b_shape) {
+            let a_position = a_transform.translation.truncate();
+            let b_position = b_transform.translation.truncate();
+            let diff = a_position - b_position;
+            let epsilon = (a_moving.velocity - b_moving.velocity) * 0.01;
+            if diff.length_squared() >= (diff + epsilon).length_squared() {
+                let direction = diff.normalize();
+                a_moving.velocity = direction * a_moving.velocity.length();
+                b_moving.velocity = -direction * b_moving.velocity.length();
+            }
+        }
+    }
+}
+
+fn asteroid_split_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    asteroids: Query<(Entity, &Asteroid, &Transform)>,
+    sprite_sheets: Res<SpriteSheets>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    mut run_stats: ResMut<RunStats>,
+    level: Res<Level>,
+    game_assets: Res<GameAssets>,
+    mut chain: ResMut<Chain>,
+    time: Res<Time>,
+    localization: Res<Localization>,
+) {
+    let mut live_asteroids = asteroids.iter().count();
+    for event in died_events.iter() {
+        if let Ok((asteroid_entity, asteroid, transform)) = asteroids.get(event.entity) {
+            live_asteroids = live_asteroids.saturating_sub(1);
+            // Over the budget, splits leave fragments out and pay out what they would
+            // have been worth, their own fragments included
+            let frag_count = level.asteroid_frag_count();
+            let fragments = match asteroid.size.smaller() {
+                Some(_) => frag_count.min(MAX_LIVE_ASTEROIDS.saturating_sub(live_asteroids) as u32),
+                None => 0,
+            };
+            live_asteroids += fragments as usize;
+            let skipped_score = asteroid.size.smaller().map_or(0, |size| {
+                (frag_count - fragments) * asteroid_total_score(size, frag_count)
+            });
+            let score_delta = (asteroid_score(asteroid.size) + skipped_score) * score_multiplier.0;
+            score.increase(score_delta);
+            let now = time.elapsed_seconds();
+            if event.cause.chains() && now - chain.last_destroyed <= CHAIN_WINDOW {
+                chain.length += 1;
+                let bonus = CHAIN_BONUS * (chain.length - 1) * score_multiplier.0;
+                score.increase(bonus);
+                commands.spawn(GameNotificationBundle::new(
+                    localization.format("CHAIN x{length}!", &[("length", &chain.length)]),
+                    game_assets.font.clone(),
+                    transform.translation.truncate() + Vec2::new(0.0, 20.0),
+                    16.0 + 4.0 * chain.length.min(6) as f32,
+                    1.5,
+                ));
+            } else {
+                chain.length = 1;
+            }
+            chain.last_destroyed = now;
+            run_stats.asteroids_destroyed[asteroid.size as usize] += 1;
+            commands.spawn(ScorePopupBundle::new(
+                score_delta,
+                game_assets.font.clone(),
+                transform.translation.truncate(),
+                20.0,
+            ));
+            commands.spawn(CoronaParticleBundle::new(
+                transform.translation.truncate(),
+                asteroid.size.radius() / AsteroidSize::Large.radius(),
+                &sprite_sheets.particles,
+            ));
+            commands.entity(asteroid_entity).despawn();
+            if matches!(asteroid.size, AsteroidSize::Large) {
+                let position = transform.translation.truncate();
+                for debris in DebrisBundle::burst(&sprite_sheets, asteroid.variant, position) {
+                    commands.spawn(debris);
+                }
+            }
+            if random::<f32>() < MINERAL_DROP_CHANCE {
+                let velocity = Vec2::from_angle(random::<f32>() * TAU) * lerp(10.0, 40.0, random());
+                commands.spawn(MineralBundle::new(
+                    sprite_sheets.as_ref(),
+                    asteroid.variant,
+                    asteroid.size as u32 + 1,
+                    transform.translation.truncate(),
+                    velocity,
+                ));
+            }
+            if let Some(size) = asteroid.size.smaller() {
+                let direction = (transform.rotation * transform.translation)
+                    .truncate()
+                    .normalize();
+                let data = (0..fragments)
+                    .map(|i| i as f32 * TAU / fragments as f32)
+                    .map(|angle| direction.rotate(Vec2::from_angle(angle)));
+
+                let parent_position = transform.translation.truncate();
+                let spinning_speed = random::<f32>() - 0.5;
+                for dir in data {
+                    let position = parent_position + dir * 5.0;
+                    let velocity = dir * 30.0;
+                    commands.spawn(AsteroidBundle::new(
+                        sprite_sheets.as_ref(),
+                        asteroid.variant,
+                        size,
+                        position,
+                        velocity,
+                        spinning_speed,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Score for destroying an asteroid and everything it splits into
+fn asteroid_total_score(size: AsteroidSize, frag_count: u32) -> u32 {
+    asteroid_score(size)
+        + size.smaller().map_or(0, |smaller| {
+            frag_count * asteroid_total_score(smaller, frag_count)
+        })
+}
+
+fn asteroid_score(size: AsteroidSize) -> u32 {
+    match size {
+        AsteroidSize::Tiny => 50,
+        AsteroidSize::Small => 100,
+        AsteroidSize::Medium => 150,
+        AsteroidSize::Large => 200,
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{components::*, constants::*, input::InputState, AppState, GameSet};
+use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+pub struct BotPlugin;
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            ship_bot_system
+                .before(GameSet::Control)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Flies its ship by writing the ship's own `InputState` instead of reading the player's
+#[derive(Component)]
+pub struct ShipBot;
+
+/// Something the bot has to shoot or get out of the way of
+struct Threat {
+    position: Vec2,
+    velocity: Vec2,
+    radius: f32,
+}
+
+/// Steers away from whatever is about to hit the ship, otherwise aims at the closest
+/// threat and shoots once lined up
+fn ship_bot_system(
+    mut bots_query: Query<(&mut InputState, &Ship, &Transform, &Moving), With<ShipBot>>,
+    threats_query: Query<
+        (&Transform, Option<&Moving>, &CollisionShape),
+        (Or<(With<Asteroid>, With<Enemy>)>, Without<Ship>),
+    >,
+) {
+    let threats: Vec<_> = threats_query
+        .iter()
+        .map(|(transform, moving, shape)| Threat {
+            position: transform.translation.truncate(),
+            velocity: moving.map_or(Vec2::ZERO, |moving| moving.velocity),
+            radius: match shape.shape {
+                Shape::Circle { radius, .. } => radius * transform.scale.x,
+                Shape::Line { .. } => 0.0,
+            },
+        })
+        .collect();
+
+    for (mut input, ship, transform, moving) in bots_query.iter_mut() {
+        *input = InputState::default();
+        if ship.respawn_delay > 0.0 {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let heading = (transform.rotation * Vec3::Y).truncate();
+
+        // Closest approach of each threat over the next moment, relative to the ship
+        let danger = threats
+            .iter()
+            .filter_map(|threat| {
+                let offset = threat.position - position;
+                let relative_velocity = threat.velocity - moving.velocity;
+                let speed_squared = relative_velocity.length_squared();
+                let time = if speed_squared > 0.0 {
+                    (-offset.dot(relative_velocity) / speed_squared).clamp(0.0, BOT_LOOKAHEAD)
+                } else {
+                    0.0
+                };
+                let miss = offset + relative_velocity * time;
+                (miss.length() < threat.radius + BOT_SAFE_DISTANCE).then_some((time, miss))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let (desired, evading) = match danger {
+            Some((time, miss)) => {
+                if time < BOT_HYPERSPACE_TIME && ship.invulnerability <= 0.0 {
+                    input.hyperspace = ship.hyperspace_jumps > 0;
+                }
+                // Out of the way of the threat's path, towards whichever side it misses on
+                let away = if miss == Vec2::ZERO {
+                    heading.perp()
+                } else {
+                    -miss
+                };
+                (away, true)
+            }
+            None => {
+                let target = threats.iter().min_by(|a, b| {
+                    a.position
+                        .distance_squared(position)
+                        .total_cmp(&b.position.distance_squared(position))
+                });
+                let Some(target) = target else {
+                    continue;
+                };
+                let distance = target.position.distance(position);
+                let lead = target.velocity * distance / BOT_PROJECTILE_SPEED;
+                let aim = target.position + lead - position;
+                input.fire =
+                    distance < BOT_FIRE_RANGE && heading.angle_between(aim).abs() < BOT_FIRE_ANGLE;
+                (aim, false)
+            }
+        };
+        if desired == Vec2::ZERO {
+            continue;
+        }
+
+        let turn = (heading.angle_between(desired) + PI).rem_euclid(TAU) - PI;
+        input.left = turn > BOT_TURN_TOLERANCE;
+        input.right = turn < -BOT_TURN_TOLERANCE;
+        input.throttle = evading
+            && turn.abs() < BOT_FIRE_ANGLE * 2.0
+            && moving.velocity.length() < BOT_MAX_SPEED;
+    }
+}
@@ -1,25 +1,163 @@
-use bevy::{prelude::*, render::camera::Viewport};
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    input::mouse::MouseWheel,
+    prelude::*,
+    render::{camera::Viewport, view::RenderLayers},
+};
 
-use crate::constants::*;
+use crate::{
+    components::Ship,
+    constants::*,
+    input::InputState,
+    plugins::{Settings, ShipBot},
+    AppState,
+};
+use rand::random;
+
+const SPECTATOR_PAN_SPEED: f32 = 300.0;
+const SPECTATOR_ZOOM_STEP: f32 = 1.1;
+const SPECTATOR_MIN_ZOOM: f32 = 0.25;
+const SPECTATOR_MAX_ZOOM: f32 = 4.0;
+
+/// Render layer of everything drawn around the game viewport
+const LETTERBOX_LAYER: u8 = 1;
+const LETTERBOX_STARS: usize = 400;
+/// Half the size of the area the letterbox stars are scattered over, enough for 4K windows
+const LETTERBOX_STAR_FIELD: Vec2 = Vec2::new(1920.0, 1080.0);
+const LETTERBOX_FRAME_WIDTH: f32 = 3.0;
+const LETTERBOX_FRAME_COLOR: Color = Color::rgb(0.15, 0.2, 0.35);
+
+/// The camera showing the game itself
+#[derive(Component)]
+struct GameCamera;
+
+/// Covers the game viewport in the letterbox camera so that its stars only show outside of it
+#[derive(Component)]
+struct LetterboxPlayArea;
+
+/// Outline around the game viewport in the letterbox camera
+#[derive(Component)]
+struct LetterboxFrame;
+
+/// Free camera for watching the bot play, detached from the fixed game viewport
+#[derive(Resource)]
+struct Spectator {
+    enabled: bool,
+    zoom: f32,
+}
+
+impl Default for Spectator {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zoom: 1.0,
+        }
+    }
+}
+
+/// Short-lived camera motion layered on top of the fixed game view
+#[derive(Resource, Default)]
+pub struct CameraEffects {
+    /// Zoom punch time left in seconds
+    punch: f32,
+    /// Shake strength from 0 to 1, squared for the actual shake distance
+    trauma: f32,
+    /// Smoothed camera offset towards the ship
+    follow: Vec2,
+}
+
+impl CameraEffects {
+    pub fn punch(&mut self) {
+        self.punch = CAMERA_PUNCH_DURATION;
+    }
+    pub fn shake(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
 
 fn add_camera(mut commands: Commands, window_query: Query<&Window>) {
     let window = window_query.single();
-    commands.spawn(Camera2dBundle {
-        projection: OrthographicProjection {
-            near: -1.0,
-            scaling_mode: bevy::render::camera::ScalingMode::AutoMin {
-                min_width: GAME_WIDTH as f32,
-                min_height: GAME_HEIGHT as f32,
-            },
-            area: Rect::from_center_size(Vec2::ZERO, Vec2::new(800.0, 480.0)),
+    commands.spawn((
+        Camera2dBundle {
+            projection: OrthographicProjection {
+                near: -1.0,
+                scaling_mode: bevy::render::camera::ScalingMode::AutoMin {
+                    min_width: GAME_WIDTH as f32,
+                    min_height: GAME_HEIGHT as f32,
+                },
+                area: Rect::from_center_size(Vec2::ZERO, Vec2::new(800.0, 480.0)),
+                ..Default::default()
+            },
+            camera: Camera {
+                viewport: Some(window_to_viewport(window, GAME_WIDTH, GAME_HEIGHT)),
+                ..default()
+            },
+            // The letterbox camera clears the window and draws the play area background
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::None,
+            },
             ..Default::default()
         },
-        camera: Camera {
-            viewport: Some(window_to_viewport(window, GAME_WIDTH, GAME_HEIGHT)),
+        GameCamera,
+    ));
+}
+/// Draws a dim starfield and a frame around the game viewport, behind the game camera
+fn add_letterbox(mut commands: Commands, clear_color: Res<ClearColor>) {
+    let layer = RenderLayers::layer(LETTERBOX_LAYER);
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
+            },
+            ..default()
+        },
+        UiCameraConfig { show_ui: false },
+        layer,
+    ));
+    for _ in 0..LETTERBOX_STARS {
+        let position = (Vec2::new(random(), random()) * 2.0 - Vec2::ONE) * LETTERBOX_STAR_FIELD;
+        let brightness = 0.2 + random::<f32>() * 0.3;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(brightness, brightness, brightness),
+                    custom_size: Some(Vec2::splat(1.0 + random::<f32>())),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            layer,
+        ));
+    }
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: LETTERBOX_FRAME_COLOR,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
             ..default()
         },
-        ..Default::default()
-    });
+        LetterboxFrame,
+        layer,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: clear_color.0,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 2.0),
+            ..default()
+        },
+        LetterboxPlayArea,
+        layer,
+    ));
 }
 fn window_to_viewport(window: &Window, width: u32, height: u32) -> Viewport {
     let physical_size = UVec2::new(
@@ -40,15 +178,210 @@ fn window_to_viewport(window: &Window, width: u32, height: u32) -> Viewport {
         ..default()
     }
 }
-fn viewport_system(mut camera_query: Query<&mut Camera>, window_query: Query<&Window>) {
+fn viewport_system(
+    mut camera_query: Query<&mut Camera, With<GameCamera>>,
+    window_query: Query<&Window>,
+    spectator: Res<Spectator>,
+) {
     let mut camera = camera_query.single_mut();
     let window = window_query.single();
-    camera.viewport = Some(window_to_viewport(window, GAME_WIDTH, GAME_HEIGHT));
+    // The spectator gets the whole window to look around in
+    camera.viewport = if spectator.enabled {
+        None
+    } else {
+        Some(window_to_viewport(window, GAME_WIDTH, GAME_HEIGHT))
+    };
+}
+/// Fits the play area background and frame to the game viewport
+fn letterbox_system(
+    camera_query: Query<&Camera, With<GameCamera>>,
+    window_query: Query<&Window>,
+    mut play_area_query: Query<&mut Sprite, (With<LetterboxPlayArea>, Without<LetterboxFrame>)>,
+    mut frame_query: Query<&mut Sprite, (With<LetterboxFrame>, Without<LetterboxPlayArea>)>,
+) {
+    let camera = camera_query.single();
+    let window = window_query.single();
+    let size = camera
+        .viewport
+        .as_ref()
+        .map_or(Vec2::new(window.width(), window.height()), |viewport| {
+            viewport.physical_size.as_vec2() / window.scale_factor() as f32
+        });
+    for mut sprite in play_area_query.iter_mut() {
+        sprite.custom_size = Some(size);
+    }
+    for mut sprite in frame_query.iter_mut() {
+        sprite.custom_size = Some(size + Vec2::splat(LETTERBOX_FRAME_WIDTH * 2.0));
+    }
+}
+/// Hands the ship over to the bot while spectating, and back when done
+fn spectator_toggle_system(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mut spectator: ResMut<Spectator>,
+    ships_query: Query<Entity, With<Ship>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F8) {
+        return;
+    }
+    spectator.enabled = !spectator.enabled;
+    for ship_entity in ships_query.iter() {
+        if spectator.enabled {
+            commands
+                .entity(ship_entity)
+                .insert((ShipBot, InputState::default()));
+        } else {
+            commands
+                .entity(ship_entity)
+                .remove::<(ShipBot, InputState)>();
+        }
+    }
+    if !spectator.enabled {
+        reset_camera(&mut spectator, &mut camera_query);
+    }
+}
+fn spectator_camera_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut spectator: ResMut<Spectator>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if !spectator.enabled {
+        mouse_wheel.clear();
+        return;
+    }
+    let mut zoom_steps: f32 = mouse_wheel.iter().map(|event| -event.y.signum()).sum();
+    if keyboard.just_pressed(KeyCode::Minus) {
+        zoom_steps += 1.0;
+    }
+    if keyboard.just_pressed(KeyCode::Equals) {
+        zoom_steps -= 1.0;
+    }
+    spectator.zoom = (spectator.zoom * SPECTATOR_ZOOM_STEP.powf(zoom_steps))
+        .clamp(SPECTATOR_MIN_ZOOM, SPECTATOR_MAX_ZOOM);
+
+    let pan = [
+        (KeyCode::Left, Vec2::NEG_X),
+        (KeyCode::Right, Vec2::X),
+        (KeyCode::Up, Vec2::Y),
+        (KeyCode::Down, Vec2::NEG_Y),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keyboard.pressed(*key))
+    .map(|(_, direction)| direction)
+    .sum::<Vec2>();
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        projection.scale = spectator.zoom;
+        transform.translation +=
+            (pan * SPECTATOR_PAN_SPEED * spectator.zoom * time.delta_seconds()).extend(0.0);
+    }
+}
+/// Combines the zoom punch, ship follow and screen shake into the camera transform,
+/// leaving out whichever ones are turned off in the settings
+fn camera_effects_system(
+    mut effects: ResMut<CameraEffects>,
+    spectator: Res<Spectator>,
+    settings: Res<Settings>,
+    ships_query: Query<&Transform, (With<Ship>, Without<GameCamera>)>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if spectator.enabled {
+        return;
+    }
+    let delta = time.delta_seconds();
+    effects.punch = (effects.punch - delta).max(0.0);
+    effects.trauma = (effects.trauma - CAMERA_SHAKE_DECAY * delta).max(0.0);
+
+    let follow_target = match ships_query.iter().next() {
+        Some(transform) if settings.camera_follow => {
+            transform.translation.truncate() * CAMERA_FOLLOW_AMOUNT
+        }
+        _ => Vec2::ZERO,
+    };
+    effects.follow = effects
+        .follow
+        .lerp(follow_target, (CAMERA_FOLLOW_SPEED * delta).min(1.0));
+    let shake = if settings.screen_shake && !settings.reduced_flashing {
+        let direction = Vec2::new(random::<f32>() * 2.0 - 1.0, random::<f32>() * 2.0 - 1.0);
+        direction * effects.trauma * effects.trauma * CAMERA_SHAKE_DISTANCE
+    } else {
+        Vec2::ZERO
+    };
+    // Snaps in at once and eases back out
+    let punch = if settings.zoom_punch {
+        (effects.punch / CAMERA_PUNCH_DURATION).powi(2) * CAMERA_PUNCH_ZOOM
+    } else {
+        0.0
+    };
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        let offset = effects.follow + shake;
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+        projection.scale = 1.0 - punch;
+    }
+}
+fn reset_camera_effects(
+    mut effects: ResMut<CameraEffects>,
+    mut spectator: ResMut<Spectator>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+) {
+    *effects = CameraEffects::default();
+    reset_camera(&mut spectator, &mut camera_query);
+}
+fn reset_camera(
+    spectator: &mut Spectator,
+    camera_query: &mut Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+) {
+    spectator.zoom = 1.0;
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        projection.scale = 1.0;
+    }
+}
+fn stop_spectating(
+    mut commands: Commands,
+    mut spectator: ResMut<Spectator>,
+    ships_query: Query<Entity, With<ShipBot>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+) {
+    if !spectator.enabled {
+        return;
+    }
+    spectator.enabled = false;
+    for ship_entity in ships_query.iter() {
+        commands
+            .entity(ship_entity)
+            .remove::<(ShipBot, InputState)>();
+    }
+    reset_camera(&mut spectator, &mut camera_query);
 }
 pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, add_camera)
-            .add_systems(Update, viewport_system);
+        app.init_resource::<Spectator>()
+            .init_resource::<CameraEffects>()
+            .add_systems(Startup, (add_camera, add_letterbox))
+            .add_systems(
+                Update,
+                (viewport_system, letterbox_system.after(viewport_system)),
+            )
+            .add_systems(
+                Update,
+                (
+                    spectator_toggle_system,
+                    spectator_camera_system.after(spectator_toggle_system),
+                    camera_effects_system.after(spectator_toggle_system),
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                OnExit(AppState::InGame),
+                (stop_spectating, reset_camera_effects.after(stop_spectating)),
+            );
     }
 }
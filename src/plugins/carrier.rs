@@ -0,0 +1,318 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{CameraEffects, Died},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::random;
+use std::f32::consts::{PI, TAU};
+
+pub struct CarrierPlugin;
+impl Plugin for CarrierPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CarrierSpawnTimer>()
+            .add_systems(
+                Update,
+                (
+                    carrier_spawn_system,
+                    carrier_launch_system,
+                    minion_steering_system,
+                    carrier_destroy_system,
+                    minion_destroy_system,
+                    carrier_explosion_system,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnEnter(AppState::LoadLevel), reset_carrier_spawn_timer);
+    }
+}
+
+/// Slow, heavily armored ship releasing minions until it is destroyed
+#[derive(Component)]
+struct Carrier {
+    launch_delay: f32,
+}
+
+/// Small ship launched by a carrier, trying to ram the player
+#[derive(Component)]
+struct Minion {
+    carrier: Entity,
+    angle: f32,
+}
+
+/// Wreck of a carrier going off in a chain of explosions
+#[derive(Component)]
+struct CarrierExplosion {
+    stages_left: u32,
+    delay: f32,
+}
+
+/// Seconds until the next carrier, if the level has any
+#[derive(Default, Resource)]
+struct CarrierSpawnTimer(Option<f32>);
+
+fn reset_carrier_spawn_timer(mut timer: ResMut<CarrierSpawnTimer>, level: Res<Level>) {
+    timer.0 = level.carrier_spawn_interval();
+}
+
+fn carrier_spawn_system(
+    mut commands: Commands,
+    mut timer: ResMut<CarrierSpawnTimer>,
+    carriers_query: Query<(), With<Carrier>>,
+    level: Res<Level>,
+    mode: Res<GameMode>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+) {
+    // Survival waves bring their own enemies
+    if *mode == GameMode::Survival {
+        return;
+    }
+    let Some(remaining) = timer.0.as_mut() else {
+        return;
+    };
+    // Only one carrier at a time
+    if !carriers_query.is_empty() {
+        return;
+    }
+    *remaining -= time.delta_seconds();
+    if *remaining > 0.0 {
+        return;
+    }
+    *remaining += level.carrier_spawn_interval().unwrap_or(f32::INFINITY);
+
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let side = if random() { 1.0 } else { -1.0 };
+    let position = Vec2::new(side * span.x, lerp(-span.y, span.y, random()) / 2.0);
+    let transform =
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(CARRIER_SCALE));
+    commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas: sprite_sheets.ufo.atlas.clone(),
+            transform,
+            sprite: TextureAtlasSprite {
+                index: sprite_sheets.ufo.ship[0],
+                color: Color::rgb(0.7, 0.5, 0.9),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Carrier {
+            launch_delay: CARRIER_LAUNCH_INTERVAL / 2.0,
+        },
+        Enemy,
+        Health::new(CARRIER_HEALTH),
+        DropTable {
+            entries: vec![(DropEntry::AnyPowerup, 1.0)],
+            nothing: 0.0,
+            minerals: 20,
+        },
+        Moving {
+            velocity: Vec2::new(-side * CARRIER_SPEED, 0.0),
+            ..Default::default()
+        },
+        Wrapping,
+        LevelEntity,
+        CollisionShape::new(
+            Shape::Circle {
+                center: Vec2::ZERO,
+                radius: CARRIER_RADIUS,
+            },
+            transform,
+        ),
+    ));
+}
+
+/// Releases a minion every so often, as long as the carrier's flight isn't full
+fn carrier_launch_system(
+    mut commands: Commands,
+    mut carriers_query: Query<(Entity, &mut Carrier, &Transform)>,
+    minions_query: Query<&Minion>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (carrier_entity, mut carrier, transform) in carriers_query.iter_mut() {
+        carrier.launch_delay -= time.delta_seconds() * time_scale.0;
+        if carrier.launch_delay > 0.0 {
+            continue;
+        }
+        carrier.launch_delay = CARRIER_LAUNCH_INTERVAL;
+        let minions = minions_query
+            .iter()
+            .filter(|minion| minion.carrier == carrier_entity)
+            .count();
+        if minions >= CARRIER_MAX_MINIONS {
+            continue;
+        }
+        let angle = random::<f32>() * TAU;
+        let position = transform.translation.truncate()
+            + Vec2::from_angle(angle).rotate(Vec2::Y) * CARRIER_RADIUS * CARRIER_SCALE;
+        let transform = Transform::from_translation(position.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(angle))
+            .with_scale(Vec3::splat(MINION_SCALE));
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: sprite_sheets.ship.atlas.clone(),
+                transform,
+                sprite: TextureAtlasSprite {
+                    index: sprite_sheets.ship.rapid,
+                    color: Color::rgb(0.8, 0.5, 1.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Minion {
+                carrier: carrier_entity,
+                angle,
+            },
+            Enemy,
+            Health::new(MINION_HEALTH),
+            ContactDamage(MINION_CONTACT_DAMAGE),
+            Moving::default(),
+            Wrapping,
+            LevelEntity,
+            CollisionShape::new(
+                Shape::Circle {
+                    center: Vec2::ZERO,
+                    radius: MINION_RADIUS,
+                },
+                transform,
+            ),
+        ));
+    }
+}
+
+fn minion_steering_system(
+    mut minions_query: Query<(&mut Minion, &mut Transform, &mut Moving)>,
+    ships_query: Query<&Transform, (With<Ship>, Without<Minion>)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta = time.delta_seconds() * time_scale.0;
+    let target = ships_query.get_single().ok();
+    for (mut minion, mut transform, mut moving) in minions_query.iter_mut() {
+        if let Some(ship_transform) = target {
+            let to_ship = (ship_transform.translation - transform.translation).truncate();
+            if to_ship != Vec2::ZERO {
+                let turn =
+                    (Vec2::Y.angle_between(to_ship) - minion.angle + PI).rem_euclid(TAU) - PI;
+                let max_turn = MINION_TURN_RATE * delta;
+                minion.angle += turn.clamp(-max_turn, max_turn);
+            }
+        }
+        transform.rotation = Quat::from_rotation_z(minion.angle);
+        moving.velocity = Vec2::from_angle(minion.angle).rotate(Vec2::Y) * MINION_SPEED;
+    }
+}
+
+fn carrier_destroy_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    carriers_query: Query<&Transform, With<Carrier>>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = carriers_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+            CarrierExplosion {
+                stages_left: CARRIER_EXPLOSION_STAGES,
+                delay: 0.0,
+            },
+            LevelEntity,
+        ));
+        let score_delta = CARRIER_SCORE * score_multiplier.0;
+        score.increase(score_delta);
+        commands.spawn(ScorePopupBundle::new(
+            score_delta,
+            game_assets.font.clone(),
+            position,
+            30.0,
+        ));
+        commands.entity(event.entity).despawn();
+    }
+}
+
+/// Goes off in smaller blasts around the wreck before the final one
+fn carrier_explosion_system(
+    mut commands: Commands,
+    mut explosions_query: Query<(Entity, &mut CarrierExplosion, &Transform)>,
+    sprite_sheets: Res<SpriteSheets>,
+    mut camera_effects: ResMut<CameraEffects>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (entity, mut explosion, transform) in explosions_query.iter_mut() {
+        explosion.delay -= time.delta_seconds() * time_scale.0;
+        if explosion.delay > 0.0 {
+            continue;
+        }
+        explosion.delay = CARRIER_EXPLOSION_INTERVAL;
+        explosion.stages_left -= 1;
+        let center = transform.translation.truncate();
+        camera_effects.shake(CARRIER_BLAST_SHAKE);
+        if explosion.stages_left > 0 {
+            let offset = Vec2::from_angle(random::<f32>() * TAU)
+                * random::<f32>()
+                * CARRIER_RADIUS
+                * CARRIER_SCALE;
+            commands.spawn(ExplosionBundle::new(
+                &sprite_sheets.explosion,
+                center + offset,
+            ));
+            commands.spawn(RingParticleBundle::new(
+                center + offset,
+                &sprite_sheets.particles,
+            ));
+        } else {
+            camera_effects.punch();
+            commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, center));
+            commands.spawn(WaveParticleBundle::new(center, &sprite_sheets.particles));
+            commands.spawn(CoronaParticleBundle::new(
+                center,
+                CARRIER_SCALE,
+                &sprite_sheets.particles,
+            ));
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn minion_destroy_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    minions_query: Query<&Transform, With<Minion>>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = minions_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
+        for _ in 0..6 {
+            let velocity = Vec2::from_angle(random::<f32>() * TAU) * lerp(40.0, 120.0, random());
+            commands.spawn(SparkParticleBundle::new(
+                position,
+                velocity,
+                -velocity,
+                &sprite_sheets.particles,
+            ));
+        }
+        score.increase(MINION_SCORE * score_multiplier.0);
+        commands.entity(event.entity).despawn();
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{components::*, AppState};
+use bevy::{ecs::query::Has, prelude::*};
+
+pub struct CollisionDebugPlugin;
+impl Plugin for CollisionDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowCollisionShapes>().add_systems(
+            Update,
+            (
+                collision_debug_toggle_system,
+                collision_debug_draw_system
+                    .after(collision_debug_toggle_system)
+                    .run_if(in_state(AppState::InGame)),
+            ),
+        );
+    }
+}
+
+/// Whether collision shapes are drawn over the game, toggled with F9
+#[derive(Default, Resource)]
+struct ShowCollisionShapes(bool);
+
+fn collision_debug_toggle_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut show: ResMut<ShowCollisionShapes>,
+) {
+    if keyboard.just_pressed(KeyCode::F9) {
+        show.0 = !show.0;
+    }
+}
+
+/// Outlines every collision shape as the collision checks see it, the ship's side in
+/// cyan and everything else in red
+fn collision_debug_draw_system(
+    mut gizmos: Gizmos,
+    show: Res<ShowCollisionShapes>,
+    shapes_query: Query<(&CollisionShape, Has<Ship>, Has<ShipProjectile>)>,
+) {
+    if !show.0 {
+        return;
+    }
+    for (shape, is_ship, is_projectile) in shapes_query.iter() {
+        let color = if is_ship || is_projectile {
+            Color::CYAN
+        } else {
+            Color::RED
+        };
+        match shape.global_shape() {
+            Shape::Circle { center, radius } => {
+                gizmos.circle_2d(center, radius, color);
+            }
+            Shape::Line { base, delta, width } => {
+                // Center line, with the edges of its width on both sides
+                let side = delta.perp().normalize_or_zero() * width / 2.0;
+                gizmos.line_2d(base, base + delta, color);
+                for offset in [side, -side] {
+                    gizmos.line_2d(base + offset, base + delta + offset, color.with_a(0.5));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    input::{self, InputState},
+    plugins::{spawn_ufo, MenuNavigationSet, ShipBot, UfoKind},
+    resources::*,
+    AppState, GameSet,
+};
+use bevy::{prelude::*, sprite::Anchor};
+use rand::{random, thread_rng, Rng};
+
+/// Lines of output kept on screen below the prompt
+const CONSOLE_LINES: usize = 12;
+const CONSOLE_HEIGHT: f32 = 240.0;
+
+pub struct ConsolePlugin;
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_systems(Startup, init_console)
+            .add_systems(
+                Update,
+                (
+                    console_toggle_system,
+                    console_input_system.after(console_toggle_system),
+                    console_capture_input
+                        .after(input::update_input_state)
+                        .after(console_toggle_system)
+                        .before(MenuNavigationSet)
+                        .before(GameSet::Control),
+                    console_text_system.after(console_input_system),
+                    god_mode_system,
+                ),
+            );
+    }
+}
+
+/// Drop-down developer console, toggled with the backtick key
+#[derive(Default, Resource)]
+struct Console {
+    open: bool,
+    input: String,
+    output: Vec<String>,
+    history: Vec<String>,
+    /// Position while browsing the history, counting back from the latest command
+    history_index: Option<usize>,
+    god_mode: bool,
+}
+
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.output.push(line.into());
+    }
+}
+
+#[derive(Component)]
+struct ConsoleBackground;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn init_console(mut commands: Commands, game_assets: Res<GameAssets>) {
+    let top = GAME_HEIGHT as f32 / 2.0;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.8),
+                custom_size: Some(Vec2::new(GAME_WIDTH as f32, CONSOLE_HEIGHT)),
+                anchor: Anchor::TopCenter,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, top, 0.9),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ConsoleBackground,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 16.0,
+                    color: Color::GREEN,
+                },
+            ),
+            text_anchor: Anchor::BottomLeft,
+            transform: Transform::from_xyz(
+                -(GAME_WIDTH as f32) / 2.0 + 8.0,
+                top - CONSOLE_HEIGHT + 4.0,
+                0.95,
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ConsoleText,
+    ));
+}
+
+fn console_toggle_system(keyboard: Res<Input<KeyCode>>, mut console: ResMut<Console>) {
+    if keyboard.just_pressed(KeyCode::Grave) {
+        console.open = !console.open;
+    }
+}
+
+/// Keeps typing into the console from also flying the ship or moving around menus
+fn console_capture_input(console: Res<Console>, mut input: ResMut<InputState>) {
+    if console.open {
+        *input = InputState::default();
+    }
+}
+
+fn console_input_system(
+    mut commands: Commands,
+    mut console: ResMut<Console>,
+    mut characters: EventReader<ReceivedCharacter>,
+    keyboard: Res<Input<KeyCode>>,
+    mut ships_query: Query<(
+        Entity,
+        &mut Ship,
+        &mut ShieldEnergy,
+        &Transform,
+        Has<ShipBot>,
+    )>,
+    mut level: ResMut<Level>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    if !console.open {
+        characters.clear();
+        return;
+    }
+    for event in characters.iter() {
+        if !event.char.is_control() && event.char != '`' {
+            console.input.push(event.char);
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if keyboard.just_pressed(KeyCode::Up) && !console.history.is_empty() {
+        let index = console
+            .history_index
+            .map_or(0, |index| (index + 1).min(console.history.len() - 1));
+        console.history_index = Some(index);
+        console.input = console.history[console.history.len() - 1 - index].clone();
+    }
+    if keyboard.just_pressed(KeyCode::Down) {
+        console.history_index = console.history_index.and_then(|index| index.checked_sub(1));
+        console.input = match console.history_index {
+            Some(index) => console.history[console.history.len() - 1 - index].clone(),
+            None => String::new(),
+        };
+    }
+    if !keyboard.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    let line = std::mem::take(&mut console.input);
+    console.history_index = None;
+    if line.trim().is_empty() {
+        return;
+    }
+    console.print(format!("> {line}"));
+    if console.history.last() != Some(&line) {
+        console.history.push(line.clone());
+    }
+
+    let words: Vec<_> = line.split_whitespace().collect();
+    let in_game = *state.get() == AppState::InGame;
+    let result = match words.as_slice() {
+        ["help"] => Ok(
+            "spawn asteroid <tiny|small|medium|large> [count], spawn ufo <large|small|stealth>, \
+             set level <n>, give <weapon|shield|lives|jumps> <n>, god, bot"
+                .to_string(),
+        ),
+        _ if !in_game => Err("Only available in game".to_string()),
+        ["spawn", "asteroid", size, rest @ ..] => {
+            let count = match rest {
+                [] => Ok(1),
+                [count] => count.parse().map_err(|_| format!("Bad count: {count}")),
+                _ => Err("Too many arguments".to_string()),
+            };
+            parse_asteroid_size(size).and_then(|size| {
+                let count: u32 = count?;
+                let ship_position = ships_query
+                    .iter()
+                    .next()
+                    .map_or(Vec2::ZERO, |(_, _, _, transform, _)| {
+                        transform.translation.truncate()
+                    });
+                let mut rng = thread_rng();
+                for _ in 0..count {
+                    let distance: f32 = rng.gen_range(level.asteroid_distance_bounds());
+                    let position = ship_position
+                        + Vec2::from_angle(random::<f32>() * std::f32::consts::TAU) * distance;
+                    let speed = rng.gen_range(level.asteroid_speed_bounds());
+                    let velocity =
+                        Vec2::from_angle(random::<f32>() * std::f32::consts::TAU) * speed;
+                    commands.spawn(AsteroidBundle::new(
+                        &sprite_sheets,
+                        level.asteroid_variant(),
+                        size,
+                        position,
+                        velocity,
+                        random::<f32>() - 0.5,
+                    ));
+                }
+                Ok(format!("Spawned {count} {size:?} asteroid(s)"))
+            })
+        }
+        ["spawn", "ufo", kind] => parse_ufo_kind(kind).map(|kind| {
+            spawn_ufo(&mut commands, &sprite_sheets, &level, kind, 1.0);
+            format!("Spawned {kind:?} UFO")
+        }),
+        ["set", "level", number] => match number.parse::<u32>() {
+            Ok(number) if number > 0 => {
+                level.0 = number - 1;
+                next_state.set(AppState::LoadLevel);
+                Ok(format!("Loading level {number}"))
+            }
+            _ => Err(format!("Bad level: {number}")),
+        },
+        ["give", item, amount] => match amount.parse::<u8>() {
+            Ok(amount) => ships_query
+                .iter_mut()
+                .next()
+                .ok_or_else(|| "No ship".to_string())
+                .and_then(|(_, mut ship, mut shield, _, _)| {
+                    give(&mut ship, &mut shield, item, amount)
+                }),
+            Err(_) => Err(format!("Bad amount: {amount}")),
+        },
+        ["god"] => {
+            console.god_mode = !console.god_mode;
+            Ok(format!("God mode {}", on_off(console.god_mode)))
+        }
+        ["bot"] => ships_query
+            .iter()
+            .next()
+            .ok_or_else(|| "No ship".to_string())
+            .map(|(ship_entity, _, _, _, has_bot)| {
+                if has_bot {
+                    commands
+                        .entity(ship_entity)
+                        .remove::<(ShipBot, InputState)>();
+                } else {
+                    commands
+                        .entity(ship_entity)
+                        .insert((ShipBot, InputState::default()));
+                }
+                format!("Bot {}", on_off(!has_bot))
+            }),
+        _ => Err(format!("Unknown command: {line}")),
+    };
+    match result {
+        Ok(message) => console.print(message),
+        Err(message) => console.print(format!("Error: {message}")),
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn parse_asteroid_size(name: &str) -> Result<AsteroidSize, String> {
+    match name {
+        "tiny" => Ok(AsteroidSize::Tiny),
+        "small" => Ok(AsteroidSize::Small),
+        "medium" => Ok(AsteroidSize::Medium),
+        "large" => Ok(AsteroidSize::Large),
+        _ => Err(format!("Unknown asteroid size: {name}")),
+    }
+}
+
+fn parse_ufo_kind(name: &str) -> Result<UfoKind, String> {
+    match name {
+        "large" => Ok(UfoKind::Large),
+        "small" => Ok(UfoKind::Small),
+        "stealth" => Ok(UfoKind::Stealth),
+        _ => Err(format!("Unknown UFO kind: {name}")),
+    }
+}
+
+fn give(
+    ship: &mut Ship,
+    shield: &mut ShieldEnergy,
+    item: &str,
+    amount: u8,
+) -> Result<String, String> {
+    match item {
+        "rapid" => ship.weapon_rapid_level = amount.min(8),
+        "spread" => ship.weapon_spread_level = amount.min(8),
+        "beam" => ship.weapon_beam_level = amount.min(8),
+        "plasma" => {
+            ship.weapon_plasma_level = amount.min(8);
+            ship.plasma_ammo = PLASMA_MAX_AMMO;
+        }
+        "charge" => ship.weapon_charge_level = amount.min(8),
+        "railgun" => ship.weapon_railgun_level = amount.min(8),
+        "shield" => {
+            *shield = ShieldEnergy::full((amount as f32 * SHIELD_PIP_ENERGY).min(SHIELD_MAX_ENERGY))
+        }
+        "lives" => ship.lives = amount,
+        "jumps" => ship.hyperspace_jumps = amount,
+        _ => return Err(format!("Unknown item: {item}")),
+    }
+    Ok(format!("Gave {item} {amount}"))
+}
+
+fn god_mode_system(console: Res<Console>, mut ships_query: Query<&mut Ship>) {
+    if !console.god_mode {
+        return;
+    }
+    for mut ship in ships_query.iter_mut() {
+        if ship.respawn_delay <= 0.0 {
+            ship.invulnerability = ship.invulnerability.max(1.0);
+        }
+    }
+}
+
+fn console_text_system(
+    console: Res<Console>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<ConsoleText>>,
+    mut background_query: Query<&mut Visibility, (With<ConsoleBackground>, Without<ConsoleText>)>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    let visibility = if console.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    for mut background_visibility in background_query.iter_mut() {
+        *background_visibility = visibility;
+    }
+    for (mut text, mut text_visibility) in text_query.iter_mut() {
+        *text_visibility = visibility;
+        let skip = console.output.len().saturating_sub(CONSOLE_LINES);
+        let lines: Vec<_> = console.output[skip..]
+            .iter()
+            .cloned()
+            .chain([format!("> {}_", console.input)])
+            .collect();
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::input::{key_name, Action, BindingPreset, InputState, KeyBindings};
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet, Settings};
+use crate::resources::GameAssets;
+use crate::AppState;
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct ControlsEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ControlsItem {
+    Preset,
+    Action(Action),
+    Back,
+}
+
+fn items() -> Vec<ControlsItem> {
+    [ControlsItem::Preset]
+        .into_iter()
+        .chain(Action::ALL.into_iter().map(ControlsItem::Action))
+        .chain([ControlsItem::Back])
+        .collect()
+}
+
+#[derive(Component)]
+struct ControlsItemText {
+    index: usize,
+    item: ControlsItem,
+}
+
+#[derive(Component)]
+struct ControlsTitleText;
+
+/// Action waiting for a key press to bind it to
+#[derive(Resource, Default)]
+struct Rebinding(Option<Action>);
+
+/// Preset the bindings are exactly the same as, if any
+fn current_preset(bindings: &KeyBindings) -> Option<BindingPreset> {
+    BindingPreset::ALL
+        .into_iter()
+        .find(|preset| preset.bindings() == *bindings)
+}
+
+/// Keyboard bindings screen, reached from the options
+pub struct ControlsPlugin;
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(AppState::Controls), init_controls)
+            .add_systems(
+                OnExit(AppState::Controls),
+                crate::despawn_tagged::<ControlsEntity>,
+            )
+            .add_systems(
+                Update,
+                (controls_input, controls_text_system.after(controls_input))
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::Controls)),
+            );
+    }
+}
+
+fn init_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    rebinding.0 = None;
+    commands
+        .spawn(MenuNavigation::new(items().len()))
+        .insert(ControlsEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(ControlsEntity);
+
+    let font = game_assets.font.clone();
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ),
+            text_anchor: Anchor::CenterLeft,
+            transform: Transform::from_xyz(-250.0, 200.0, 0.1),
+            ..default()
+        })
+        .insert(ControlsTitleText)
+        .insert(ControlsEntity);
+    for (index, item) in items().into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::CenterLeft,
+                transform: Transform::from_xyz(-250.0, 160.0 - index as f32 * 22.0, 0.1),
+                ..default()
+            })
+            .insert(ControlsItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(ControlsEntity);
+    }
+}
+
+fn controls_input(
+    input: Res<InputState>,
+    keyboard: Res<Input<KeyCode>>,
+    mut menu_query: Query<&mut MenuNavigation>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(mut menu) = menu_query.get_single_mut() else {
+        return;
+    };
+    let items = items();
+    if let Some(action) = rebinding.0 {
+        // The key being bound may well move the menu too, so keep the selection put
+        if let Some(index) = items
+            .iter()
+            .position(|item| *item == ControlsItem::Action(action))
+        {
+            menu.selected = index;
+        }
+        let Some(&key) = keyboard.get_just_pressed().next() else {
+            return;
+        };
+        // Escape cancels, unless pause is getting it back
+        if key != KeyCode::Escape || action == Action::Pause {
+            settings.bindings.bind(action, key);
+            if let Err(err) = settings.save() {
+                error!("Could not save settings: {err}");
+            }
+        }
+        rebinding.0 = None;
+        return;
+    }
+    if input.back {
+        next_state.set(AppState::Options);
+    } else if items[menu.selected] == ControlsItem::Preset
+        && (input.ok || input.menu_left || input.menu_right)
+    {
+        // Customized bindings start over from the first preset
+        let step = if input.menu_left {
+            BindingPreset::ALL.len() - 1
+        } else {
+            1
+        };
+        let next = current_preset(&settings.bindings)
+            .and_then(|preset| BindingPreset::ALL.iter().position(|p| *p == preset))
+            .map_or(0, |index| (index + step) % BindingPreset::ALL.len());
+        settings.bindings = BindingPreset::ALL[next].bindings();
+        if let Err(err) = settings.save() {
+            error!("Could not save settings: {err}");
+        }
+    } else if input.ok {
+        match items[menu.selected] {
+            ControlsItem::Preset => {}
+            ControlsItem::Action(action) => rebinding.0 = Some(action),
+            ControlsItem::Back => next_state.set(AppState::Options),
+        }
+    }
+}
+
+fn controls_text_system(
+    menu_query: Query<&MenuNavigation>,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    localization: Res<Localization>,
+    mut title_query: Query<&mut Text, (With<ControlsTitleText>, Without<ControlsItemText>)>,
+    mut items_query: Query<(&ControlsItemText, &mut Text)>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for mut text in title_query.iter_mut() {
+        text.sections[0].value = localization.get("Controls").to_string();
+    }
+    for (item_text, mut text) in items_query.iter_mut() {
+        let (label, conflict) = match item_text.item {
+            ControlsItem::Preset => (
+                localization.format(
+                    "Preset: < {preset} >",
+                    &[(
+                        "preset",
+                        &localization.get(
+                            current_preset(&settings.bindings)
+                                .map_or("Custom", |preset| preset.name()),
+                        ),
+                    )],
+                ),
+                false,
+            ),
+            ControlsItem::Action(action) if rebinding.0 == Some(action) => (
+                localization.format(
+                    "{action}: press a key",
+                    &[("action", &localization.get(action.name()))],
+                ),
+                false,
+            ),
+            ControlsItem::Action(action) => {
+                let conflict = settings.bindings.conflicts(action).next().is_some();
+                let mut label = localization.format(
+                    "{action}: {key}",
+                    &[
+                        ("action", &localization.get(action.name())),
+                        ("key", &key_name(settings.bindings.key(action))),
+                    ],
+                );
+                if conflict {
+                    label = format!("{label} {}", localization.get("(conflict)"));
+                }
+                (label, conflict)
+            }
+            ControlsItem::Back => (localization.get("Back").to_string(), false),
+        };
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label
+        };
+        text.sections[0].style.color = match (selected, conflict) {
+            (true, _) => Color::YELLOW,
+            (false, true) => Color::RED,
+            (false, false) => Color::WHITE,
+        };
+    }
+}
@@ -0,0 +1,75 @@
+use crate::input::InputState;
+use crate::plugins::Localization;
+use crate::resources::GameAssets;
+use crate::AppState;
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct CreditsEntity;
+
+/// Heading and the people credited under it, as listed in `assets/img/LICENSE`
+const CREDITS: [(&str, &[&str]); 4] = [
+    ("Game", &["Teemu Erkkola"]),
+    ("Ships and asteroids", &["Carl Olsson (surt)"]),
+    ("Explosions", &["Wrathgames Studio"]),
+    ("Background", &["Cuzco"]),
+];
+
+pub struct CreditsPlugin;
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Credits), init_credits)
+            .add_systems(
+                OnExit(AppState::Credits),
+                crate::despawn_tagged::<CreditsEntity>,
+            )
+            .add_systems(Update, credits_input.run_if(in_state(AppState::Credits)));
+    }
+}
+
+fn init_credits(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(CreditsEntity);
+
+    let font = game_assets.font.clone();
+    let mut lines = vec![(localization.get("Credits").to_string(), 32.0, Color::WHITE)];
+    for (heading, names) in CREDITS {
+        lines.push((localization.get(heading).to_string(), 16.0, Color::GRAY));
+        lines.extend(
+            names
+                .iter()
+                .map(|name| (name.to_string(), 20.0, Color::WHITE)),
+        );
+    }
+    for (i, (line, font_size, color)) in lines.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    line,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size,
+                        color,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 180.0 - i as f32 * 40.0, 0.1),
+                ..default()
+            })
+            .insert(CreditsEntity);
+    }
+}
+
+fn credits_input(input: Res<InputState>, mut next_state: ResMut<NextState<AppState>>) {
+    if input.ok || input.back {
+        next_state.set(AppState::Title);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    components::{Health, ShieldEnergy},
+    AppState,
+};
+use bevy::prelude::*;
+
+pub struct DamagePlugin;
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<Died>()
+            .add_systems(PostUpdate, damage_system.run_if(in_state(AppState::InGame)));
+    }
+}
+
+/// Damage dealt to an entity with `Health`, applied after the gameplay systems have run
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub cause: DamageCause,
+}
+
+/// How the damage was dealt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageCause {
+    /// A single projectile that stops at what it hits
+    Shot,
+    /// Something that keeps going through its targets, like plasma, beams and piercing shots
+    Sweep,
+    Collision,
+    Malfunction,
+}
+
+impl DamageCause {
+    /// Whether destruction by this cause continues a chain started by an earlier one
+    pub fn chains(&self) -> bool {
+        matches!(self, DamageCause::Sweep)
+    }
+}
+
+/// Sent once when an entity's `Health` runs out
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Died {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+    pub cause: DamageCause,
+}
+
+fn damage_system(
+    mut damage_events: EventReader<DamageEvent>,
+    mut died_events: EventWriter<Died>,
+    mut health_query: Query<(&mut Health, Option<&mut ShieldEnergy>)>,
+) {
+    for event in damage_events.iter() {
+        let Ok((mut health, shield)) = health_query.get_mut(event.target) else {
+            continue;
+        };
+        if health.current <= 0.0 {
+            continue;
+        }
+        let amount = match shield {
+            Some(mut shield) => shield.absorb(event.amount),
+            None => event.amount,
+        };
+        if amount <= 0.0 {
+            continue;
+        }
+        if !health.damage(amount) {
+            died_events.send(Died {
+                entity: event.target,
+                killer: event.source,
+                cause: event.cause,
+            });
+        }
+    }
+}
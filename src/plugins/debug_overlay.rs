@@ -0,0 +1,153 @@
+use crate::{components::*, constants::*, plugins::PauseState, resources::GameAssets, AppState};
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    sprite::Anchor,
+};
+
+/// Frame time the graph's full height stands for, in milliseconds
+const FRAME_TIME_GRAPH_SCALE: f32 = 50.0;
+const FRAME_TIME_GRAPH_HEIGHT: f32 = 40.0;
+const FRAME_TIME_GRAPH_BAR_WIDTH: f32 = 4.0;
+
+pub struct DebugOverlayPlugin;
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FrameTimeDiagnosticsPlugin)
+            .init_resource::<ShowDebugOverlay>()
+            .add_systems(Startup, init_debug_overlay)
+            .add_systems(
+                Update,
+                (
+                    debug_overlay_toggle_system,
+                    debug_overlay_text_system.after(debug_overlay_toggle_system),
+                    frame_time_graph_system.after(debug_overlay_toggle_system),
+                ),
+            );
+    }
+}
+
+/// Whether the overlay is shown, toggled with F3
+#[derive(Default, Resource)]
+struct ShowDebugOverlay(bool);
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+fn init_debug_overlay(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 14.0,
+                    color: Color::YELLOW,
+                },
+            ),
+            text_anchor: Anchor::BottomLeft,
+            transform: Transform::from_xyz(
+                -(GAME_WIDTH as f32) / 2.0 + 8.0,
+                -(GAME_HEIGHT as f32) / 2.0 + FRAME_TIME_GRAPH_HEIGHT + 16.0,
+                0.95,
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DebugOverlayText,
+    ));
+}
+
+fn debug_overlay_toggle_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut show: ResMut<ShowDebugOverlay>,
+    mut text_query: Query<&mut Visibility, With<DebugOverlayText>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F3) {
+        return;
+    }
+    show.0 = !show.0;
+    for mut visibility in text_query.iter_mut() {
+        *visibility = if show.0 {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn debug_overlay_text_system(
+    show: Res<ShowDebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    entities_query: Query<()>,
+    asteroids_query: Query<(), With<Asteroid>>,
+    projectiles_query: Query<(), With<ShipProjectile>>,
+    particles_query: Query<(), With<Expiring>>,
+    app_state: Res<State<AppState>>,
+    pause_state: Res<State<PauseState>>,
+    mut text_query: Query<&mut Text, With<DebugOverlayText>>,
+) {
+    if !show.0 {
+        return;
+    }
+    let measurement = |id| {
+        diagnostics
+            .get(id)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+    let lines = [
+        format!(
+            "FPS: {:.0} ({:.1} ms)",
+            measurement(FrameTimeDiagnosticsPlugin::FPS),
+            measurement(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        ),
+        format!("Entities: {}", entities_query.iter().count()),
+        format!("Asteroids: {}", asteroids_query.iter().count()),
+        format!("Projectiles: {}", projectiles_query.iter().count()),
+        format!("Particles: {}", particles_query.iter().count()),
+        format!("State: {:?} / {:?}", app_state.get(), pause_state.get()),
+    ];
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+/// Bars for the last frame times along the bottom left corner, oldest first
+fn frame_time_graph_system(
+    mut gizmos: Gizmos,
+    show: Res<ShowDebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+) {
+    if !show.0 {
+        return;
+    }
+    let Some(frame_time) = diagnostics.get(FrameTimeDiagnosticsPlugin::FRAME_TIME) else {
+        return;
+    };
+    let origin = Vec2::new(
+        -(GAME_WIDTH as f32) / 2.0 + 8.0,
+        -(GAME_HEIGHT as f32) / 2.0 + 8.0,
+    );
+    for (i, &value) in frame_time.values().enumerate() {
+        let fraction = (value as f32 / FRAME_TIME_GRAPH_SCALE).min(1.0);
+        let base = origin + Vec2::X * i as f32 * FRAME_TIME_GRAPH_BAR_WIDTH;
+        // Anything slower than 60 fps stands out
+        let color = if value > 1000.0 / 60.0 {
+            Color::RED
+        } else {
+            Color::GREEN
+        };
+        gizmos.line_2d(
+            base,
+            base + Vec2::Y * fraction * FRAME_TIME_GRAPH_HEIGHT,
+            color,
+        );
+    }
+    let width = frame_time.get_max_history_length() as f32 * FRAME_TIME_GRAPH_BAR_WIDTH;
+    gizmos.line_2d(
+        origin + Vec2::Y * FRAME_TIME_GRAPH_HEIGHT,
+        origin + Vec2::new(width, FRAME_TIME_GRAPH_HEIGHT),
+        Color::GRAY,
+    );
+}
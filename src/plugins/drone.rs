@@ -0,0 +1,171 @@
+use crate::{bundles::*, components::*, constants::*, plugins::Profile, resources::*, AppState};
+use bevy::prelude::*;
+
+pub struct DronePlugin;
+impl Plugin for DronePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                drone_spawn_system,
+                drone_orbit_system,
+                drone_shoot_system,
+                drone_asteroid_collision_system.after(drone_spawn_system),
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+#[derive(Component)]
+struct Drone {
+    angle: f32,
+    shoot_delay: f32,
+}
+
+/// Keeps the number of drones orbiting each ship in sync with `Ship::drones`
+fn drone_spawn_system(
+    mut commands: Commands,
+    ships_query: Query<(Entity, &Ship, Option<&Children>)>,
+    drones_query: Query<(Entity, &Drone)>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for (ship_entity, ship, children) in ships_query.iter() {
+        let drones: Vec<_> = children
+            .into_iter()
+            .flatten()
+            .filter_map(|child| drones_query.get(*child).ok())
+            .collect();
+        for (drone_entity, _) in drones.iter().skip(ship.drones as usize) {
+            commands.entity(*drone_entity).despawn_recursive();
+        }
+        if drones.len() < ship.drones as usize {
+            // A new drone takes the opposite side of the orbit from an existing one
+            let angle = drones
+                .first()
+                .map_or(0.0, |(_, drone)| drone.angle + std::f32::consts::PI);
+            let drone = Drone {
+                angle,
+                shoot_delay: DRONE_SHOOT_INTERVAL,
+            };
+            commands.entity(ship_entity).with_children(|ship| {
+                ship.spawn(drone_bundle(&sprite_sheets.ship)).insert(drone);
+            });
+        }
+    }
+}
+
+fn drone_bundle(ship_images: &ShipImages) -> (SpriteSheetBundle, CollisionShape) {
+    let transform =
+        Transform::from_xyz(DRONE_ORBIT_RADIUS, 0.0, 0.01).with_scale(Vec3::splat(DRONE_SCALE));
+    (
+        SpriteSheetBundle {
+            texture_atlas: ship_images.atlas.clone(),
+            transform,
+            sprite: TextureAtlasSprite {
+                index: ship_images.rapid,
+                color: Color::rgb(0.6, 1.0, 0.6),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        CollisionShape::new(
+            Shape::Circle {
+                center: Vec2::ZERO,
+                radius: DRONE_RADIUS,
+            },
+            transform,
+        ),
+    )
+}
+
+fn drone_orbit_system(
+    mut drones_query: Query<(&mut Drone, &mut Transform, &Parent)>,
+    ships_query: Query<&Transform, (With<Ship>, Without<Drone>)>,
+    time: Res<Time>,
+) {
+    for (mut drone, mut transform, parent) in drones_query.iter_mut() {
+        drone.angle = (drone.angle + DRONE_ORBIT_SPEED * time.delta_seconds())
+            .rem_euclid(std::f32::consts::TAU);
+        // Counter the ship rotation so the orbit does not follow the ship's turning
+        let ship_rotation = ships_query
+            .get(parent.get())
+            .map(|ship_transform| ship_transform.rotation)
+            .unwrap_or_default();
+        let offset = Vec2::from_angle(drone.angle) * DRONE_ORBIT_RADIUS;
+        transform.translation = (ship_rotation.inverse() * offset.extend(0.0))
+            .truncate()
+            .extend(0.01);
+        transform.rotation = ship_rotation.inverse();
+    }
+}
+
+fn drone_shoot_system(
+    mut commands: Commands,
+    mut drones_query: Query<(&mut Drone, &GlobalTransform)>,
+    asteroids_query: Query<&Transform, With<Asteroid>>,
+    game_assets: Res<GameAssets>,
+    profile: Res<Profile>,
+    time: Res<Time>,
+) {
+    for (mut drone, drone_transform) in drones_query.iter_mut() {
+        drone.shoot_delay -= time.delta_seconds();
+        if drone.shoot_delay > 0.0 {
+            continue;
+        }
+        let position = drone_transform.translation().truncate();
+        let nearest = asteroids_query
+            .iter()
+            .map(|asteroid_transform| asteroid_transform.translation.truncate())
+            .filter(|target| target.distance_squared(position) < DRONE_RANGE * DRONE_RANGE)
+            .min_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+        let Some(target) = nearest else {
+            continue;
+        };
+        drone.shoot_delay = DRONE_SHOOT_INTERVAL;
+        let aim = (target - position).normalize_or_zero();
+        let transform = Transform {
+            translation: position.extend(0.0),
+            rotation: Quat::from_rotation_z(Vec2::Y.angle_between(aim)),
+            ..Default::default()
+        };
+        commands.spawn(ShipProjectileBundle::new(
+            ShipProjectile::Rapid,
+            game_assets.laser.clone(),
+            aim * DRONE_SHOT_SPEED,
+            transform,
+            DRONE_RANGE / DRONE_SHOT_SPEED,
+            1.0,
+            profile.projectile_palette.color(),
+        ));
+    }
+}
+
+fn drone_asteroid_collision_system(
+    mut commands: Commands,
+    drones_query: Query<(Entity, &CollisionShape, &GlobalTransform, &Parent), With<Drone>>,
+    asteroids_query: Query<&CollisionShape, With<Asteroid>>,
+    mut ships_query: Query<&mut Ship>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for (drone_entity, drone_shape, drone_transform, parent) in drones_query.iter() {
+        let Ok(mut ship) = ships_query.get_mut(parent.get()) else {
+            continue;
+        };
+        if ship.invulnerability > 0.0 {
+            continue;
+        }
+        if asteroids_query
+            .iter()
+            .any(|asteroid_shape| drone_shape.intersects(asteroid_shape))
+        {
+            ship.drones = ship.drones.saturating_sub(1);
+            commands.entity(drone_entity).despawn_recursive();
+            let position = drone_transform.translation().truncate();
+            commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    components::*, constants::*, plugins::Settings, resources::TimeScale, utils::*, AppState,
+};
+use bevy::{ecs::query::ReadOnlyWorldQuery, prelude::*};
+
+pub struct EffectsPlugin;
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spinning_system,
+                expiring_system,
+                scaling_system,
+                fading_system,
+                animation_system,
+                reduced_flashing_system,
+            ),
+        )
+        .add_systems(
+            Update,
+            (scaling_system, expiring_system, fading_system).run_if(in_state(AppState::LoadLevel)),
+        )
+        // After this frame's spawns have landed but before anything is drawn
+        .add_systems(PostUpdate, entity_cap_system);
+    }
+}
+
+fn spinning_system(
+    mut spinning_query: Query<(&Spinning, &mut Transform)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta = time.delta().as_secs_f32() * time_scale.0;
+    for (spinning, mut transform) in spinning_query.iter_mut() {
+        transform.rotation *= Quat::from_rotation_z(spinning.speed * delta);
+    }
+}
+
+fn scaling_system(mut scaling_query: Query<(&mut Scaling, &mut Transform)>, time: Res<Time>) {
+    for (mut scaling, mut transform) in scaling_query.iter_mut() {
+        scaling.elapsed += time.delta_seconds();
+        let scale = lerp(scaling.from, scaling.to, scaling.elapsed / scaling.duration);
+        transform.scale = Vec3::splat(scale);
+    }
+}
+
+fn fading_system(
+    mut fading_query: Query<(
+        &mut Fading,
+        Option<&mut Text>,
+        Option<&mut Sprite>,
+        Option<&mut TextureAtlasSprite>,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut fading, text, sprite, atlas_sprite) in fading_query.iter_mut() {
+        fading.elapsed += time.delta_seconds();
+        let alpha = lerp(fading.from, fading.to, fading.elapsed / fading.duration);
+        if let Some(mut text) = text {
+            for section in text.sections.iter_mut() {
+                section.style.color.set_a(alpha);
+            }
+        }
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(alpha);
+        }
+        if let Some(mut sprite) = atlas_sprite {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
+
+fn expiring_system(
+    mut commands: Commands,
+    mut expiring_query: Query<(Entity, &mut Expiring)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (entity, mut expiring) in expiring_query.iter_mut() {
+        expiring.life -= time.delta().as_secs_f32() * time_scale.0;
+        if expiring.life < 0.0 {
+            commands.entity(entity).despawn_recursive()
+        }
+    }
+}
+
+fn animation_system(
+    mut animated_query: Query<(&mut Animated, &mut Handle<Image>)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    for (mut animated, mut image) in animated_query.iter_mut() {
+        animated.elapsed += delta;
+        let position = if animated.looping {
+            animated.elapsed.rem_euclid(animated.animation.duration)
+        } else {
+            animated.elapsed.min(animated.animation.duration)
+        };
+        let frame = ((animated.animation.frames.len() - 1) as f32 * position
+            / animated.animation.duration)
+            .floor() as usize;
+
+        *image = animated.animation.frames[frame].clone()
+    }
+}
+
+/// Dims new explosions for photosensitive players
+fn reduced_flashing_system(
+    settings: Res<Settings>,
+    mut explosions_query: Query<&mut Sprite, Added<Explosion>>,
+) {
+    if !settings.reduced_flashing {
+        return;
+    }
+    for mut sprite in explosions_query.iter_mut() {
+        sprite.color.set_a(REDUCED_FLASHING_EXPLOSION_ALPHA);
+    }
+}
+
+/// Keeps particles, projectiles and notifications under the caps in `Settings`, so that
+/// chain reactions don't drag the frame rate down
+fn entity_cap_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    particles_query: Query<(Entity, &Expiring), With<Particle>>,
+    projectiles_query: Query<(Entity, &Expiring), With<ShipProjectile>>,
+    notifications_query: Query<(Entity, &Expiring), With<GameNotification>>,
+) {
+    // Particles are only for show, so the newest ones are dropped instead, which
+    // amounts to skipping their spawn
+    let mut particles = oldest_first(&particles_query);
+    particles.reverse();
+    despawn_excess(&mut commands, particles, settings.max_particles);
+    despawn_excess(
+        &mut commands,
+        oldest_first(&projectiles_query),
+        settings.max_projectiles,
+    );
+    despawn_excess(
+        &mut commands,
+        oldest_first(&notifications_query),
+        settings.max_notifications,
+    );
+}
+
+/// Entities ordered by how little life they have left, standing in for their age
+fn oldest_first<F: ReadOnlyWorldQuery>(query: &Query<(Entity, &Expiring), F>) -> Vec<Entity> {
+    let mut entities: Vec<_> = query.iter().collect();
+    entities.sort_by(|(_, a), (_, b)| a.life.total_cmp(&b.life));
+    entities.into_iter().map(|(entity, _)| entity).collect()
+}
+
+/// Despawns entities from the front of the list until at most `cap` are left
+fn despawn_excess(commands: &mut Commands, entities: Vec<Entity>, cap: usize) {
+    let excess = entities.len().saturating_sub(cap);
+    for entity in entities.into_iter().take(excess) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{bundles::*, components::*, constants::*, resources::*, utils::lerp, AppState};
+use bevy::prelude::*;
+use rand::{random, thread_rng, Rng};
+
+/// Progress of an endless run: asteroids keep coming at an increasing rate
+#[derive(Default, Resource)]
+struct EndlessProgress {
+    elapsed: f32,
+    spawn_delay: f32,
+    scored_seconds: u32,
+}
+
+pub struct EndlessPlugin;
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EndlessProgress>()
+            .add_systems(OnEnter(AppState::NewGame), reset_endless_progress)
+            .add_systems(
+                Update,
+                (endless_spawn_system, endless_score_system)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_equals(GameMode::Endless)),
+            );
+    }
+}
+
+fn reset_endless_progress(mut progress: ResMut<EndlessProgress>) {
+    *progress = EndlessProgress::default();
+}
+
+fn endless_spawn_system(
+    mut commands: Commands,
+    mut progress: ResMut<EndlessProgress>,
+    level: Res<Level>,
+    modifiers: Res<Modifiers>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+) {
+    progress.elapsed += time.delta_seconds();
+    progress.spawn_delay -= time.delta_seconds();
+    if progress.spawn_delay > 0.0 {
+        return;
+    }
+    let ramp = (progress.elapsed / ENDLESS_RAMP_TIME).min(1.0);
+    progress.spawn_delay = lerp(
+        ENDLESS_SPAWN_INTERVAL_START,
+        ENDLESS_SPAWN_INTERVAL_MIN,
+        ramp,
+    );
+
+    // Enter from a random point on the screen edge, heading roughly across the screen
+    let mut rng = thread_rng();
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let d = random::<f32>() * span * 2.0 - span;
+    let position = match rng.gen_range(0..4) {
+        0 => Vec2::new(d.x, span.y),
+        1 => Vec2::new(span.x, d.y),
+        2 => Vec2::new(d.x, -span.y),
+        _ => Vec2::new(-span.x, d.y),
+    };
+    let target = (random::<f32>() * span - span / 2.0) * 0.5;
+    let speed = rng.gen_range(level.asteroid_speed_bounds())
+        * lerp(1.0, 2.0, ramp)
+        * modifiers.asteroid_speed();
+    let velocity = (target - position).normalize_or_zero() * speed;
+    let size = match rng.gen_range(0..3) {
+        0 => AsteroidSize::Large,
+        1 => AsteroidSize::Medium,
+        _ => AsteroidSize::Small,
+    };
+    commands.spawn(AsteroidBundle::new(
+        sprite_sheets.as_ref(),
+        level.asteroid_variant(),
+        size,
+        position,
+        velocity,
+        random::<f32>() - 0.5,
+    ));
+}
+
+fn endless_score_system(
+    mut progress: ResMut<EndlessProgress>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+) {
+    let survived = progress.elapsed as u32;
+    if survived > progress.scored_seconds {
+        score.increase(
+            (survived - progress.scored_seconds) * ENDLESS_SCORE_PER_SECOND * score_multiplier.0,
+        );
+        progress.scored_seconds = survived;
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{DamageCause, DamageEvent, Localization, Settings},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::{random, thread_rng, Rng};
+use std::f32::consts::TAU;
+
+pub struct EventsPlugin;
+impl Plugin for EventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RandomEvents>()
+            .add_systems(
+                Update,
+                (
+                    random_event_start_system,
+                    random_event_system.after(random_event_start_system),
+                    event_warning_blink_system,
+                    meteor_cleanup_system,
+                    solar_flare_system.after(random_event_system),
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnEnter(AppState::LoadLevel), reset_random_events);
+    }
+}
+
+/// Something happening to the whole play area for a while, announced in advance
+#[derive(Clone, Copy, Debug)]
+enum RandomEvent {
+    /// Tiny asteroids streaking across the screen towards `direction`
+    MeteorShower { direction: Vec2 },
+    /// Burning zone sweeping in from the edge opposite to `direction`
+    SolarFlare { direction: Vec2 },
+}
+
+impl RandomEvent {
+    fn random() -> Self {
+        let direction = random_axis();
+        if random() {
+            RandomEvent::MeteorShower { direction }
+        } else {
+            RandomEvent::SolarFlare { direction }
+        }
+    }
+    fn duration(&self) -> f32 {
+        match self {
+            RandomEvent::MeteorShower { .. } => METEOR_SHOWER_DURATION,
+            RandomEvent::SolarFlare { .. } => SOLAR_FLARE_DURATION,
+        }
+    }
+}
+
+struct ActiveEvent {
+    event: RandomEvent,
+    warning: f32,
+    remaining: f32,
+    spawn_delay: f32,
+}
+
+#[derive(Default, Resource)]
+struct RandomEvents {
+    /// Seconds until the next event, if the level has any
+    next: Option<f32>,
+    current: Option<ActiveEvent>,
+}
+
+#[derive(Component)]
+struct EventWarning;
+
+/// Glow on the edge a solar flare is about to come from
+#[derive(Component)]
+struct SolarFlareGlow;
+
+#[derive(Component)]
+struct SolarFlare {
+    direction: Vec2,
+    /// How far the front has come from the edge
+    front: f32,
+}
+
+/// Asteroid from a meteor shower, gone once it has crossed the screen
+#[derive(Component)]
+struct Meteor;
+
+/// One of the four directions along the screen axes
+fn random_axis() -> Vec2 {
+    [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y][thread_rng().gen_range(0..4)]
+}
+
+fn random_event_delay(level: &Level) -> Option<f32> {
+    level
+        .random_event_interval()
+        .map(|interval| thread_rng().gen_range(interval))
+}
+
+fn reset_random_events(mut random_events: ResMut<RandomEvents>, level: Res<Level>) {
+    *random_events = RandomEvents {
+        next: random_event_delay(&level),
+        current: None,
+    };
+}
+
+fn random_event_start_system(
+    mut commands: Commands,
+    mut random_events: ResMut<RandomEvents>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+    time: Res<Time>,
+) {
+    if random_events.current.is_some() {
+        return;
+    }
+    let Some(next) = random_events.next.as_mut() else {
+        return;
+    };
+    *next -= time.delta_seconds();
+    if *next > 0.0 {
+        return;
+    }
+
+    let event = RandomEvent::random();
+    let (text, direction) = match event {
+        RandomEvent::MeteorShower { direction } => {
+            (localization.get("Meteor shower incoming!"), direction)
+        }
+        RandomEvent::SolarFlare { direction } => {
+            (localization.get("Solar flare incoming!"), direction)
+        }
+    };
+    let font = game_assets.font.clone();
+    let style = TextStyle {
+        font,
+        font_size: 24.0,
+        color: Color::rgb(1.0, 0.4, 0.2),
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(text, style.clone()),
+            transform: Transform::from_xyz(0.0, 60.0, 0.5),
+            ..default()
+        },
+        EventWarning,
+        LevelEntity,
+    ));
+    // Arrow on the edge the event comes from, pointing the way it goes
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0) - 24.0;
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "➜",
+                TextStyle {
+                    font_size: 48.0,
+                    ..style
+                },
+            ),
+            transform: Transform::from_translation((-direction * span).extend(0.5))
+                .with_rotation(Quat::from_rotation_z(Vec2::X.angle_between(direction))),
+            ..default()
+        },
+        EventWarning,
+        LevelEntity,
+    ));
+
+    if let RandomEvent::SolarFlare { .. } = event {
+        let size = flare_size(direction);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 0.8, 0.3, 0.0),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(
+                    (-direction * (half_extent(direction) - SOLAR_FLARE_WIDTH / 2.0)).extend(0.4),
+                ),
+                ..default()
+            },
+            SolarFlareGlow,
+            EventWarning,
+            LevelEntity,
+        ));
+    }
+
+    random_events.current = Some(ActiveEvent {
+        event,
+        warning: EVENT_WARNING_TIME,
+        remaining: event.duration(),
+        spawn_delay: 0.0,
+    });
+}
+
+fn random_event_system(
+    mut commands: Commands,
+    mut random_events: ResMut<RandomEvents>,
+    warnings_query: Query<Entity, With<EventWarning>>,
+    level: Res<Level>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let Some(current) = random_events.current.as_mut() else {
+        return;
+    };
+    if current.warning > 0.0 {
+        current.warning -= time.delta_seconds();
+        if current.warning <= 0.0 {
+            for entity in warnings_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            if let RandomEvent::SolarFlare { direction } = current.event {
+                let size = flare_size(direction);
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(1.0, 0.6, 0.2, 0.4),
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, 0.4),
+                        ..default()
+                    },
+                    SolarFlare {
+                        direction,
+                        front: 0.0,
+                    },
+                    LevelEntity,
+                ));
+            }
+        }
+        return;
+    }
+
+    let delta = time.delta_seconds() * time_scale.0;
+    current.remaining -= delta;
+    match current.event {
+        RandomEvent::MeteorShower { direction } => {
+            current.spawn_delay -= delta;
+            while current.spawn_delay <= 0.0 {
+                current.spawn_delay += METEOR_INTERVAL;
+                spawn_meteor(&mut commands, &sprite_sheets, &level, direction);
+            }
+        }
+        // Moved along by its own system
+        RandomEvent::SolarFlare { .. } => (),
+    }
+    if current.remaining <= 0.0 {
+        random_events.current = None;
+        random_events.next = random_event_delay(&level);
+    }
+}
+
+fn spawn_meteor(
+    commands: &mut Commands,
+    sprite_sheets: &SpriteSheets,
+    level: &Level,
+    direction: Vec2,
+) {
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let across = direction.perp() * span * lerp(-1.0, 1.0, random());
+    let position = -direction * (span + AsteroidSize::Tiny.radius()) + across;
+    let spread = lerp(-0.15, 0.15, random());
+    let velocity = Vec2::from_angle(spread).rotate(direction) * METEOR_SPEED;
+    commands
+        .spawn(AsteroidBundle::new(
+            sprite_sheets,
+            level.asteroid_variant(),
+            AsteroidSize::Tiny,
+            position,
+            velocity,
+            random::<f32>() * 4.0 - 2.0,
+        ))
+        .insert(Meteor)
+        .remove::<Wrapping>();
+}
+
+/// Half the screen along `direction`
+fn half_extent(direction: Vec2) -> f32 {
+    Vec2::new(GAME_WIDTH as f32, GAME_HEIGHT as f32).dot(direction.abs()) / 2.0
+}
+
+/// Size of a flare band reaching across the whole screen
+fn flare_size(direction: Vec2) -> Vec2 {
+    let across = half_extent(direction.perp()) * 2.0;
+    direction.abs() * SOLAR_FLARE_WIDTH + direction.perp().abs() * across
+}
+
+/// Sweeps flares in from their edge, burning the ship and any enemies in the way
+fn solar_flare_system(
+    mut commands: Commands,
+    mut flares_query: Query<(Entity, &mut SolarFlare, &mut Transform, &mut Sprite)>,
+    mut ships_query: Query<(Entity, &mut Ship, &Transform), Without<SolarFlare>>,
+    enemies_query: Query<(Entity, &Transform), (With<Enemy>, Without<SolarFlare>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta = time.delta_seconds() * time_scale.0;
+    for (entity, mut flare, mut transform, mut sprite) in flares_query.iter_mut() {
+        let direction = flare.direction;
+        let edge = half_extent(direction);
+        let reach = edge * 2.0 * SOLAR_FLARE_REACH;
+        flare.front += reach / SOLAR_FLARE_DURATION * delta;
+        if flare.front >= reach {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Position along the flare's way, starting from its edge
+        let depth = |transform: &Transform| transform.translation.truncate().dot(direction) + edge;
+        let back = (flare.front - SOLAR_FLARE_WIDTH).max(0.0);
+        let burning = |transform: &Transform| (back..=flare.front).contains(&depth(transform));
+        transform.translation = (direction * ((back + flare.front) / 2.0 - edge)).extend(0.4);
+        sprite.color.set_a(lerp(0.5, 0.0, flare.front / reach));
+
+        for (ship_entity, mut ship, ship_transform) in ships_query.iter_mut() {
+            if !burning(ship_transform) || ship.invulnerability > 0.0 {
+                continue;
+            }
+            ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+            damage_events.send(DamageEvent {
+                target: ship_entity,
+                source: None,
+                amount: SOLAR_FLARE_DAMAGE,
+                cause: DamageCause::Sweep,
+            });
+        }
+        for (enemy_entity, enemy_transform) in enemies_query.iter() {
+            if burning(enemy_transform) {
+                damage_events.send(DamageEvent {
+                    target: enemy_entity,
+                    source: None,
+                    amount: SOLAR_FLARE_ENEMY_DPS * delta,
+                    cause: DamageCause::Sweep,
+                });
+            }
+        }
+    }
+}
+
+fn event_warning_blink_system(
+    mut warnings_query: Query<&mut Text, With<EventWarning>>,
+    mut glows_query: Query<&mut Sprite, With<SolarFlareGlow>>,
+    random_events: Res<RandomEvents>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    // The flare's edge brightens as it gets closer
+    if let Some(current) = random_events.current.as_ref() {
+        for mut sprite in glows_query.iter_mut() {
+            sprite
+                .color
+                .set_a(lerp(0.6, 0.0, current.warning / EVENT_WARNING_TIME));
+        }
+    }
+    let alpha = if settings.reduced_flashing {
+        let phase = time.elapsed_seconds() / REDUCED_FLASHING_PULSE * TAU;
+        lerp(0.3, 1.0, 0.5 + 0.5 * phase.cos())
+    } else if time.elapsed_seconds() % 0.5 < 0.25 {
+        1.0
+    } else {
+        0.3
+    };
+    for mut text in warnings_query.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+/// Meteors don't wrap around, so clear them out once they have left the screen
+fn meteor_cleanup_system(
+    mut commands: Commands,
+    meteors_query: Query<(Entity, &Transform), With<Meteor>>,
+) {
+    let bounds = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0)
+        + AsteroidSize::Tiny.radius() * 2.0;
+    for (entity, transform) in meteors_query.iter() {
+        let position = transform.translation.truncate().abs();
+        if position.x > bounds.x || position.y > bounds.y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
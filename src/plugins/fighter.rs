@@ -0,0 +1,249 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{Died, UfoLaserBundle},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::random;
+use std::f32::consts::{PI, TAU};
+
+pub struct EnemyFighterPlugin;
+impl Plugin for EnemyFighterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FighterSpawnTimer>()
+            .add_systems(
+                Update,
+                (
+                    fighter_spawn_system,
+                    fighter_steering_system,
+                    fighter_shoot_system.after(fighter_steering_system),
+                    fighter_destroy_system,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnEnter(AppState::LoadLevel), reset_fighter_spawn_timer);
+    }
+}
+
+/// Small ship that hunts down the player, weaving around asteroids on the way
+#[derive(Component)]
+struct EnemyFighter {
+    angle: f32,
+    shoot_delay: f32,
+}
+
+/// Seconds until the next fighter, if the level has any
+#[derive(Default, Resource)]
+struct FighterSpawnTimer(Option<f32>);
+
+fn reset_fighter_spawn_timer(mut timer: ResMut<FighterSpawnTimer>, level: Res<Level>) {
+    timer.0 = level.fighter_spawn_interval();
+}
+
+fn fighter_spawn_system(
+    mut commands: Commands,
+    mut timer: ResMut<FighterSpawnTimer>,
+    level: Res<Level>,
+    mode: Res<GameMode>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+) {
+    // Survival waves bring their own enemies
+    if *mode == GameMode::Survival {
+        return;
+    }
+    let Some(remaining) = timer.0.as_mut() else {
+        return;
+    };
+    *remaining -= time.delta_seconds();
+    if *remaining > 0.0 {
+        return;
+    }
+    *remaining += level.fighter_spawn_interval().unwrap_or(f32::INFINITY);
+
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let position = if random() {
+        Vec2::new(
+            lerp(-span.x, span.x, random()),
+            span.y.copysign(random::<f32>() - 0.5),
+        )
+    } else {
+        Vec2::new(
+            span.x.copysign(random::<f32>() - 0.5),
+            lerp(-span.y, span.y, random()),
+        )
+    };
+    // Enter heading for the middle of the screen
+    let angle = Vec2::Y.angle_between(-position);
+    let transform = Transform::from_translation(position.extend(0.0))
+        .with_rotation(Quat::from_rotation_z(angle))
+        .with_scale(Vec3::splat(FIGHTER_SCALE));
+    commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas: sprite_sheets.ship.atlas.clone(),
+            transform,
+            sprite: TextureAtlasSprite {
+                index: sprite_sheets.ship.rapid,
+                color: Color::rgb(1.0, 0.4, 0.4),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        EnemyFighter {
+            angle,
+            shoot_delay: FIGHTER_SHOOT_INTERVAL,
+        },
+        Enemy,
+        Health::new(FIGHTER_HEALTH),
+        // Mostly weapons, to keep up with the fighters
+        DropTable {
+            entries: vec![
+                (DropEntry::Powerup(Powerup::Laser), 0.1),
+                (DropEntry::Powerup(Powerup::Spread), 0.1),
+                (DropEntry::Powerup(Powerup::PlasmaAmmo), 0.1),
+                (DropEntry::Powerup(Powerup::Shield), 0.2),
+                (DropEntry::Powerup(Powerup::Repair), 0.05),
+            ],
+            nothing: 0.6,
+            minerals: 3,
+        },
+        Moving {
+            velocity: Vec2::from_angle(angle).rotate(Vec2::Y) * FIGHTER_SPEED,
+            ..Default::default()
+        },
+        Wrapping,
+        LevelEntity,
+        CollisionShape::new(
+            Shape::Circle {
+                center: Vec2::ZERO,
+                radius: FIGHTER_RADIUS,
+            },
+            transform,
+        ),
+    ));
+}
+
+/// Seeks the ship while steering clear of any asteroids close by
+fn fighter_steering_system(
+    mut fighters_query: Query<(
+        &mut EnemyFighter,
+        &mut Transform,
+        &mut Moving,
+        &CollisionShape,
+    )>,
+    ships_query: Query<&Transform, (With<Ship>, Without<EnemyFighter>)>,
+    asteroids_query: Query<(&Transform, &CollisionShape), (With<Asteroid>, Without<EnemyFighter>)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta = time.delta_seconds() * time_scale.0;
+    for (mut fighter, mut transform, mut moving, shape) in fighters_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let heading = Vec2::from_angle(fighter.angle).rotate(Vec2::Y);
+        let seek = ships_query.get_single().map_or(heading, |ship_transform| {
+            (ship_transform.translation.truncate() - position).normalize_or_zero()
+        });
+        let avoid: Vec2 = asteroids_query
+            .iter()
+            .filter_map(|(asteroid_transform, asteroid_shape)| {
+                let gap = shape.distance(asteroid_shape);
+                (gap < FIGHTER_AVOID_DISTANCE).then(|| {
+                    let away =
+                        (position - asteroid_transform.translation.truncate()).normalize_or_zero();
+                    away * 2.0 * (1.0 - gap.max(0.0) / FIGHTER_AVOID_DISTANCE)
+                })
+            })
+            .sum();
+        let desired = (seek + avoid).normalize_or_zero();
+        if desired != Vec2::ZERO {
+            let turn = (Vec2::Y.angle_between(desired) - fighter.angle + PI).rem_euclid(TAU) - PI;
+            let max_turn = FIGHTER_TURN_RATE * delta;
+            fighter.angle += turn.clamp(-max_turn, max_turn);
+        }
+        transform.rotation = Quat::from_rotation_z(fighter.angle);
+        moving.velocity = Vec2::from_angle(fighter.angle).rotate(Vec2::Y) * FIGHTER_SPEED;
+    }
+}
+
+/// Fires straight ahead whenever the ship is lined up in front
+fn fighter_shoot_system(
+    mut commands: Commands,
+    mut fighters_query: Query<(&mut EnemyFighter, &Transform)>,
+    ships_query: Query<(&Ship, &Transform), Without<EnemyFighter>>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let target = ships_query
+        .get_single()
+        .ok()
+        .filter(|(ship, _)| ship.respawn_delay <= 0.0 && ship.lives > 0);
+    for (mut fighter, transform) in fighters_query.iter_mut() {
+        fighter.shoot_delay -= time.delta_seconds() * time_scale.0;
+        let Some((_, ship_transform)) = target else {
+            continue;
+        };
+        if fighter.shoot_delay > 0.0 {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let heading = Vec2::from_angle(fighter.angle).rotate(Vec2::Y);
+        let to_ship = ship_transform.translation.truncate() - position;
+        if to_ship.length() > FIGHTER_FIRE_RANGE
+            || heading.angle_between(to_ship).abs() > FIGHTER_FIRE_ANGLE
+        {
+            continue;
+        }
+        fighter.shoot_delay = FIGHTER_SHOOT_INTERVAL;
+        commands.spawn(UfoLaserBundle::new(
+            &sprite_sheets.ufo,
+            position + heading * FIGHTER_RADIUS,
+            fighter.angle,
+            heading * FIGHTER_SHOT_SPEED,
+            FIGHTER_FIRE_RANGE / FIGHTER_SHOT_SPEED,
+        ));
+    }
+}
+
+fn fighter_destroy_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    fighters_query: Query<&Transform, With<EnemyFighter>>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = fighters_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        // A quicker, sparkier burst than the UFO's
+        commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
+        for _ in 0..16 {
+            let velocity = Vec2::from_angle(random::<f32>() * TAU) * lerp(40.0, 160.0, random());
+            commands.spawn(SparkParticleBundle::new(
+                position,
+                velocity,
+                -velocity,
+                &sprite_sheets.particles,
+            ));
+        }
+
+        let score_delta = FIGHTER_SCORE * score_multiplier.0;
+        score.increase(score_delta);
+        commands.spawn(ScorePopupBundle::new(
+            score_delta,
+            game_assets.font.clone(),
+            position,
+            20.0,
+        ));
+        commands.entity(event.entity).despawn();
+    }
+}
@@ -0,0 +1,70 @@
+use ab_glyph::Font as _;
+use bevy::prelude::*;
+
+/// The font used for all text, covering Latin, Greek and Cyrillic
+pub const PRIMARY_FONT: &str = "fonts/DejaVuSans.ttf";
+
+/// Fonts to draw text with, in order of preference. The primary font comes first,
+/// followed by any other fonts under `assets/fonts` for characters it lacks.
+#[derive(Default, Resource)]
+pub struct FontFallback {
+    pub fonts: Vec<Handle<Font>>,
+}
+
+impl FontFallback {
+    /// The first font that has a glyph for the character, or the primary font
+    pub fn font_for(&self, ch: char, fonts: &Assets<Font>) -> Handle<Font> {
+        self.fonts
+            .iter()
+            .find(|handle| {
+                fonts
+                    .get(handle)
+                    .is_some_and(|font| font.font.glyph_id(ch).0 != 0)
+            })
+            .or(self.fonts.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+    /// Splits the text into sections so that each character is drawn with a font that has it
+    pub fn sections(&self, text: &str, style: TextStyle, fonts: &Assets<Font>) -> Vec<TextSection> {
+        let mut sections: Vec<TextSection> = Vec::new();
+        for ch in text.chars() {
+            let font = self.font_for(ch, fonts);
+            match sections.last_mut() {
+                Some(section) if section.style.font == font => section.value.push(ch),
+                _ => sections.push(TextSection::new(
+                    ch,
+                    TextStyle {
+                        font,
+                        ..style.clone()
+                    },
+                )),
+            }
+        }
+        sections
+    }
+}
+
+pub struct FontsPlugin;
+impl Plugin for FontsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FontFallback>()
+            .add_systems(Startup, load_fonts);
+    }
+}
+
+fn load_fonts(asset_server: Res<AssetServer>, mut fallback: ResMut<FontFallback>) {
+    let primary = asset_server.load(PRIMARY_FONT);
+    let others = match asset_server.load_folder("fonts") {
+        Ok(handles) => handles
+            .into_iter()
+            .map(|handle| handle.typed::<Font>())
+            .filter(|handle| *handle != primary)
+            .collect(),
+        Err(err) => {
+            warn!("Could not load fallback fonts: {err}");
+            Vec::new()
+        }
+    };
+    fallback.fonts = std::iter::once(primary).chain(others).collect();
+}
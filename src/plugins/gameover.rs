@@ -0,0 +1,135 @@
+use crate::components::{AsteroidSize, ShipWeapon};
+use crate::input::InputState;
+use crate::plugins::{time_attack_finished, Localization, TimeAttackClock};
+use crate::resources::{GameAssets, GameMode, RunStats};
+use crate::utils::format_time;
+use crate::AppState;
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct GameOverEntity;
+
+pub struct GameOverPlugin;
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameOver), init_gameover)
+            .add_systems(
+                OnExit(AppState::GameOver),
+                crate::despawn_tagged::<GameOverEntity>,
+            )
+            .add_systems(Update, gameover_input.run_if(in_state(AppState::GameOver)));
+    }
+}
+
+fn init_gameover(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    run_stats: Res<RunStats>,
+    mode: Res<GameMode>,
+    clock: Res<TimeAttackClock>,
+    localization: Res<Localization>,
+) {
+    let texture = asset_server.load("img/gameover.png");
+    commands
+        .spawn(SpriteBundle {
+            texture,
+            ..default()
+        })
+        .insert(GameOverEntity);
+
+    let destroyed = |size: AsteroidSize| run_stats.asteroids_destroyed[size as usize];
+    let time_played = run_stats.time_played as u32;
+    let mut lines = vec![
+        localization.format(
+            "Levels cleared: {levels}",
+            &[("levels", &run_stats.levels_cleared)],
+        ),
+        localization.format(
+            "Asteroids destroyed: {large} large, {medium} medium, {small} small, {tiny} tiny",
+            &[
+                ("large", &destroyed(AsteroidSize::Large)),
+                ("medium", &destroyed(AsteroidSize::Medium)),
+                ("small", &destroyed(AsteroidSize::Small)),
+                ("tiny", &destroyed(AsteroidSize::Tiny)),
+            ],
+        ),
+        localization.format(
+            "UFOs destroyed: {ufos}",
+            &[("ufos", &run_stats.ufos_destroyed)],
+        ),
+        localization.format(
+            "Accuracy: {accuracy}% ({hit}/{fired})",
+            &[
+                ("accuracy", &format!("{:.0}", run_stats.accuracy() * 100.0)),
+                ("hit", &run_stats.shots_hit),
+                ("fired", &run_stats.shots_fired),
+            ],
+        ),
+        weapon_accuracy_line(&run_stats, &localization),
+        localization.format(
+            "Powerups collected: {powerups}",
+            &[("powerups", &run_stats.powerups_collected)],
+        ),
+        localization.format(
+            "Time played: {time}",
+            &[(
+                "time",
+                &format!("{}:{:02}", time_played / 60, time_played % 60),
+            )],
+        ),
+    ];
+
+    if *mode == GameMode::TimeAttack {
+        lines.push(if time_attack_finished(&run_stats) {
+            localization.format("Final time: {time}", &[("time", &format_time(clock.time))])
+        } else {
+            localization.get("Final time: did not finish").to_string()
+        });
+    }
+
+    let font = game_assets.font.clone();
+    for (i, line) in lines.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    line,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, -20.0 - i as f32 * 24.0, 0.01),
+                ..default()
+            })
+            .insert(GameOverEntity);
+    }
+}
+
+fn weapon_accuracy_line(run_stats: &RunStats, localization: &Localization) -> String {
+    let weapons: Vec<_> = ShipWeapon::ALL
+        .into_iter()
+        .filter_map(|weapon| {
+            let stats = run_stats.weapons.get(&weapon)?;
+            Some(format!(
+                "{} {:.0}% ({}/{})",
+                localization.get(weapon.name()),
+                stats.accuracy() * 100.0,
+                stats.hit,
+                stats.fired
+            ))
+        })
+        .collect();
+    if weapons.is_empty() {
+        localization.get("Weapons: none fired").to_string()
+    } else {
+        localization.format("Weapons: {weapons}", &[("weapons", &weapons.join(", "))])
+    }
+}
+
+fn gameover_input(input: Res<InputState>, mut next_state: ResMut<NextState<AppState>>) {
+    if input.ok {
+        next_state.set(AppState::HighScoreEntry)
+    }
+}
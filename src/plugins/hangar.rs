@@ -0,0 +1,198 @@
+use crate::input::InputState;
+use crate::plugins::profile::{PermanentBonus, Profile};
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet};
+use crate::resources::GameAssets;
+use crate::AppState;
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct HangarEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HangarItem {
+    Bonus(PermanentBonus),
+    Back,
+}
+
+#[derive(Component)]
+struct HangarItemText {
+    index: usize,
+    item: HangarItem,
+}
+
+#[derive(Component)]
+struct HangarPointsText;
+
+pub struct HangarPlugin;
+impl Plugin for HangarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Hangar), init_hangar)
+            .add_systems(
+                OnExit(AppState::Hangar),
+                crate::despawn_tagged::<HangarEntity>,
+            )
+            .add_systems(
+                Update,
+                (hangar_input, hangar_text_system.after(hangar_input))
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::Hangar)),
+            );
+    }
+}
+
+fn hangar_items() -> Vec<HangarItem> {
+    PermanentBonus::ALL
+        .into_iter()
+        .map(HangarItem::Bonus)
+        .chain(std::iter::once(HangarItem::Back))
+        .collect()
+}
+
+fn hangar_text(text: &str, font: Handle<Font>, position: Vec2, size: f32) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(
+            text,
+            TextStyle {
+                font,
+                font_size: size,
+                color: Color::WHITE,
+            },
+        ),
+        text_anchor: Anchor::CenterLeft,
+        transform: Transform::from_translation(position.extend(0.1)),
+        ..default()
+    }
+}
+
+fn init_hangar(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    profile: Res<Profile>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn(MenuNavigation::new(hangar_items().len()))
+        .insert(HangarEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(HangarEntity);
+
+    let font = game_assets.font.clone();
+    let left = -250.0;
+    commands
+        .spawn(hangar_text(
+            localization.get("Hangar"),
+            font.clone(),
+            Vec2::new(left, 180.0),
+            32.0,
+        ))
+        .insert(HangarEntity);
+    commands
+        .spawn(hangar_text("", font.clone(), Vec2::new(left, 140.0), 20.0))
+        .insert(HangarPointsText)
+        .insert(HangarEntity);
+    for (index, item) in hangar_items().into_iter().enumerate() {
+        let position = Vec2::new(left, 80.0 - index as f32 * 32.0);
+        commands
+            .spawn(hangar_text("", font.clone(), position, 20.0))
+            .insert(HangarItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(HangarEntity);
+    }
+
+    let shots = profile.lifetime_shots();
+    let accuracy = localization.format(
+        "Lifetime accuracy: {accuracy}% ({hit}/{fired})",
+        &[
+            ("accuracy", &format!("{:.0}", shots.accuracy() * 100.0)),
+            ("hit", &shots.hit),
+            ("fired", &shots.fired),
+        ],
+    );
+    commands
+        .spawn(hangar_text(&accuracy, font, Vec2::new(left, -140.0), 16.0))
+        .insert(HangarEntity);
+}
+
+fn hangar_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let items = hangar_items();
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.back {
+        next_state.set(AppState::Title);
+    } else if input.ok {
+        match items[menu.selected] {
+            HangarItem::Bonus(bonus) => {
+                if profile.buy(bonus) {
+                    if let Err(err) = profile.save() {
+                        error!("Could not save profile: {err}");
+                    }
+                }
+            }
+            HangarItem::Back => next_state.set(AppState::Title),
+        }
+    }
+}
+
+fn hangar_text_system(
+    menu_query: Query<&MenuNavigation>,
+    profile: Res<Profile>,
+    localization: Res<Localization>,
+    mut items_query: Query<(&HangarItemText, &mut Text)>,
+    mut points_query: Query<&mut Text, (With<HangarPointsText>, Without<HangarItemText>)>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for mut text in points_query.iter_mut() {
+        text.sections[0].value = localization.format(
+            "Unlock points: {points}",
+            &[("points", &profile.unlock_points)],
+        );
+    }
+    for (item_text, mut text) in items_query.iter_mut() {
+        let (label, color) = match item_text.item {
+            HangarItem::Bonus(bonus) => {
+                let level = profile.bonus_level(bonus);
+                let status = if level >= bonus.max_level() {
+                    localization.get("max").to_string()
+                } else {
+                    localization.format("{cost} points", &[("cost", &bonus.cost())])
+                };
+                let color = if profile.can_buy(bonus) {
+                    Color::WHITE
+                } else {
+                    Color::GRAY
+                };
+                (
+                    format!(
+                        "{} {}/{} - {}",
+                        localization.get(bonus.name()),
+                        level,
+                        bonus.max_level(),
+                        status
+                    ),
+                    color,
+                )
+            }
+            HangarItem::Back => (localization.get("Back").to_string(), Color::WHITE),
+        };
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label
+        };
+        text.sections[0].style.color = if selected { Color::YELLOW } else { color };
+    }
+}
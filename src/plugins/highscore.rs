@@ -1,38 +1,94 @@
 use crate::components::Fading;
 use crate::constants::*;
 use crate::input::InputState;
-use crate::resources::Score;
+use crate::plugins::{
+    time_attack_finished, FontFallback, Localization, QuitToDesktop, TimeAttackClock,
+};
+use crate::resources::{GameAssets, GameMode, RunStats, Score};
+use crate::utils::{crypt, format_time};
 use crate::AppState;
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{ErrorKind, Read, Write};
 
 #[derive(Component)]
 struct HighScoreEntity;
 
+/// Characters on the name entry keyboard, laid out in rows of `NAME_KEYBOARD_COLUMNS`
+const NAME_CHARS: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZÅÄÖÜÉÈÁÀÇÑØÆßΑΒΓΔΘΛΠΣΦΨΩБГДЖЗИЛПФЦЧШЩЫЭЮЯ0123456789-.! ";
+const NAME_KEYBOARD_COLUMNS: usize = 13;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NameKey {
+    Char(char),
+    Backspace,
+    Done,
+}
+
 #[derive(Component)]
-struct HighScoreEntryLetter {
-    index: i32,
-    blinking: bool,
+struct NameKeyText {
+    row: usize,
+    column: usize,
+    key: NameKey,
+}
+
+#[derive(Component)]
+struct NameText;
+
+/// Name being entered and the keyboard key under the cursor, as (row, column)
+#[derive(Resource, Default)]
+struct NameEntry {
+    name: String,
+    cursor: (usize, usize),
+}
+
+fn name_keyboard() -> Vec<Vec<NameKey>> {
+    let chars: Vec<_> = NAME_CHARS.chars().map(NameKey::Char).collect();
+    chars
+        .chunks(NAME_KEYBOARD_COLUMNS)
+        .map(<[NameKey]>::to_vec)
+        .chain([vec![NameKey::Backspace, NameKey::Done]])
+        .collect()
 }
 
 pub struct HighScoreEntry {
     name: String,
     score: u32,
+    assisted: bool,
+    /// Board the entry is on
+    mode: GameMode,
 }
 
+/// High score tables of every game mode
 #[derive(Resource)]
 pub struct HighScore {
-    pub entries: Vec<HighScoreEntry>,
+    boards: HashMap<GameMode, Vec<HighScoreEntry>>,
 }
 
+/// Board and page of it shown on the high score screen
+#[derive(Resource, Default)]
+struct HighScoreTab {
+    mode: GameMode,
+    page: usize,
+}
+
+#[derive(Component)]
+struct HighScorePageText;
+
+#[derive(Component)]
+struct HighScoreTabText(GameMode);
+
+/// Entries of the board being shown, replaced when switching tabs
+#[derive(Component)]
+struct HighScoreRow;
+
 pub struct HighScorePlugin;
 impl Plugin for HighScorePlugin {
     fn build(&self, app: &mut App) {
-        let high_score = HighScore::load().unwrap_or(HighScore {
-            entries: Vec::new(),
-        });
-        app.insert_resource(high_score)
+        app.insert_resource(HighScore::load_all())
+            .init_resource::<NameEntry>()
+            .init_resource::<HighScoreTab>()
             .add_systems(OnEnter(AppState::HighScore), init_highscore)
             .add_systems(
                 OnExit(AppState::HighScore),
@@ -40,7 +96,11 @@ impl Plugin for HighScorePlugin {
             )
             .add_systems(
                 Update,
-                highscore_input.run_if(in_state(AppState::HighScore)),
+                (
+                    highscore_input,
+                    highscore_board_system.after(highscore_input),
+                )
+                    .run_if(in_state(AppState::HighScore)),
             )
             .add_systems(OnEnter(AppState::HighScoreEntry), init_highscore_entry)
             .add_systems(
@@ -49,7 +109,10 @@ impl Plugin for HighScorePlugin {
             )
             .add_systems(
                 Update,
-                (highscore_entry_input, highscore_entry_letter_blink)
+                (
+                    highscore_entry_input,
+                    name_entry_text_system.after(highscore_entry_input),
+                )
                     .run_if(in_state(AppState::HighScoreEntry)),
             );
     }
@@ -58,7 +121,10 @@ impl Plugin for HighScorePlugin {
 fn init_highscore(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    high_score: Res<HighScore>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+    mode: Res<GameMode>,
+    mut tab: ResMut<HighScoreTab>,
 ) {
     let texture = asset_server.load("img/highscores.png");
     commands
@@ -67,44 +133,192 @@ fn init_highscore(
             ..default()
         })
         .insert(HighScoreEntity);
-    let entries = high_score.entries.len() as i32;
+    // Opens on the board of the mode last played, flagging the tab changed either way
+    *tab = HighScoreTab {
+        mode: *mode,
+        page: 0,
+    };
+
+    let tab_width = GAME_WIDTH as f32 / GameMode::ALL.len() as f32;
+    for (i, mode) in GameMode::ALL.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    localization.get(mode.name()),
+                    TextStyle {
+                        font: game_assets.font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(
+                    (i as f32 + 0.5) * tab_width - GAME_WIDTH as f32 / 2.0,
+                    30.0,
+                    0.1,
+                ),
+                ..default()
+            })
+            .insert(HighScoreTabText(mode))
+            .insert(HighScoreEntity);
+    }
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 20.0,
+                    color: Color::GRAY,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -GAME_HEIGHT as f32 / 2.0 + 20.0, 0.1),
+            ..default()
+        })
+        .insert(HighScorePageText)
+        .insert(HighScoreEntity);
+}
+
+fn page_count(entries: usize) -> usize {
+    ((entries + HIGH_SCORE_PAGE_ENTRIES - 1) / HIGH_SCORE_PAGE_ENTRIES).max(1)
+}
+
+/// Lists the entries on the selected page of the board and highlights its tab
+fn highscore_board_system(
+    mut commands: Commands,
+    tab: Res<HighScoreTab>,
+    high_score: Res<HighScore>,
+    rows_query: Query<Entity, With<HighScoreRow>>,
+    mut tabs_query: Query<(&HighScoreTabText, &mut Text), Without<HighScorePageText>>,
+    mut page_query: Query<&mut Text, With<HighScorePageText>>,
+    font_fallback: Res<FontFallback>,
+    fonts: Res<Assets<Font>>,
+    localization: Res<Localization>,
+) {
+    if !tab.is_changed() {
+        return;
+    }
+    for (tab_text, mut text) in tabs_query.iter_mut() {
+        text.sections[0].style.color = if tab_text.0 == tab.mode {
+            Color::YELLOW
+        } else {
+            Color::GRAY
+        };
+    }
+    for entity in rows_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let board = high_score.board(tab.mode);
+    let pages = page_count(board.len());
+    for mut text in page_query.iter_mut() {
+        text.sections[0].value = if pages > 1 {
+            localization.format(
+                "Page {page}/{pages}",
+                &[("page", &(tab.page + 1)), ("pages", &pages)],
+            )
+        } else {
+            String::new()
+        };
+    }
+    let first = tab.page * HIGH_SCORE_PAGE_ENTRIES;
+    let page: Vec<_> = board
+        .iter()
+        .skip(first)
+        .take(HIGH_SCORE_PAGE_ENTRIES)
+        .collect();
+    let entries = page.len() as i32;
     let rows_per_column = 5;
     let columns = entries / (rows_per_column + 1) + 1;
     let column_padding = if entries % columns == 0 { 0 } else { 1 };
     let column_size = entries / columns + column_padding;
 
-    let font = asset_server.load("fonts/DejaVuSans.ttf");
-    for (i, entry) in high_score.entries.iter().enumerate() {
+    let style = TextStyle {
+        font: default(),
+        font_size: 32.0,
+        color: Color::WHITE,
+    };
+    for (i, entry) in page.iter().enumerate() {
         let column = (i as i32 / column_size) as f32;
         let row = (i as i32 % column_size) as f32;
         let position = Vec2::new(
             (column + 0.5) * GAME_WIDTH as f32 / columns as f32 - GAME_WIDTH as f32 / 2.0,
             -(row + 0.5) * 40.0,
         );
+        let rank = (first + i) as u32 + 1;
+        let mut text = format!(
+            "{rank}. {} - {}",
+            entry.name,
+            format_value(entry.mode, entry.score)
+        );
+        if entry.assisted {
+            text = format!("{text} {}", localization.get("(assisted)"));
+        }
         commands
             .spawn(HighScoreText::new(
                 position,
                 i as u32 + 1,
-                &entry.name,
-                entry.score,
-                font.clone(),
+                font_fallback.sections(&text, style.clone(), &fonts),
             ))
+            .insert(HighScoreRow)
             .insert(HighScoreEntity);
     }
 }
 
-fn highscore_input(input: Res<InputState>, mut next_state: ResMut<NextState<AppState>>) {
-    if input.ok {
+fn highscore_input(
+    input: Res<InputState>,
+    high_score: Res<HighScore>,
+    mut tab: ResMut<HighScoreTab>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if input.ok || input.back {
         next_state.set(AppState::Title)
+    } else if input.menu_left || input.menu_right {
+        let modes = GameMode::ALL.len();
+        let step = if input.menu_left { modes - 1 } else { 1 };
+        let index = GameMode::ALL
+            .iter()
+            .position(|mode| *mode == tab.mode)
+            .unwrap_or(0);
+        *tab = HighScoreTab {
+            mode: GameMode::ALL[(index + step) % modes],
+            page: 0,
+        };
+    } else if input.up || input.down {
+        let pages = page_count(high_score.board(tab.mode).len());
+        if pages > 1 {
+            let step = if input.up { pages - 1 } else { 1 };
+            tab.page = (tab.page + step) % pages;
+        }
+    }
+}
+
+/// The value a run is ranked by, if it can be ranked at all
+fn run_result(
+    mode: GameMode,
+    score: &Score,
+    run_stats: &RunStats,
+    clock: &TimeAttackClock,
+) -> Option<u32> {
+    match mode {
+        GameMode::TimeAttack if time_attack_finished(run_stats) => Some(clock.hundredths()),
+        GameMode::TimeAttack => None,
+        _ => Some(score.value()),
     }
 }
 
 fn init_highscore_entry(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
     high_score: Res<HighScore>,
+    mode: Res<GameMode>,
     score: Res<Score>,
+    run_stats: Res<RunStats>,
+    clock: Res<TimeAttackClock>,
+    font_fallback: Res<FontFallback>,
+    fonts: Res<Assets<Font>>,
+    mut name_entry: ResMut<NameEntry>,
 ) {
+    *name_entry = NameEntry::default();
     let texture = asset_server.load("img/gameover.png");
     commands
         .spawn(SpriteBundle {
@@ -113,118 +327,157 @@ fn init_highscore_entry(
         })
         .insert(HighScoreEntity);
 
-    let is_high_score = high_score.entries.len() < MAX_HIGH_SCORE_ENTRIES
-        || high_score.entries.iter().any(|hs| hs.score < score.value());
+    let is_high_score = run_result(*mode, &score, &run_stats, &clock)
+        .is_some_and(|value| high_score.qualifies(*mode, value));
+    if !is_high_score {
+        return;
+    }
+    info!("New high score!");
+
+    let font = game_assets.font.clone();
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -40.0, 0.01),
+            ..default()
+        })
+        .insert(NameText)
+        .insert(HighScoreEntity);
 
-    let font = asset_server.load("fonts/DejaVuSans.ttf");
-    if is_high_score {
-        info!("New high score!");
-        for i in 0..NUM_HIGH_SCORE_ENTRY_LETTERS {
-            let x = (i as i32 * 40 - (NUM_HIGH_SCORE_ENTRY_LETTERS + 1) * 20) as f32;
+    let key_width = 36.0;
+    let left = -(NAME_KEYBOARD_COLUMNS as f32 - 1.0) * key_width / 2.0;
+    for (row, keys) in name_keyboard().into_iter().enumerate() {
+        for (column, key) in keys.into_iter().enumerate() {
+            let (font, x) = match key {
+                NameKey::Char(ch) => (
+                    font_fallback.font_for(ch, &fonts),
+                    left + column as f32 * key_width,
+                ),
+                NameKey::Backspace => (font.clone(), -80.0),
+                NameKey::Done => (font.clone(), 80.0),
+            };
             commands
                 .spawn(Text2dBundle {
                     text: Text::from_section(
-                        "A".to_string(),
+                        "",
                         TextStyle {
-                            font: font.clone(),
-                            font_size: 32.0,
+                            font,
+                            font_size: 24.0,
                             color: Color::WHITE,
                         },
                     ),
-                    transform: Transform::from_xyz(x, -70.0, 0.01),
+                    transform: Transform::from_xyz(x, -90.0 - row as f32 * 28.0, 0.01),
                     ..default()
                 })
-                .insert(HighScoreEntryLetter {
-                    index: i,
-                    blinking: i == 0,
-                })
+                .insert(NameKeyText { row, column, key })
                 .insert(HighScoreEntity);
         }
     }
 }
 
 fn highscore_entry_input(
-    mut letters: Query<(&mut HighScoreEntryLetter, &mut Text)>,
+    keys: Query<&NameKeyText>,
     input: Res<InputState>,
-    mut selected: Local<i32>,
+    mut name_entry: ResMut<NameEntry>,
     mut next_state: ResMut<NextState<AppState>>,
     mut high_score: ResMut<HighScore>,
+    mode: Res<GameMode>,
     score: Res<Score>,
+    run_stats: Res<RunStats>,
+    clock: Res<TimeAttackClock>,
+    quit_to_desktop: Res<QuitToDesktop>,
 ) {
-    if input.ok {
-        if letters.is_empty() {
+    if keys.is_empty() {
+        if input.ok || quit_to_desktop.0 {
             next_state.set(AppState::HighScore);
-        } else {
-            *selected += 1;
-            if *selected == NUM_HIGH_SCORE_ENTRY_LETTERS {
-                // FIXME: This is horrible, but it works
-                let mut indexed_letters: Vec<_> = letters
-                    .iter()
-                    .map(|(letter, text)| (letter.index, &text.sections[0].value))
-                    .collect();
-                indexed_letters.sort_unstable_by_key(|(index, _)| *index);
-                let name: String = indexed_letters
-                    .iter()
-                    .map(|&(_, letter)| letter.clone())
-                    .collect();
-                high_score.entries.push(HighScoreEntry {
-                    name,
-                    score: score.value(),
-                });
-                high_score
-                    .entries
-                    .sort_by_key(|entry| -(entry.score as i64));
-                high_score
-                    .entries
-                    .truncate(NUM_HIGH_SCORE_ENTRY_LETTERS as usize);
-                high_score.save().expect("Could not save high score!");
-                *selected = 0;
-                next_state.set(AppState::HighScore);
-            }
         }
+        return;
+    }
+
+    let keyboard = name_keyboard();
+    let (mut row, mut column) = name_entry.cursor;
+    if input.up {
+        row = (row + keyboard.len() - 1) % keyboard.len();
+    }
+    if input.down {
+        row = (row + 1) % keyboard.len();
+    }
+    column = column.min(keyboard[row].len() - 1);
+    if input.menu_left {
+        column = (column + keyboard[row].len() - 1) % keyboard[row].len();
+    }
+    if input.menu_right {
+        column = (column + 1) % keyboard[row].len();
+    }
+    name_entry.cursor = (row, column);
+
+    let full = name_entry.name.chars().count() >= MAX_NAME_LENGTH;
+    if input.back {
+        name_entry.name.pop();
+    }
+    if !input.ok {
+        return;
     }
-    const CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-    for (mut letter, mut text) in letters.iter_mut() {
-        let selected = letter.index == *selected;
-        letter.blinking = selected;
-        if selected {
-            if input.up {
-                let ch = text.sections[0].value.chars().next().unwrap();
-                let new_ch = CHARS
-                    .chars()
-                    .cycle()
-                    .skip_while(|c| *c != ch)
-                    .skip(1)
-                    .next()
-                    .unwrap();
-                text.sections[0].value = new_ch.to_string();
+    match keyboard[row][column] {
+        NameKey::Char(ch) if !full => {
+            name_entry.name.push(ch);
+            // Jump to the finishing key once the name is as long as it gets
+            if name_entry.name.chars().count() >= MAX_NAME_LENGTH {
+                name_entry.cursor = (keyboard.len() - 1, 1);
             }
-            if input.down {
-                let ch = text.sections[0].value.chars().next().unwrap();
-                let new_ch = CHARS
-                    .chars()
-                    .rev()
-                    .cycle()
-                    .skip_while(|c| *c != ch)
-                    .skip(1)
-                    .next()
-                    .unwrap();
-                text.sections[0].value = new_ch.to_string();
+        }
+        NameKey::Char(_) => (),
+        NameKey::Backspace => {
+            name_entry.name.pop();
+        }
+        NameKey::Done => {
+            let name = name_entry.name.trim().to_string();
+            if name.is_empty() {
+                return;
             }
+            let value = run_result(*mode, &score, &run_stats, &clock)
+                .expect("Name entered for an unranked run");
+            high_score.insert(HighScoreEntry {
+                name,
+                score: value,
+                assisted: run_stats.assisted,
+                mode: *mode,
+            });
+            high_score.save(*mode).expect("Could not save high score!");
+            next_state.set(AppState::HighScore);
         }
     }
 }
 
-fn highscore_entry_letter_blink(
-    mut letters: Query<(&HighScoreEntryLetter, &mut Visibility)>,
-    time: Res<Time>,
+fn name_entry_text_system(
+    name_entry: Res<NameEntry>,
+    localization: Res<Localization>,
+    mut name_query: Query<&mut Text, (With<NameText>, Without<NameKeyText>)>,
+    mut keys_query: Query<(&NameKeyText, &mut Text)>,
 ) {
-    for (letter, mut visibility) in letters.iter_mut() {
-        *visibility = if letter.blinking && time.elapsed_seconds_wrapped().rem_euclid(0.4) < 0.2 {
-            Visibility::Hidden
+    for mut text in name_query.iter_mut() {
+        let padding = MAX_NAME_LENGTH.saturating_sub(name_entry.name.chars().count());
+        text.sections[0].value = format!("{}{}", name_entry.name, "_".repeat(padding));
+    }
+    for (key, mut text) in keys_query.iter_mut() {
+        let selected = (key.row, key.column) == name_entry.cursor;
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
         } else {
-            Visibility::Visible
-        }
+            Color::WHITE
+        };
+        text.sections[0].value = match key.key {
+            NameKey::Char(ch) => ch.to_string(),
+            NameKey::Backspace => localization.get("Delete").to_string(),
+            NameKey::Done => localization.get("Done").to_string(),
+        };
     }
 }
 
@@ -234,60 +487,128 @@ struct HighScoreText {
     fading: Fading,
 }
 impl HighScoreText {
-    fn new(position: Vec2, rank: u32, name: &str, score: u32, font: Handle<Font>) -> Self {
+    /// Fades in over `order` seconds, so that the rows appear one after another
+    fn new(position: Vec2, order: u32, sections: Vec<TextSection>) -> Self {
         HighScoreText {
             text: Text2dBundle {
-                text: Text::from_section(
-                    format!("{rank}. {name} - {score}"),
-                    TextStyle {
-                        font,
-                        font_size: 32.0,
-                        color: Color::WHITE,
-                    },
-                ),
+                text: Text::from_sections(sections),
                 transform: Transform::from_translation(position.extend(0.1)),
                 ..default()
             },
             fading: Fading {
                 from: 0.0,
                 to: 1.0,
-                duration: rank as f32,
+                duration: order as f32,
                 elapsed: 0.0,
             },
         }
     }
 }
 
+fn ranks_above(mode: GameMode, value: u32, other: u32) -> bool {
+    match mode {
+        GameMode::TimeAttack => value < other,
+        _ => value > other,
+    }
+}
+
+fn format_value(mode: GameMode, value: u32) -> String {
+    match mode {
+        GameMode::TimeAttack => format_time(value as f32 / 100.0),
+        _ => value.to_string(),
+    }
+}
+
 impl HighScore {
-    fn crypt(content: &[u8]) -> Vec<u8> {
-        let key = "Space Rocks!".as_bytes().into_iter().cycle();
-        content.iter().zip(key).map(|(a, b)| a ^ b).collect()
+    fn file_name(mode: GameMode) -> &'static str {
+        match mode {
+            GameMode::Classic => "highscore.enc",
+            GameMode::Endless => "highscore-endless.enc",
+            GameMode::TimeAttack => "besttimes.enc",
+            GameMode::Survival => "highscore-survival.enc",
+        }
     }
-    fn save(&self) -> std::io::Result<()> {
+    fn board(&self, mode: GameMode) -> &[HighScoreEntry] {
+        self.boards.get(&mode).map_or(&[], Vec::as_slice)
+    }
+    /// Whether a run of the mode would make it onto its board
+    fn qualifies(&self, mode: GameMode, value: u32) -> bool {
+        let board = self.board(mode);
+        board.len() < MAX_HIGH_SCORE_ENTRIES
+            || board
+                .iter()
+                .any(|entry| ranks_above(mode, value, entry.score))
+    }
+    /// Adds an entry to the board of its mode, keeping the board ranked and capped
+    fn insert(&mut self, entry: HighScoreEntry) {
+        let mode = entry.mode;
+        let board = self.boards.entry(mode).or_default();
+        board.push(entry);
+        board.sort_by_key(|entry| match mode {
+            // Best times are the lowest ones
+            GameMode::TimeAttack => entry.score as i64,
+            _ => -(entry.score as i64),
+        });
+        board.truncate(MAX_HIGH_SCORE_ENTRIES);
+    }
+    fn load_all() -> Self {
+        let boards = GameMode::ALL
+            .into_iter()
+            .map(|mode| {
+                let board = match HighScore::load(mode) {
+                    Ok(board) => board,
+                    Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+                    Err(err) => {
+                        warn!("Could not load the {mode:?} high scores, starting over: {err}");
+                        Vec::new()
+                    }
+                };
+                (mode, board)
+            })
+            .collect();
+        HighScore { boards }
+    }
+    fn save(&self, mode: GameMode) -> std::io::Result<()> {
         let content: String = self
-            .entries
+            .board(mode)
             .iter()
-            .map(|e| format!("{}:{}\n", e.name, e.score))
+            .map(|e| {
+                let assisted = if e.assisted { ":assisted" } else { "" };
+                format!("{}:{}{assisted}\n", e.name, e.score)
+            })
             .collect();
-        let encoded = HighScore::crypt(&content.as_bytes());
-        let mut file = File::create("highscore.enc")?;
+        let encoded = crypt(&content.as_bytes());
+        let mut file = File::create(HighScore::file_name(mode))?;
         file.write_all(&encoded)?;
         Ok(())
     }
-    fn load() -> std::io::Result<Self> {
-        let mut file = File::open("highscore.enc")?;
+    fn load(mode: GameMode) -> std::io::Result<Vec<HighScoreEntry>> {
+        let mut file = File::open(HighScore::file_name(mode))?;
         let mut content = Vec::new();
         file.read_to_end(&mut content)?;
-        let decoded = HighScore::crypt(&content);
-        let entries: Vec<_> = std::str::from_utf8(&decoded)
-            .expect("Invalid high score file!")
-            .split(|ch| ch == '\n')
+        let decoded = crypt(&content);
+        let entries = std::str::from_utf8(&decoded)
+            .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?
+            .lines()
             .filter_map(|e| e.split_once(':'))
-            .map(|(name, score_str)| HighScoreEntry {
-                name: name.to_string(),
-                score: score_str.parse().expect("Invalid high score file!"),
+            .filter_map(|(name, rest)| {
+                // Entries from before assists have no flag
+                let (score_str, assisted) = match rest.split_once(':') {
+                    Some((score_str, flag)) => (score_str, flag == "assisted"),
+                    None => (rest, false),
+                };
+                let Ok(score) = score_str.parse() else {
+                    warn!("Skipping high score entry with a bad score: {name}:{rest}");
+                    return None;
+                };
+                Some(HighScoreEntry {
+                    name: name.to_string(),
+                    score,
+                    assisted,
+                    mode,
+                })
             })
             .collect();
-        Ok(HighScore { entries })
+        Ok(entries)
     }
 }
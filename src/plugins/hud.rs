@@ -1,130 +1,931 @@
-use crate::{components::*, constants::*, resources::*, AppState};
+use crate::{
+    bundles::powerup_sprite,
+    components::*,
+    constants::*,
+    plugins::{Announcement, Localization, Settings, SurvivalWave, Turret},
+    resources::*,
+    AppState,
+};
 use bevy::{prelude::*, sprite::Anchor};
+use std::f32::consts::TAU;
 
 pub struct HudPlugin;
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                update_hud_system,
-                update_hud_text_system.after(update_hud_system),
-            )
-                .run_if(in_state(AppState::InGame)),
-        );
+        app.init_resource::<PreviousLives>()
+            .add_systems(OnEnter(AppState::NewGame), reset_previous_lives)
+            .add_systems(
+                Update,
+                (
+                    update_hud_system,
+                    score_popup_system,
+                    update_hud_text_system.after(update_hud_system),
+                    charge_gauge_system,
+                    beam_gauge_system,
+                    health_bar_system,
+                    heat_bar_system,
+                    shield_pips_system,
+                    timed_effect_strip_system,
+                    modifier_icons_system,
+                    level_progress_system,
+                    last_life_warning_system,
+                    weapon_icons_system,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            );
     }
 }
+
+/// Position of something laid out for the HUD in the top left corner, mirrored over to
+/// the corner chosen in the settings
+fn hud_transform(settings: &Settings, x: f32, y: f32, z: f32) -> Transform {
+    let mirror = settings.hud_corner.mirror();
+    Transform::from_xyz(x * mirror.x, y * mirror.y, z)
+}
+
+fn hud_anchor(settings: &Settings, anchor: Anchor) -> Anchor {
+    let mirror = settings.hud_corner.mirror();
+    if mirror == Vec2::ONE {
+        anchor
+    } else {
+        Anchor::Custom(anchor.as_vec() * mirror)
+    }
+}
+
+fn hud_color(settings: &Settings, color: Color) -> Color {
+    color.with_a(color.a() * settings.hud_opacity)
+}
+
+#[derive(Component)]
+pub struct ChargeGauge;
+
+const CHARGE_GAUGE_WIDTH: f32 = 120.0;
+
+/// Beam length left to fire, drawn over the `capacity` the beam recharges up to
+#[derive(Component)]
+pub struct BeamGauge {
+    capacity: bool,
+}
+
+/// Pixels of gauge per pixel of beam length
+const BEAM_GAUGE_SCALE: f32 = 0.75;
+
+#[derive(Component)]
+pub struct HealthBar;
+
+const HEALTH_BAR_WIDTH: f32 = 120.0;
+
+#[derive(Component)]
+pub struct HeatBar;
+
+const HEAT_BAR_WIDTH: f32 = 120.0;
+
+/// One pip per `SHIELD_PIP_ENERGY` of shield, `background` ones showing the shield's capacity
+#[derive(Component)]
+pub struct ShieldPip {
+    index: usize,
+    background: bool,
+}
+
+const SHIELD_PIP_WIDTH: f32 = 20.0;
+const SHIELD_PIP_SPACING: f32 = 25.0;
+
+#[derive(Component)]
+pub struct TimedEffectIcon;
+
+#[derive(Component)]
+pub struct TimedEffectBar;
+
+const TIMED_EFFECT_SPACING: f32 = 36.0;
+const TIMED_EFFECT_BAR_WIDTH: f32 = 28.0;
+
+#[derive(Component)]
+pub struct ModifierIcons;
+
+/// Edge of the red border around the play area while on the last life
+#[derive(Component)]
+pub struct LastLifeBorder;
+
+/// Lives the ship had on the previous frame, `None` before the first frame of a run
+#[derive(Resource, Default)]
+struct PreviousLives(Option<u8>);
+
+fn reset_previous_lives(mut previous_lives: ResMut<PreviousLives>) {
+    previous_lives.0 = None;
+}
+
+/// What's left between the player and the end of the level, with the most asteroids
+/// seen this level as `total` for the bar to count down from
+#[derive(Component, Default)]
+pub struct LevelProgress {
+    total: u32,
+}
+
+#[derive(Component)]
+pub struct LevelProgressBar;
+
+const LEVEL_PROGRESS_BAR_WIDTH: f32 = 120.0;
+
+/// Roughly where the score sits in the HUD text, for score popups to fly to
+const SCORE_POPUP_TARGET: Vec2 = Vec2::new(
+    -(GAME_WIDTH as f32) / 2.0 + 170.0,
+    (GAME_HEIGHT as f32) / 2.0 - 16.0,
+);
+
+#[derive(Component)]
+pub struct WeaponIcon(ShipWeapon);
+
+#[derive(Component)]
+pub struct WeaponIconSprite;
+
+#[derive(Component)]
+pub struct WeaponIconFrame;
+
+#[derive(Component)]
+pub struct WeaponCooldownOverlay;
+
+#[derive(Component)]
+pub struct WeaponLevelPip(u8);
+
+#[derive(Component)]
+pub struct WeaponAmmoText;
+
+const WEAPON_ICON_SPACING: f32 = 36.0;
+const WEAPON_ICON_SIZE: f32 = 28.0;
+const WEAPON_LEVEL_PIPS: u8 = 8;
+
 #[derive(Component, Default, PartialEq, Eq)]
 pub struct HUD {
     pub level: u32,
+    pub wave: Option<u32>,
     pub score: u32,
+    pub minerals: u32,
     pub lives: u8,
-    pub weapon: ShipWeapon,
-    pub weapon_rapid_level: u8,
-    pub weapon_spread_level: u8,
-    pub weapon_beam_level: u8,
-    pub weapon_plasma_level: u8,
-    pub changed: bool,
+    pub hyperspace_jumps: u8,
 }
 
 fn update_hud_system(
     ships_query: Query<&Ship>,
+    popups_query: Query<&ScorePopup>,
     score: Res<Score>,
+    minerals: Res<Minerals>,
     level: Res<Level>,
+    mode: Res<GameMode>,
+    wave: Res<SurvivalWave>,
     mut hud_query: Query<&mut HUD>,
     mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut shown_score: Local<f32>,
 ) {
     let ship = ships_query.single();
+    // Points still flying over in popups get counted once they land
+    let in_flight: u32 = popups_query.iter().map(|popup| popup.amount).sum();
+    let target = score.value().saturating_sub(in_flight) as f32;
+    if target < *shown_score {
+        // A new game started
+        *shown_score = target;
+    } else {
+        *shown_score += (target - *shown_score) * (SCORE_ROLL_RATE * time.delta_seconds()).min(1.0);
+        if target - *shown_score < 1.0 {
+            *shown_score = target;
+        }
+    }
     let new_hud = HUD {
         level: level.number(),
-        score: score.value(),
+        wave: (*mode == GameMode::Survival).then_some(wave.0),
+        score: shown_score.round() as u32,
+        minerals: minerals.value(),
         lives: ship.lives,
-        weapon: ship.weapon,
-        weapon_rapid_level: ship.weapon_rapid_level,
-        weapon_spread_level: ship.weapon_spread_level,
-        weapon_beam_level: ship.weapon_beam_level,
-        weapon_plasma_level: ship.weapon_plasma_level,
-        changed: false,
+        hyperspace_jumps: ship.hyperspace_jumps,
     };
     if hud_query.is_empty() {
-        commands.spawn(HUD {
-            changed: true,
-            ..new_hud
-        });
+        commands
+            .spawn(Text2dBundle {
+                text: Text {
+                    sections: vec![TextSection::new(
+                        "",
+                        TextStyle {
+                            font: game_assets.font.clone(),
+                            font_size: if settings.hud_compact { 16.0 } else { 20.0 },
+                            color: hud_color(&settings, Color::WHITE),
+                        },
+                    )],
+                    alignment: TextAlignment::Left,
+                    ..default()
+                },
+                text_anchor: hud_anchor(&settings, Anchor::TopRight),
+                transform: hud_transform(
+                    &settings,
+                    -(GAME_WIDTH as f32) / 2.05,
+                    (GAME_HEIGHT as f32) / 2.05,
+                    0.5,
+                ),
+                ..default()
+            })
+            .insert(new_hud)
+            .insert(LevelEntity);
     } else {
-        let mut hud = hud_query.single_mut();
-        if *hud != new_hud {
-            *hud = new_hud;
-            hud.changed = true;
-        }
+        // Only flags the HUD as changed when something shown on it actually differs
+        hud_query.single_mut().set_if_neq(new_hud);
+    }
+}
+
+/// Holds score popups in place for a moment, then flies them into the HUD score
+fn score_popup_system(
+    mut popups_query: Query<(&mut ScorePopup, &mut Transform)>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let target = SCORE_POPUP_TARGET * settings.hud_corner.mirror();
+    for (mut popup, mut transform) in popups_query.iter_mut() {
+        popup.elapsed += time.delta_seconds();
+        let flight = ((popup.elapsed - SCORE_POPUP_HOLD) / SCORE_POPUP_FLIGHT).clamp(0.0, 1.0);
+        // Eases in so that it starts slow and zips off
+        let position = popup.from.lerp(target, flight * flight);
+        transform.translation = position.extend(transform.translation.z);
+        transform.scale = Vec3::splat(1.0 - 0.5 * flight);
     }
 }
 
 fn update_hud_text_system(
+    mut hud_query: Query<(&HUD, &mut Text), Changed<HUD>>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+) {
+    let Ok((hud, mut text)) = hud_query.get_single_mut() else {
+        return;
+    };
+    if settings.hud_compact {
+        let progress = match hud.wave {
+            Some(wave) => localization.format("W{wave}", &[("wave", &wave)]),
+            None => localization.format("L{level}", &[("level", &hud.level)]),
+        };
+        text.sections[0].value = localization.format(
+            "{progress} | {score} | x{lives}",
+            &[
+                ("progress", &progress),
+                ("score", &hud.score),
+                ("lives", &hud.lives),
+            ],
+        );
+        return;
+    }
+    let progress = match hud.wave {
+        Some(wave) => localization.format("Wave: {wave}", &[("wave", &wave)]),
+        None => localization.format("Level: {level}", &[("level", &hud.level)]),
+    };
+    let hud_text = localization.format(
+        "{progress} | Score: {score} | Minerals: {minerals} | Lives: {lives} | Jumps: {jumps}",
+        &[
+            ("progress", &progress),
+            ("score", &hud.score),
+            ("minerals", &hud.minerals),
+            ("lives", &hud.lives),
+            ("jumps", &hud.hyperspace_jumps),
+        ],
+    );
+
+    text.sections[0].value = hud_text;
+}
+
+fn charge_gauge_system(
     mut commands: Commands,
-    mut hud_query: Query<(Entity, &HUD)>,
-    asset_server: Res<AssetServer>,
+    ships_query: Query<&Ship>,
+    mut gauge_query: Query<(&mut Sprite, &mut Visibility), With<ChargeGauge>>,
+    settings: Res<Settings>,
 ) {
-    // FIXME: The HUD system originally user Changed<HUD> to update the Text.sections
-    //        but for some reasons that caused the HUD to sometimes not render at all
-    //        Creating a new text bundle for every update and using the changed property
-    //        for HUD is a workaround that seems to work.
-    if hud_query.is_empty() {
+    let ship = ships_query.single();
+    if gauge_query.is_empty() {
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: hud_color(&settings, Color::rgba(0.5, 0.8, 1.0, 0.8)),
+                    custom_size: Some(Vec2::new(0.0, 6.0)),
+                    anchor: hud_anchor(&settings, Anchor::CenterLeft),
+                    ..default()
+                },
+                transform: hud_transform(
+                    &settings,
+                    -(GAME_WIDTH as f32) / 2.0 + 10.0,
+                    -(GAME_HEIGHT as f32) / 2.0 + 10.0,
+                    0.5,
+                ),
+                visibility: Visibility::Hidden,
+                ..default()
+            })
+            .insert(ChargeGauge)
+            .insert(LevelEntity);
         return;
     }
-    let (entity, hud) = hud_query.single_mut();
-    if !hud.changed {
+    let (mut sprite, mut visibility) = gauge_query.single_mut();
+    *visibility = if ship.weapon == ShipWeapon::Charge {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    let fraction = ship.charge / CHARGE_MAX_TIME;
+    sprite.custom_size = Some(Vec2::new(CHARGE_GAUGE_WIDTH * fraction, 6.0));
+    sprite.color = if fraction >= 1.0 {
+        hud_color(&settings, Color::rgba(1.0, 1.0, 1.0, 0.9))
+    } else {
+        hud_color(&settings, Color::rgba(0.5, 0.8, 1.0, 0.8))
+    };
+}
+
+/// Shows in place of the charge gauge while the beam is selected
+fn beam_gauge_system(
+    mut commands: Commands,
+    ships_query: Query<&Ship>,
+    beams_query: Query<&Beam>,
+    mut gauge_query: Query<(&BeamGauge, &mut Sprite, &mut Visibility)>,
+    settings: Res<Settings>,
+) {
+    let ship = ships_query.single();
+    if gauge_query.is_empty() {
+        for capacity in [true, false] {
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: hud_color(&settings, Color::rgba(1.0, 1.0, 1.0, 0.2)),
+                        custom_size: Some(Vec2::new(0.0, 6.0)),
+                        anchor: hud_anchor(&settings, Anchor::CenterLeft),
+                        ..default()
+                    },
+                    transform: hud_transform(
+                        &settings,
+                        -(GAME_WIDTH as f32) / 2.0 + 10.0,
+                        -(GAME_HEIGHT as f32) / 2.0 + 10.0,
+                        if capacity { 0.5 } else { 0.51 },
+                    ),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                .insert(BeamGauge { capacity })
+                .insert(LevelEntity);
+        }
         return;
     }
-    fn weapon_text(name: &str, level: u8, selected: bool) -> String {
-        match (level, selected) {
-            (0, _) => String::new(),
-            (level, true) => format!("[{name}{level}]"),
-            (level, false) => format!("{name}{level}"),
+    let beam = beams_query.iter().next();
+    for (gauge, mut sprite, mut visibility) in gauge_query.iter_mut() {
+        let Some(beam) = beam.filter(|_| ship.weapon == ShipWeapon::Beam) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Visible;
+        let capacity = BEAM_BASE_LENGTH + BEAM_LENGTH_PER_LEVEL * ship.weapon_beam_level as f32;
+        if gauge.capacity {
+            sprite.custom_size = Some(Vec2::new(capacity * BEAM_GAUGE_SCALE, 6.0));
+            continue;
         }
+        sprite.custom_size = Some(Vec2::new(beam.max_length * BEAM_GAUGE_SCALE, 6.0));
+        // Shrinks while firing and grows back once the beam has retracted
+        sprite.color = if beam.active {
+            hud_color(&settings, Color::rgba(1.0, 0.6, 0.2, 0.9))
+        } else if beam.max_length < capacity {
+            hud_color(&settings, Color::rgba(0.5, 0.8, 1.0, 0.8))
+        } else {
+            hud_color(&settings, Color::rgba(1.0, 1.0, 1.0, 0.9))
+        };
     }
-    let weapons = [
-        (ShipWeapon::Rapid, "L", hud.weapon_rapid_level),
-        (ShipWeapon::Spread, "S", hud.weapon_spread_level),
-        (ShipWeapon::Beam, "B", hud.weapon_beam_level),
-        (ShipWeapon::Plasma, "P", hud.weapon_plasma_level),
-    ]
-    .map(|(weapon, name, level)| weapon_text(name, level, weapon == hud.weapon));
-
-    let hud_text = format!(
-        "Level: {} | Score: {} | Lives: {} | Weapons: {}",
-        hud.level,
-        hud.score,
-        hud.lives,
-        &weapons.join(" ")
-    );
+}
 
-    commands.entity(entity).despawn();
-    commands
-        .spawn(Text2dBundle {
-            text: Text {
-                sections: vec![TextSection::new(
-                    hud_text,
+fn health_bar_system(
+    mut commands: Commands,
+    ships_query: Query<&Health, With<Ship>>,
+    mut bar_query: Query<&mut Sprite, With<HealthBar>>,
+    settings: Res<Settings>,
+) {
+    let health = ships_query.single();
+    if bar_query.is_empty() {
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(HEALTH_BAR_WIDTH, 6.0)),
+                    anchor: hud_anchor(&settings, Anchor::CenterLeft),
+                    ..default()
+                },
+                transform: hud_transform(
+                    &settings,
+                    -(GAME_WIDTH as f32) / 2.0 + 10.0,
+                    -(GAME_HEIGHT as f32) / 2.0 + 22.0,
+                    0.5,
+                ),
+                ..default()
+            })
+            .insert(HealthBar)
+            .insert(LevelEntity);
+        return;
+    }
+    let mut sprite = bar_query.single_mut();
+    let fraction = health.fraction();
+    sprite.custom_size = Some(Vec2::new(HEALTH_BAR_WIDTH * fraction, 6.0));
+    sprite.color = hud_color(&settings, Color::rgba(1.0 - fraction, fraction, 0.2, 0.8));
+}
+
+fn heat_bar_system(
+    mut commands: Commands,
+    ships_query: Query<&Ship>,
+    mut bar_query: Query<&mut Sprite, With<HeatBar>>,
+    settings: Res<Settings>,
+) {
+    let ship = ships_query.single();
+    if bar_query.is_empty() {
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(0.0, 6.0)),
+                    anchor: hud_anchor(&settings, Anchor::CenterLeft),
+                    ..default()
+                },
+                transform: hud_transform(
+                    &settings,
+                    -(GAME_WIDTH as f32) / 2.0 + 10.0,
+                    -(GAME_HEIGHT as f32) / 2.0 + 34.0,
+                    0.5,
+                ),
+                ..default()
+            })
+            .insert(HeatBar)
+            .insert(LevelEntity);
+        return;
+    }
+    let mut sprite = bar_query.single_mut();
+    let heat = ship.heat(ship.weapon);
+    sprite.custom_size = Some(Vec2::new(HEAT_BAR_WIDTH * heat, 6.0));
+    sprite.color = if ship.is_overheated(ship.weapon) {
+        hud_color(&settings, Color::rgba(1.0, 0.2, 0.2, 0.9))
+    } else {
+        hud_color(&settings, Color::rgba(1.0, 0.6, 0.2, 0.8))
+    };
+}
+
+/// Shield energy as pips above the other bars, partly filled while regenerating
+fn shield_pips_system(
+    mut commands: Commands,
+    ships_query: Query<&ShieldEnergy, With<Ship>>,
+    mut pips_query: Query<(&ShieldPip, &mut Sprite, &mut Visibility)>,
+    settings: Res<Settings>,
+) {
+    let shield = ships_query.single();
+    if pips_query.is_empty() {
+        let pips = (SHIELD_MAX_ENERGY / SHIELD_PIP_ENERGY).ceil() as usize;
+        for index in 0..pips {
+            for background in [true, false] {
+                commands
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: hud_color(&settings, Color::rgba(0.3, 0.6, 1.0, 0.25)),
+                            custom_size: Some(Vec2::new(SHIELD_PIP_WIDTH, 6.0)),
+                            anchor: hud_anchor(&settings, Anchor::CenterLeft),
+                            ..default()
+                        },
+                        transform: hud_transform(
+                            &settings,
+                            -(GAME_WIDTH as f32) / 2.0 + 10.0 + index as f32 * SHIELD_PIP_SPACING,
+                            -(GAME_HEIGHT as f32) / 2.0 + 46.0,
+                            if background { 0.5 } else { 0.51 },
+                        ),
+                        ..default()
+                    })
+                    .insert(ShieldPip { index, background })
+                    .insert(LevelEntity);
+            }
+        }
+        return;
+    }
+    for (pip, mut sprite, mut visibility) in pips_query.iter_mut() {
+        let start = pip.index as f32 * SHIELD_PIP_ENERGY;
+        *visibility = if start < shield.max {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if pip.background {
+            continue;
+        }
+        let fill = ((shield.current - start) / SHIELD_PIP_ENERGY).clamp(0.0, 1.0);
+        sprite.custom_size = Some(Vec2::new(SHIELD_PIP_WIDTH * fill, 6.0));
+        // The pip still filling up is dimmer
+        sprite.color = if fill >= 1.0 {
+            hud_color(&settings, Color::rgba(0.4, 0.8, 1.0, 0.9))
+        } else {
+            hud_color(&settings, Color::rgba(0.4, 0.8, 1.0, 0.5))
+        };
+    }
+}
+
+/// Asteroids left in the level at the bottom of the screen, weighted by how many pieces each still splits into
+fn level_progress_system(
+    mut commands: Commands,
+    asteroids_query: Query<&Asteroid>,
+    turrets_query: Query<(), With<Turret>>,
+    mut progress_query: Query<(&mut LevelProgress, &mut Text, &Children)>,
+    mut bar_query: Query<&mut Sprite, With<LevelProgressBar>>,
+    mode: Res<GameMode>,
+    localization: Res<Localization>,
+    game_assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    // Only modes that finish levels by clearing them
+    if !matches!(*mode, GameMode::Classic | GameMode::TimeAttack) {
+        return;
+    }
+    let Ok((mut progress, mut text, children)) = progress_query.get_single_mut() else {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
                     TextStyle {
-                        font: asset_server.load("fonts/DejaVuSans.ttf"),
-                        font_size: 20.0,
-                        color: Color::WHITE,
+                        font: game_assets.font.clone(),
+                        font_size: 16.0,
+                        color: hud_color(&settings, Color::WHITE),
                     },
-                )],
-                alignment: TextAlignment::Left,
+                ),
+                text_anchor: hud_anchor(&settings, Anchor::BottomCenter),
+                transform: hud_transform(&settings, 0.0, -(GAME_HEIGHT as f32) / 2.0 + 16.0, 0.5),
                 ..default()
-            },
-            text_anchor: Anchor::TopRight,
-            transform: Transform::from_xyz(
-                -(GAME_WIDTH as f32) / 2.05,
+            })
+            .insert(LevelProgress::default())
+            .insert(LevelEntity)
+            .with_children(|parent| {
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: hud_color(&settings, Color::rgba(0.7, 0.6, 0.5, 0.8)),
+                            custom_size: Some(Vec2::new(LEVEL_PROGRESS_BAR_WIDTH, 3.0)),
+                            ..default()
+                        },
+                        // Below the text, or above it when mirrored to the top
+                        transform: hud_transform(&settings, 0.0, -6.0, 0.0),
+                        ..default()
+                    })
+                    .insert(LevelProgressBar);
+            });
+        return;
+    };
+    let remaining: u32 = asteroids_query
+        .iter()
+        .map(|asteroid| asteroid.size.cost())
+        .sum();
+    progress.total = progress.total.max(remaining);
+    let turrets = turrets_query.iter().count();
+    let label = if turrets > 0 {
+        localization.format(
+            "Rocks: {rocks} | Turrets: {turrets}",
+            &[("rocks", &remaining), ("turrets", &turrets)],
+        )
+    } else {
+        localization.format("Rocks: {rocks}", &[("rocks", &remaining)])
+    };
+    if text.sections[0].value != label {
+        text.sections[0].value = label;
+    }
+    let fraction = if progress.total > 0 {
+        remaining as f32 / progress.total as f32
+    } else {
+        0.0
+    };
+    for &child in children.iter() {
+        if let Ok(mut sprite) = bar_query.get_mut(child) {
+            sprite.custom_size = Some(Vec2::new(LEVEL_PROGRESS_BAR_WIDTH * fraction, 3.0));
+        }
+    }
+}
+
+/// Shows every active `TimedEffect` as an icon with a countdown bar in the bottom right corner
+fn timed_effect_strip_system(
+    mut commands: Commands,
+    mut effects_query: Query<(Entity, &TimedEffect, &mut Transform, Option<&Children>)>,
+    mut icon_query: Query<&mut Visibility, With<TimedEffectIcon>>,
+    mut bar_query: Query<&mut Sprite, With<TimedEffectBar>>,
+    sprite_sheets: Res<SpriteSheets>,
+    settings: Res<Settings>,
+) {
+    let mut effects: Vec<_> = effects_query.iter_mut().collect();
+    effects.sort_by_key(|(entity, ..)| *entity);
+    for (i, (entity, effect, mut transform, children)) in effects.into_iter().enumerate() {
+        *transform = hud_transform(
+            &settings,
+            (GAME_WIDTH as f32) / 2.0 - 20.0 - i as f32 * TIMED_EFFECT_SPACING,
+            -(GAME_HEIGHT as f32) / 2.0 + 28.0,
+            0.5,
+        );
+        let Some(children) = children else {
+            let (index, color) = powerup_sprite(effect.powerup, &sprite_sheets.powerup);
+            commands.entity(entity).with_children(|parent| {
+                parent
+                    .spawn(SpriteSheetBundle {
+                        texture_atlas: sprite_sheets.powerup.atlas.clone(),
+                        sprite: TextureAtlasSprite {
+                            index,
+                            color: hud_color(&settings, color),
+                            ..default()
+                        },
+                        transform: Transform::from_scale(Vec3::splat(0.75)),
+                        ..default()
+                    })
+                    .insert(TimedEffectIcon);
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: hud_color(&settings, Color::rgba(1.0, 1.0, 1.0, 0.8)),
+                            custom_size: Some(Vec2::new(TIMED_EFFECT_BAR_WIDTH, 3.0)),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(-TIMED_EFFECT_BAR_WIDTH / 2.0, -16.0, 0.0),
+                        ..default()
+                    })
+                    .insert(TimedEffectBar);
+            });
+            continue;
+        };
+        // Blinks when about to run out, the shrinking bar is warning enough otherwise
+        let visible = settings.reduced_flashing
+            || effect.remaining > TIMED_EFFECT_WARNING
+            || (effect.remaining * 4.0) as u32 % 2 == 1;
+        for &child in children.iter() {
+            if let Ok(mut visibility) = icon_query.get_mut(child) {
+                *visibility = if visible {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            if let Ok(mut sprite) = bar_query.get_mut(child) {
+                sprite.custom_size =
+                    Some(Vec2::new(TIMED_EFFECT_BAR_WIDTH * effect.fraction(), 3.0));
+            }
+        }
+    }
+}
+
+/// Row of icons in the top right corner for the modifiers active in this run
+fn modifier_icons_system(
+    mut commands: Commands,
+    icons_query: Query<(), With<ModifierIcons>>,
+    modifiers: Res<Modifiers>,
+    game_assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    if !icons_query.is_empty() || modifiers.0.is_empty() {
+        return;
+    }
+    let icons: Vec<_> = modifiers.iter().map(|modifier| modifier.icon()).collect();
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                icons.join(" "),
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 20.0,
+                    color: hud_color(&settings, Color::ORANGE),
+                },
+            ),
+            text_anchor: hud_anchor(&settings, Anchor::TopRight),
+            transform: hud_transform(
+                &settings,
+                (GAME_WIDTH as f32) / 2.05,
                 (GAME_HEIGHT as f32) / 2.05,
-                -0.01,
+                0.5,
             ),
             ..default()
         })
-        .insert(HUD {
-            changed: false,
-            ..*hud
-        })
+        .insert(ModifierIcons)
         .insert(LevelEntity);
 }
+
+/// Powerup whose icon stands for a weapon
+fn weapon_powerup(weapon: ShipWeapon) -> Powerup {
+    match weapon {
+        ShipWeapon::Rapid => Powerup::Laser,
+        ShipWeapon::Spread => Powerup::Spread,
+        ShipWeapon::Beam => Powerup::Beam,
+        ShipWeapon::Plasma => Powerup::Plasma,
+        ShipWeapon::Charge => Powerup::Charge,
+        ShipWeapon::Railgun => Powerup::Railgun,
+    }
+}
+
+/// Row of the ship's weapons above the bars in the bottom left corner, each with its level
+/// as pips and the selected one framed and darkened from the top while cooling down
+fn weapon_icons_system(
+    mut commands: Commands,
+    ships_query: Query<&Ship>,
+    mut icons_query: Query<(&WeaponIcon, &mut Visibility, &Children)>,
+    mut frame_query: Query<&mut Visibility, (With<WeaponIconFrame>, Without<WeaponIcon>)>,
+    mut sprite_query: Query<&mut TextureAtlasSprite, With<WeaponIconSprite>>,
+    mut overlay_query: Query<&mut Sprite, With<WeaponCooldownOverlay>>,
+    mut pip_query: Query<
+        (&WeaponLevelPip, &mut Visibility),
+        (Without<WeaponIcon>, Without<WeaponIconFrame>),
+    >,
+    mut ammo_query: Query<&mut Text, With<WeaponAmmoText>>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+    settings: Res<Settings>,
+) {
+    let ship = ships_query.single();
+    if icons_query.is_empty() {
+        for (i, weapon) in ShipWeapon::ALL.into_iter().enumerate() {
+            let (index, color) = powerup_sprite(weapon_powerup(weapon), &sprite_sheets.powerup);
+            commands
+                .spawn(SpatialBundle::from_transform(hud_transform(
+                    &settings,
+                    -(GAME_WIDTH as f32) / 2.0 + 24.0 + i as f32 * WEAPON_ICON_SPACING,
+                    -(GAME_HEIGHT as f32) / 2.0 + 80.0,
+                    0.5,
+                )))
+                .insert(WeaponIcon(weapon))
+                .insert(LevelEntity)
+                .with_children(|parent| {
+                    parent
+                        .spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: hud_color(&settings, Color::rgba(1.0, 1.0, 0.0, 0.3)),
+                                custom_size: Some(Vec2::splat(WEAPON_ICON_SIZE + 4.0)),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(WeaponIconFrame);
+                    parent
+                        .spawn(SpriteSheetBundle {
+                            texture_atlas: sprite_sheets.powerup.atlas.clone(),
+                            sprite: TextureAtlasSprite {
+                                index,
+                                color: hud_color(&settings, color),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(0.0, 0.0, 0.1)
+                                .with_scale(Vec3::splat(0.75)),
+                            ..default()
+                        })
+                        .insert(WeaponIconSprite);
+                    parent
+                        .spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: hud_color(&settings, Color::rgba(0.0, 0.0, 0.0, 0.6)),
+                                custom_size: Some(Vec2::new(WEAPON_ICON_SIZE, 0.0)),
+                                anchor: Anchor::BottomCenter,
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(0.0, -WEAPON_ICON_SIZE / 2.0, 0.2),
+                            ..default()
+                        })
+                        .insert(WeaponCooldownOverlay);
+                    for pip in 0..WEAPON_LEVEL_PIPS {
+                        parent
+                            .spawn(SpriteBundle {
+                                sprite: Sprite {
+                                    color: hud_color(&settings, Color::rgba(1.0, 1.0, 1.0, 0.8)),
+                                    custom_size: Some(Vec2::new(2.0, 3.0)),
+                                    ..default()
+                                },
+                                transform: Transform::from_xyz(
+                                    (pip as f32 - (WEAPON_LEVEL_PIPS - 1) as f32 / 2.0) * 3.5,
+                                    -WEAPON_ICON_SIZE / 2.0 - 5.0,
+                                    0.1,
+                                ),
+                                ..default()
+                            })
+                            .insert(WeaponLevelPip(pip));
+                    }
+                    if weapon == ShipWeapon::Plasma {
+                        parent
+                            .spawn(Text2dBundle {
+                                text: Text::from_section(
+                                    "",
+                                    TextStyle {
+                                        font: game_assets.font.clone(),
+                                        font_size: 14.0,
+                                        color: hud_color(&settings, Color::WHITE),
+                                    },
+                                ),
+                                text_anchor: Anchor::BottomCenter,
+                                transform: Transform::from_xyz(0.0, WEAPON_ICON_SIZE / 2.0, 0.1),
+                                ..default()
+                            })
+                            .insert(WeaponAmmoText);
+                    }
+                });
+        }
+        return;
+    }
+    for (&WeaponIcon(weapon), mut visibility, children) in icons_query.iter_mut() {
+        let level = ship.weapon_level(weapon);
+        let selected = weapon == ship.weapon;
+        *visibility = if level > 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        for &child in children.iter() {
+            if let Ok(mut frame_visibility) = frame_query.get_mut(child) {
+                *frame_visibility = if selected {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            if let Ok(mut sprite) = sprite_query.get_mut(child) {
+                let alpha = if selected { 1.0 } else { 0.4 };
+                sprite.color.set_a(alpha * settings.hud_opacity);
+            }
+            if let Ok(mut sprite) = overlay_query.get_mut(child) {
+                // Cooldown only applies to the weapon being fired
+                let fraction = if selected {
+                    ship.cooldown_fraction()
+                } else {
+                    0.0
+                };
+                sprite.custom_size = Some(Vec2::new(WEAPON_ICON_SIZE, WEAPON_ICON_SIZE * fraction));
+            }
+            if let Ok((pip, mut pip_visibility)) = pip_query.get_mut(child) {
+                *pip_visibility = if pip.0 < level {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            if let Ok(mut text) = ammo_query.get_mut(child) {
+                text.sections[0].value = ship.plasma_ammo.to_string();
+            }
+        }
+    }
+}
+
+/// Pulses a red border around the play area while the ship is on its last life, with
+/// a warning when it gets there
+fn last_life_warning_system(
+    mut commands: Commands,
+    ships_query: Query<&Ship>,
+    mut border_query: Query<(&mut Sprite, &mut Visibility), With<LastLifeBorder>>,
+    mut announcements: EventWriter<Announcement>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut previous_lives: ResMut<PreviousLives>,
+) {
+    let lives = ships_query.single().lives;
+    // Also warns when a run starts out on its last life
+    if lives == 1 && previous_lives.0 != Some(1) {
+        announcements.send(Announcement(localization.get("Last life!").to_string()));
+    }
+    previous_lives.0 = Some(lives);
+
+    if border_query.is_empty() {
+        let (width, height) = (GAME_WIDTH as f32, GAME_HEIGHT as f32);
+        let edges = [
+            (
+                Vec2::new(0.0, height / 2.0),
+                Vec2::new(width, LAST_LIFE_BORDER_WIDTH),
+            ),
+            (
+                Vec2::new(0.0, -height / 2.0),
+                Vec2::new(width, LAST_LIFE_BORDER_WIDTH),
+            ),
+            (
+                Vec2::new(-width / 2.0, 0.0),
+                Vec2::new(LAST_LIFE_BORDER_WIDTH, height),
+            ),
+            (
+                Vec2::new(width / 2.0, 0.0),
+                Vec2::new(LAST_LIFE_BORDER_WIDTH, height),
+            ),
+        ];
+        for (position, size) in edges {
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(1.0, 0.1, 0.1, 0.0),
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.5)),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                .insert(LastLifeBorder)
+                .insert(LevelEntity);
+        }
+        return;
+    }
+    let pulse = if settings.reduced_flashing {
+        REDUCED_FLASHING_PULSE
+    } else {
+        LAST_LIFE_PULSE
+    };
+    let phase = (time.elapsed_seconds() / pulse * TAU).cos();
+    for (mut sprite, mut visibility) in border_query.iter_mut() {
+        // Not after the last life is gone either, the game over screen is on its way
+        *visibility = if lives == 1 {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        sprite.color.set_a(0.3 + 0.15 * phase);
+    }
+}
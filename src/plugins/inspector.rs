@@ -0,0 +1,20 @@
+use crate::components::*;
+use bevy::prelude::*;
+
+/// Registers the game's components for reflection, and with the `inspector` feature
+/// adds a world inspector window for tweaking them live
+pub struct InspectorPlugin;
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Ship>()
+            .register_type::<Asteroid>()
+            .register_type::<Beam>()
+            .register_type::<Moving>()
+            .register_type::<Spinning>()
+            .register_type::<Mass>()
+            .register_type::<Health>()
+            .register_type::<ShieldEnergy>();
+        #[cfg(feature = "inspector")]
+        app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{DamageCause, DamageEvent, Died},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::random;
+use std::f32::consts::TAU;
+
+pub struct KamikazePlugin;
+impl Plugin for KamikazePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KamikazeSpawnTimer>()
+            .add_systems(
+                Update,
+                (
+                    kamikaze_spawn_system,
+                    kamikaze_homing_system,
+                    kamikaze_trigger_system.after(kamikaze_homing_system),
+                    kamikaze_blast_system,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnEnter(AppState::LoadLevel), reset_kamikaze_spawn_timer);
+    }
+}
+
+/// Fragile drone diving at the ship to blow itself up
+#[derive(Component)]
+struct Kamikaze;
+
+/// Seconds until the next kamikaze, if the level has any
+#[derive(Default, Resource)]
+struct KamikazeSpawnTimer(Option<f32>);
+
+fn reset_kamikaze_spawn_timer(mut timer: ResMut<KamikazeSpawnTimer>, level: Res<Level>) {
+    timer.0 = level.kamikaze_spawn_interval();
+}
+
+fn kamikaze_spawn_system(
+    mut commands: Commands,
+    mut timer: ResMut<KamikazeSpawnTimer>,
+    level: Res<Level>,
+    mode: Res<GameMode>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+) {
+    // Survival waves bring their own enemies
+    if *mode == GameMode::Survival {
+        return;
+    }
+    let Some(remaining) = timer.0.as_mut() else {
+        return;
+    };
+    *remaining -= time.delta_seconds();
+    if *remaining > 0.0 {
+        return;
+    }
+    *remaining += level.kamikaze_spawn_interval().unwrap_or(f32::INFINITY);
+
+    // Somewhere along the edge, where the screen wraps
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let position = if random() {
+        Vec2::new(lerp(-span.x, span.x, random()), span.y)
+    } else {
+        Vec2::new(span.x, lerp(-span.y, span.y, random()))
+    };
+    let transform =
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(KAMIKAZE_SCALE));
+    commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas: sprite_sheets.ship.atlas.clone(),
+            transform,
+            sprite: TextureAtlasSprite {
+                index: sprite_sheets.ship.rapid,
+                color: Color::rgb(1.0, 0.8, 0.2),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Kamikaze,
+        Enemy,
+        Health::new(1.0),
+        ContactDamage(KAMIKAZE_DAMAGE),
+        Moving::default(),
+        Wrapping,
+        LevelEntity,
+        CollisionShape::new(
+            Shape::Circle {
+                center: Vec2::ZERO,
+                radius: KAMIKAZE_RADIUS,
+            },
+            transform,
+        ),
+    ));
+}
+
+/// Accelerates straight at the ship, up to a top speed
+fn kamikaze_homing_system(
+    mut kamikazes_query: Query<(&mut Transform, &mut Moving), With<Kamikaze>>,
+    ships_query: Query<&Transform, (With<Ship>, Without<Kamikaze>)>,
+) {
+    let target = ships_query.get_single().ok();
+    for (mut transform, mut moving) in kamikazes_query.iter_mut() {
+        let direction = target.map_or(Vec2::ZERO, |ship_transform| {
+            (ship_transform.translation - transform.translation)
+                .truncate()
+                .normalize_or_zero()
+        });
+        moving.acceleration = direction * KAMIKAZE_ACCELERATION;
+        moving.velocity = moving.velocity.clamp_length_max(KAMIKAZE_MAX_SPEED);
+        if moving.velocity != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_between(moving.velocity));
+        }
+    }
+}
+
+/// Sets the kamikaze off once it is close enough to the ship
+fn kamikaze_trigger_system(
+    kamikazes_query: Query<(Entity, &Transform, &Health), With<Kamikaze>>,
+    ships_query: Query<(&Ship, &Transform), Without<Kamikaze>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let Ok((ship, ship_transform)) = ships_query.get_single() else {
+        return;
+    };
+    if ship.respawn_delay > 0.0 || ship.lives == 0 {
+        return;
+    }
+    for (entity, transform, health) in kamikazes_query.iter() {
+        let distance = ship_transform.translation.distance(transform.translation);
+        if distance < KAMIKAZE_TRIGGER_DISTANCE && health.current > 0.0 {
+            damage_events.send(DamageEvent {
+                target: entity,
+                source: Some(entity),
+                amount: health.current,
+                cause: DamageCause::Collision,
+            });
+        }
+    }
+}
+
+/// However it goes, a kamikaze takes out everything close by with it
+fn kamikaze_blast_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    kamikazes_query: Query<&Transform, With<Kamikaze>>,
+    mut ships_query: Query<(Entity, &mut Ship, &Transform), Without<Kamikaze>>,
+    enemies_query: Query<(Entity, &Transform), (With<Enemy>, Without<Ship>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = kamikazes_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        let in_blast = |other: &Transform| {
+            other.translation.truncate().distance(position) < KAMIKAZE_BLAST_RADIUS
+        };
+
+        for (ship_entity, mut ship, ship_transform) in ships_query.iter_mut() {
+            if !in_blast(ship_transform) || ship.invulnerability > 0.0 {
+                continue;
+            }
+            ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+            damage_events.send(DamageEvent {
+                target: ship_entity,
+                source: Some(event.entity),
+                amount: KAMIKAZE_DAMAGE,
+                cause: DamageCause::Collision,
+            });
+        }
+        for (enemy_entity, enemy_transform) in enemies_query.iter() {
+            if enemy_entity != event.entity && in_blast(enemy_transform) {
+                damage_events.send(DamageEvent {
+                    target: enemy_entity,
+                    source: Some(event.entity),
+                    amount: KAMIKAZE_DAMAGE,
+                    cause: DamageCause::Sweep,
+                });
+            }
+        }
+
+        // Only shooting one down is worth anything
+        if matches!(event.cause, DamageCause::Shot | DamageCause::Sweep) {
+            score.increase(KAMIKAZE_SCORE * score_multiplier.0);
+        }
+        commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        commands.spawn(WaveParticleBundle::new(position, &sprite_sheets.particles));
+        for _ in 0..10 {
+            let velocity = Vec2::from_angle(random::<f32>() * TAU) * lerp(60.0, 180.0, random());
+            commands.spawn(SparkParticleBundle::new(
+                position,
+                velocity,
+                -velocity,
+                &sprite_sheets.particles,
+            ));
+        }
+        commands.entity(event.entity).despawn();
+    }
+}
@@ -0,0 +1,118 @@
+use crate::components::Ship;
+use crate::constants::*;
+use crate::input::InputState;
+use crate::plugins::Localization;
+use crate::resources::{GameAssets, Level, LevelStartStats, RunStats, Score};
+use crate::AppState;
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct LevelSummaryEntity;
+
+#[derive(Resource)]
+struct LevelSummaryTimer(Timer);
+
+pub struct LevelSummaryPlugin;
+impl Plugin for LevelSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::LevelSummary), init_level_summary)
+            .add_systems(
+                OnExit(AppState::LevelSummary),
+                crate::despawn_tagged::<LevelSummaryEntity>,
+            )
+            .add_systems(
+                Update,
+                level_summary_input.run_if(in_state(AppState::LevelSummary)),
+            );
+    }
+}
+
+fn init_level_summary(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    ship_query: Query<&Ship>,
+    level: Res<Level>,
+    run_stats: Res<RunStats>,
+    level_start_stats: Res<LevelStartStats>,
+    mut score: ResMut<Score>,
+    localization: Res<Localization>,
+) {
+    let start = &level_start_stats.0;
+    let time_taken = (run_stats.time_played - start.time_played) as u32;
+    let level_stats = RunStats {
+        shots_fired: run_stats.shots_fired - start.shots_fired,
+        shots_hit: run_stats.shots_hit - start.shots_hit,
+        ..default()
+    };
+    let accuracy = level_stats.accuracy();
+    let lives = ship_query.single().lives as u32;
+
+    let lives_bonus = lives * LEVEL_LIFE_BONUS;
+    let accuracy_bonus = (accuracy * LEVEL_ACCURACY_BONUS as f32) as u32;
+    score.increase(lives_bonus + accuracy_bonus);
+
+    // The level has already been advanced to the next one
+    let lines = [
+        localization.format("Level {level} cleared", &[("level", &(level.number() - 1))]),
+        localization.format(
+            "Time: {time}",
+            &[(
+                "time",
+                &format!("{}:{:02}", time_taken / 60, time_taken % 60),
+            )],
+        ),
+        localization.format(
+            "Lives bonus: {lives} x {bonus} = {total}",
+            &[
+                ("lives", &lives),
+                ("bonus", &LEVEL_LIFE_BONUS),
+                ("total", &lives_bonus),
+            ],
+        ),
+        localization.format(
+            "Accuracy bonus: {accuracy}% = {total}",
+            &[
+                ("accuracy", &format!("{:.0}", accuracy * 100.0)),
+                ("total", &accuracy_bonus),
+            ],
+        ),
+        localization.format("Score: {score}", &[("score", &score.value())]),
+    ];
+
+    let font = game_assets.font.clone();
+    for (i, line) in lines.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    line,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: if i == 0 { 40.0 } else { 24.0 },
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 100.0 - i as f32 * 48.0, 0.01),
+                ..default()
+            })
+            .insert(LevelSummaryEntity);
+    }
+
+    commands.insert_resource(LevelSummaryTimer(Timer::from_seconds(
+        LEVEL_SUMMARY_DURATION,
+        TimerMode::Once,
+    )));
+}
+
+fn level_summary_input(
+    input: Res<InputState>,
+    mut timer: ResMut<LevelSummaryTimer>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    timer.0.tick(time.delta());
+    // Fire is also on Space, so shooting as the level ends doesn't skip the summary
+    let skipped = input.ok && timer.0.elapsed_secs() >= LEVEL_SUMMARY_INPUT_DELAY;
+    if skipped || timer.0.finished() {
+        next_state.set(AppState::Shop)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::components::{AsteroidSize, Powerup};
+use crate::constants::{PLANET_GRAVITY, TURRET_SHIELD};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A hand-made level, loaded from `assets/levels/*.level.ron`. See
+/// `assets/levels/example.level.ron.txt` for the format.
+/// Levels without a definition are generated from `Level`.
+#[derive(Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "6c9a3f0e-8d2b-4b61-9f0e-2f7c3d5a1b84"]
+pub struct LevelDefinition {
+    /// Level number as shown to the player, starting from 1
+    pub level: u32,
+    #[serde(default)]
+    pub background: Option<usize>,
+    /// Looping music, as a path under `assets/`
+    #[serde(default)]
+    pub music: Option<String>,
+    pub asteroids: Vec<AsteroidGroup>,
+    /// Seconds from level start at which UFOs appear, in ascending order.
+    /// When left out, UFOs appear by score.
+    #[serde(default)]
+    pub ufo_schedule: Option<Vec<f32>>,
+    /// Turrets that have to be destroyed, along with the asteroids, to finish the level
+    #[serde(default)]
+    pub turrets: Vec<TurretPlacement>,
+    /// Clusters of mines, optional to clear
+    #[serde(default)]
+    pub minefields: Vec<Minefield>,
+    /// Linked pairs of wormholes, each leading to the other
+    #[serde(default)]
+    pub wormholes: Vec<WormholePair>,
+    /// Planet on one edge of the screen, pulling everything towards it
+    #[serde(default)]
+    pub planet: Option<Planet>,
+    /// Clouds drifting over the play area, hiding what's beneath them
+    #[serde(default)]
+    pub nebula: Option<Nebula>,
+    #[serde(default)]
+    pub modifiers: LevelModifiers,
+    /// Drop weights replacing the usual ones for the given powerups.
+    /// A weight of 0 keeps a powerup from dropping at all.
+    #[serde(default)]
+    pub powerup_weights: HashMap<Powerup, f32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AsteroidGroup {
+    pub size: AsteroidSize,
+    #[serde(default = "AsteroidGroup::default_count")]
+    pub count: u32,
+    /// Spawn position; random around the center when left out
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    /// Initial velocity; random direction and level speed when left out
+    #[serde(default)]
+    pub velocity: Option<(f32, f32)>,
+}
+
+impl AsteroidGroup {
+    fn default_count() -> u32 {
+        1
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TurretPlacement {
+    pub position: (f32, f32),
+    /// Damage the turret's shield takes before the turret itself can be damaged
+    #[serde(default = "TurretPlacement::default_shield")]
+    pub shield: f32,
+}
+
+impl TurretPlacement {
+    fn default_shield() -> f32 {
+        TURRET_SHIELD
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Minefield {
+    pub position: (f32, f32),
+    #[serde(default = "Minefield::default_count")]
+    pub count: u32,
+    /// Mines are scattered this far around `position`
+    #[serde(default = "Minefield::default_radius")]
+    pub radius: f32,
+}
+
+impl Minefield {
+    fn default_count() -> u32 {
+        5
+    }
+    fn default_radius() -> f32 {
+        50.0
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Edge {
+    /// Unit vector pointing from the center of the screen to the edge
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            Edge::Top => Vec2::Y,
+            Edge::Bottom => Vec2::NEG_Y,
+            Edge::Left => Vec2::NEG_X,
+            Edge::Right => Vec2::X,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Planet {
+    pub edge: Edge,
+    /// Acceleration towards the edge, in pixels per second squared
+    #[serde(default = "Planet::default_gravity")]
+    pub gravity: f32,
+}
+
+impl Planet {
+    fn default_gravity() -> f32 {
+        PLANET_GRAVITY
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct WormholePair(pub (f32, f32), pub (f32, f32));
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Nebula {
+    pub clouds: u32,
+    /// From 0 for see-through to 1 for hiding everything
+    pub opacity: f32,
+}
+
+impl Default for Nebula {
+    fn default() -> Self {
+        Self {
+            clouds: 6,
+            opacity: 0.6,
+        }
+    }
+}
+
+/// Multipliers on top of the level's usual values
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LevelModifiers {
+    pub asteroid_speed: f32,
+    pub ufo_shoot_delay: f32,
+}
+
+impl Default for LevelModifiers {
+    fn default() -> Self {
+        Self {
+            asteroid_speed: 1.0,
+            ufo_shoot_delay: 1.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LevelDefinitionLoader;
+
+impl AssetLoader for LevelDefinitionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let definition: LevelDefinition = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(definition));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct LevelDefinitions {
+    pub handles: Vec<Handle<LevelDefinition>>,
+}
+
+impl LevelDefinitions {
+    pub fn find<'a>(
+        &self,
+        level_number: u32,
+        definitions: &'a Assets<LevelDefinition>,
+    ) -> Option<&'a LevelDefinition> {
+        self.handles
+            .iter()
+            .filter_map(|handle| definitions.get(handle))
+            .find(|definition| definition.level == level_number)
+    }
+}
+
+/// Definition of the level currently being played, if it has one
+#[derive(Default, Resource)]
+pub struct ActiveLevelDefinition(pub Option<LevelDefinition>);
+
+pub struct LevelsPlugin;
+impl Plugin for LevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<LevelDefinition>()
+            .init_asset_loader::<LevelDefinitionLoader>()
+            .init_resource::<LevelDefinitions>()
+            .init_resource::<ActiveLevelDefinition>()
+            .add_systems(Startup, load_level_definitions);
+    }
+}
+
+fn load_level_definitions(
+    asset_server: Res<AssetServer>,
+    mut level_definitions: ResMut<LevelDefinitions>,
+) {
+    match asset_server.load_folder("levels") {
+        Ok(handles) => {
+            level_definitions.handles = handles.into_iter().map(|handle| handle.typed()).collect()
+        }
+        Err(err) => info!("No level definitions loaded: {err}"),
+    }
+}
@@ -0,0 +1,123 @@
+use crate::plugins::Settings;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+use std::fmt::Display;
+
+/// Name of the language the game is written in, used when no translation is selected
+pub const SOURCE_LANGUAGE: &str = "English";
+
+/// Translations of the game's texts, loaded from `assets/lang/*.lang.json`.
+/// Texts are looked up by their English source text, so untranslated texts stay readable.
+#[derive(Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "b3e7d1c2-4f5a-4c8e-9a61-7d2f0e8b5c13"]
+pub struct Translation {
+    /// Name of the language in the language itself, as shown in the options
+    pub language: String,
+    pub strings: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct TranslationLoader;
+
+impl AssetLoader for TranslationLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let translation: Translation = serde_json::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(translation));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lang.json"]
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct Translations {
+    pub handles: Vec<Handle<Translation>>,
+}
+
+impl Translations {
+    /// Names of all selectable languages, the source language first
+    pub fn languages(&self, translations: &Assets<Translation>) -> Vec<String> {
+        std::iter::once(SOURCE_LANGUAGE.to_string())
+            .chain(
+                self.handles
+                    .iter()
+                    .filter_map(|handle| translations.get(handle))
+                    .map(|translation| translation.language.clone()),
+            )
+            .collect()
+    }
+}
+
+/// Texts of the selected language
+#[derive(Default, Resource)]
+pub struct Localization {
+    strings: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Translates a text, falling back to the text itself
+    pub fn get<'a>(&'a self, text: &'a str) -> &'a str {
+        self.strings.get(text).map_or(text, String::as_str)
+    }
+    /// Translates a text and fills in its `{name}` placeholders
+    pub fn format(&self, text: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.get(text).to_string(), |result, (name, value)| {
+                result.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+}
+
+pub struct LocalizationPlugin;
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Translation>()
+            .init_asset_loader::<TranslationLoader>()
+            .init_resource::<Translations>()
+            .init_resource::<Localization>()
+            .add_systems(Startup, load_translations)
+            .add_systems(Update, apply_language_system);
+    }
+}
+
+fn load_translations(asset_server: Res<AssetServer>, mut translations: ResMut<Translations>) {
+    match asset_server.load_folder("lang") {
+        Ok(handles) => {
+            translations.handles = handles.into_iter().map(|handle| handle.typed()).collect()
+        }
+        Err(err) => info!("No translations loaded: {err}"),
+    }
+}
+
+fn apply_language_system(
+    mut asset_events: EventReader<AssetEvent<Translation>>,
+    settings: Res<Settings>,
+    translations: Res<Translations>,
+    translation_assets: Res<Assets<Translation>>,
+    mut localization: ResMut<Localization>,
+) {
+    let translations_changed = asset_events.iter().count() > 0;
+    if !translations_changed && !settings.is_changed() {
+        return;
+    }
+    localization.strings = translations
+        .handles
+        .iter()
+        .filter_map(|handle| translation_assets.get(handle))
+        .find(|translation| translation.language == settings.language)
+        .map(|translation| translation.strings.clone())
+        .unwrap_or_default();
+}
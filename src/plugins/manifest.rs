@@ -0,0 +1,123 @@
+use crate::AppState;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadState, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+/// Assets to load before the title screen, listed in `assets/manifest.ron` as paths
+/// under `assets/`. Required, as the sprite atlas is built from it.
+#[derive(Deserialize, TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "f56699f8-db1e-4c33-b961-70eb66fde528"]
+pub struct AssetManifest {
+    #[serde(default)]
+    pub images: Vec<String>,
+    /// Images packed into the shared sprite atlas
+    #[serde(default)]
+    pub atlas: Vec<String>,
+    /// Frames of the explosion animation, in order
+    #[serde(default)]
+    pub explosion: Vec<String>,
+    #[serde(default)]
+    pub sounds: Vec<String>,
+    #[serde(default)]
+    pub music: Vec<String>,
+}
+
+#[derive(Default)]
+struct AssetManifestLoader;
+
+impl AssetLoader for AssetManifestLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let manifest: AssetManifest = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(manifest));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.ron"]
+    }
+}
+
+/// The manifest and handles to everything in it, held for the whole game so that
+/// nothing listed gets unloaded
+#[derive(Default, Resource)]
+pub struct ManifestAssets {
+    pub manifest: Handle<AssetManifest>,
+    /// Empty until the manifest itself has loaded
+    pub handles: Option<Vec<HandleUntyped>>,
+}
+
+impl ManifestAssets {
+    /// Fraction of the listed assets that are done loading, counting failed ones as done
+    pub fn progress(&self, asset_server: &AssetServer) -> f32 {
+        let Some(handles) = &self.handles else {
+            return 0.0;
+        };
+        if handles.is_empty() {
+            return 1.0;
+        }
+        let done = handles
+            .iter()
+            .filter(|handle| {
+                matches!(
+                    asset_server.get_load_state(handle.id()),
+                    LoadState::Loaded | LoadState::Failed
+                )
+            })
+            .count();
+        done as f32 / handles.len() as f32
+    }
+}
+
+pub struct ManifestPlugin;
+impl Plugin for ManifestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AssetManifest>()
+            .init_asset_loader::<AssetManifestLoader>()
+            .init_resource::<ManifestAssets>()
+            .add_systems(Startup, load_manifest)
+            .add_systems(
+                Update,
+                load_manifest_assets.run_if(in_state(AppState::Loading)),
+            );
+    }
+}
+
+fn load_manifest(asset_server: Res<AssetServer>, mut manifest_assets: ResMut<ManifestAssets>) {
+    manifest_assets.manifest = asset_server.load("manifest.ron");
+}
+
+fn load_manifest_assets(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<AssetManifest>>,
+    mut manifest_assets: ResMut<ManifestAssets>,
+) {
+    if manifest_assets.handles.is_some() {
+        return;
+    }
+    let Some(manifest) = manifests.get(&manifest_assets.manifest) else {
+        if let LoadState::Failed = asset_server.get_load_state(&manifest_assets.manifest) {
+            panic!("Could not load the asset manifest!");
+        }
+        return;
+    };
+    let handles = manifest
+        .images
+        .iter()
+        .chain(&manifest.atlas)
+        .chain(&manifest.explosion)
+        .chain(&manifest.sounds)
+        .chain(&manifest.music)
+        .map(|path| asset_server.load_untyped(path.as_str()))
+        .collect();
+    manifest_assets.handles = Some(handles);
+}
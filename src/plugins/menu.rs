@@ -0,0 +1,57 @@
+use crate::input::InputState;
+use bevy::prelude::*;
+
+/// Selection within a vertical menu of `items` entries, moved with up and down from
+/// the keyboard, d-pad or stick. Spawned along with the menu's other entities.
+#[derive(Component)]
+pub struct MenuNavigation {
+    pub selected: usize,
+    pub items: usize,
+}
+
+impl MenuNavigation {
+    pub fn new(items: usize) -> Self {
+        Self { selected: 0, items }
+    }
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        self.selected = selected.min(self.items.saturating_sub(1));
+        self
+    }
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected == index
+    }
+}
+
+/// Text of the menu entry at `index`, read out when it gets selected
+#[derive(Component)]
+pub struct MenuEntry(pub usize);
+
+/// Moves the menu selection before menu screens react to it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuNavigationSet;
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            menu_navigation_system
+                .in_set(MenuNavigationSet)
+                .after(crate::input::update_input_state),
+        );
+    }
+}
+
+fn menu_navigation_system(input: Res<InputState>, mut menus: Query<&mut MenuNavigation>) {
+    for mut menu in menus.iter_mut() {
+        if menu.items == 0 {
+            continue;
+        }
+        if input.up {
+            menu.selected = (menu.selected + menu.items - 1) % menu.items;
+        }
+        if input.down {
+            menu.selected = (menu.selected + 1) % menu.items;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{DamageCause, DamageEvent, Died, Minefield},
+    resources::*,
+    AppState, GameSet,
+};
+use bevy::prelude::*;
+use rand::random;
+use std::f32::consts::TAU;
+
+pub struct MinefieldPlugin;
+impl Plugin for MinefieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                mine_asteroid_collision_system.in_set(GameSet::Resolution),
+                mine_explosion_system,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Scatters a level's cluster of mines around its position
+pub fn spawn_minefield(commands: &mut Commands, sprite_sheets: &SpriteSheets, field: &Minefield) {
+    let center = Vec2::from(field.position);
+    for _ in 0..field.count {
+        let position = center
+            + Vec2::from_angle(random::<f32>() * TAU) * random::<f32>().sqrt() * field.radius;
+        let transform = Transform::from_translation(position.extend(0.0));
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: sprite_sheets.particles.atlas.clone(),
+                transform,
+                sprite: TextureAtlasSprite {
+                    index: sprite_sheets.particles.spark,
+                    color: Color::rgb(1.0, 0.3, 0.2),
+                    custom_size: Some(Vec2::splat(MINE_RADIUS * 3.0)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Mine {
+                damage: MINE_DAMAGE,
+                blast_radius: MINE_BLAST_RADIUS,
+            },
+            Enemy,
+            Health::new(1.0),
+            ContactDamage(MINE_DAMAGE),
+            Spinning { speed: 1.0 },
+            LevelEntity,
+            CollisionShape::new(
+                Shape::Circle {
+                    center: Vec2::ZERO,
+                    radius: MINE_RADIUS,
+                },
+                transform,
+            ),
+        ));
+    }
+}
+
+/// Asteroids drifting into a mine set it off
+fn mine_asteroid_collision_system(
+    mines_query: Query<(Entity, &Health, &CollisionShape), With<Mine>>,
+    asteroids_query: Query<(Entity, &CollisionShape), With<Asteroid>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (mine_entity, health, mine_shape) in mines_query.iter() {
+        if health.current <= 0.0 {
+            continue;
+        }
+        let hit = asteroids_query
+            .iter()
+            .find(|(_, asteroid_shape)| mine_shape.intersects(asteroid_shape));
+        if let Some((asteroid_entity, _)) = hit {
+            damage_events.send(DamageEvent {
+                target: mine_entity,
+                source: Some(asteroid_entity),
+                amount: health.current,
+                cause: DamageCause::Collision,
+            });
+        }
+    }
+}
+
+/// Blows up mines that went off, hurting the ship and anything else close by.
+/// Mines caught in the blast go off in turn.
+fn mine_explosion_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mines_query: Query<(&Mine, &Transform)>,
+    mut ships_query: Query<(Entity, &mut Ship, &Transform)>,
+    targets_query: Query<(Entity, &Transform), (Or<(With<Asteroid>, With<Enemy>)>, Without<Ship>)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in died_events.iter() {
+        let Ok((mine, transform)) = mines_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        let in_blast =
+            |other: &Transform| other.translation.truncate().distance(position) < mine.blast_radius;
+
+        for (ship_entity, mut ship, ship_transform) in ships_query.iter_mut() {
+            if !in_blast(ship_transform) || ship.invulnerability > 0.0 {
+                continue;
+            }
+            ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+            damage_events.send(DamageEvent {
+                target: ship_entity,
+                source: Some(event.entity),
+                amount: mine.damage,
+                cause: DamageCause::Collision,
+            });
+        }
+        for (entity, target_transform) in targets_query.iter() {
+            if entity != event.entity && in_blast(target_transform) {
+                damage_events.send(DamageEvent {
+                    target: entity,
+                    source: Some(event.entity),
+                    amount: mine.damage,
+                    cause: DamageCause::Collision,
+                });
+            }
+        }
+
+        // Clearing mines is optional, but shooting them is worth a bonus
+        if matches!(event.cause, DamageCause::Shot | DamageCause::Sweep) {
+            let score_delta = MINE_SCORE * score_multiplier.0;
+            score.increase(score_delta);
+            commands.spawn(ScorePopupBundle::new(
+                score_delta,
+                game_assets.font.clone(),
+                position,
+                16.0,
+            ));
+        }
+        commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
+        for _ in 0..8 {
+            let velocity = Vec2::from_angle(random::<f32>() * TAU) * lerp(40.0, 140.0, random());
+            commands.spawn(SparkParticleBundle::new(
+                position,
+                velocity,
+                -velocity,
+                &sprite_sheets.particles,
+            ));
+        }
+        commands.entity(event.entity).despawn();
+    }
+}
@@ -1,5 +1,5 @@
 mod camera;
-pub use camera::CameraPlugin;
+pub use camera::{CameraEffects, CameraPlugin};
 
 mod title;
 pub use title::TitleScreenPlugin;
@@ -8,7 +8,136 @@ mod hud;
 pub use hud::HudPlugin;
 
 mod ufo;
-pub use ufo::UfoPlugin;
+pub use ufo::{spawn_ufo, Ufo, UfoKind, UfoLaserBundle, UfoPlugin};
 
 mod highscore;
 pub use highscore::HighScorePlugin;
+
+mod gameover;
+pub use gameover::GameOverPlugin;
+
+mod level_summary;
+pub use level_summary::LevelSummaryPlugin;
+
+mod shop;
+pub use shop::ShopPlugin;
+
+mod profile;
+pub use profile::{Profile, ProfilePlugin};
+
+mod mode_select;
+pub use mode_select::ModeSelectPlugin;
+
+mod endless;
+pub use endless::EndlessPlugin;
+
+mod survival;
+pub use survival::{SurvivalPlugin, SurvivalWave};
+
+mod time_attack;
+pub use time_attack::{time_attack_finished, TimeAttackClock, TimeAttackPlugin};
+
+mod ship_select;
+pub use ship_select::ShipSelectPlugin;
+
+mod hangar;
+pub use hangar::HangarPlugin;
+
+mod localization;
+pub use localization::{Localization, LocalizationPlugin, Translations};
+
+mod options;
+pub use options::{OptionsPlugin, Settings};
+
+mod controls;
+pub use controls::ControlsPlugin;
+
+mod quit;
+pub use quit::{PauseState, QuitPlugin, QuitToDesktop};
+
+mod menu;
+pub use menu::{MenuEntry, MenuNavigation, MenuNavigationSet, MenuPlugin};
+
+mod fonts;
+pub use fonts::{FontFallback, FontsPlugin};
+
+mod credits;
+pub use credits::CreditsPlugin;
+
+mod stats;
+pub use stats::StatsPlugin;
+
+mod fighter;
+pub use fighter::EnemyFighterPlugin;
+
+mod turret;
+pub use turret::{spawn_turret, Turret, TurretPlugin};
+
+mod carrier;
+pub use carrier::CarrierPlugin;
+
+mod kamikaze;
+pub use kamikaze::KamikazePlugin;
+
+mod events;
+pub use events::EventsPlugin;
+
+mod wormhole;
+pub use wormhole::{spawn_wormholes, WormholePlugin};
+
+mod minefield;
+pub use minefield::{spawn_minefield, MinefieldPlugin};
+
+mod drone;
+pub use drone::DronePlugin;
+
+mod levels;
+pub use levels::{
+    ActiveLevelDefinition, LevelDefinition, LevelDefinitions, LevelsPlugin, Minefield,
+    TurretPlacement, WormholePair,
+};
+
+mod damage;
+pub use damage::{DamageCause, DamageEvent, DamagePlugin, Died};
+
+mod effects;
+pub use effects::EffectsPlugin;
+
+mod ship;
+pub use ship::ShipPlugin;
+
+mod weapon;
+pub use weapon::WeaponPlugin;
+
+mod asteroid;
+pub use asteroid::AsteroidPlugin;
+
+mod powerup;
+pub use powerup::PowerupPlugin;
+
+mod bot;
+pub use bot::{BotPlugin, ShipBot};
+
+mod inspector;
+pub use inspector::InspectorPlugin;
+
+mod manifest;
+pub use manifest::{AssetManifest, ManifestAssets, ManifestPlugin};
+
+#[cfg(feature = "dev")]
+mod console;
+#[cfg(feature = "dev")]
+pub use console::ConsolePlugin;
+
+#[cfg(feature = "dev")]
+mod collision_debug;
+#[cfg(feature = "dev")]
+pub use collision_debug::CollisionDebugPlugin;
+
+#[cfg(feature = "dev")]
+mod debug_overlay;
+#[cfg(feature = "dev")]
+pub use debug_overlay::DebugOverlayPlugin;
+
+mod accessibility;
+pub use accessibility::{AccessibilityPlugin, Announcement};
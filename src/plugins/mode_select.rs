@@ -0,0 +1,182 @@
+use crate::input::InputState;
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet};
+use crate::resources::{GameAssets, GameMode, Modifier, Modifiers};
+use crate::AppState;
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct ModeSelectEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ModeSelectItem {
+    Mode(GameMode),
+    Modifier(Modifier),
+    Back,
+}
+
+#[derive(Component)]
+struct ModeSelectItemText {
+    index: usize,
+    item: ModeSelectItem,
+}
+
+#[derive(Component)]
+struct ModeDescriptionText;
+
+fn items() -> Vec<ModeSelectItem> {
+    GameMode::ALL
+        .into_iter()
+        .map(ModeSelectItem::Mode)
+        .chain(Modifier::ALL.into_iter().map(ModeSelectItem::Modifier))
+        .chain([ModeSelectItem::Back])
+        .collect()
+}
+
+pub struct ModeSelectPlugin;
+impl Plugin for ModeSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::ModeSelect), init_mode_select)
+            .add_systems(
+                OnExit(AppState::ModeSelect),
+                crate::despawn_tagged::<ModeSelectEntity>,
+            )
+            .add_systems(
+                Update,
+                (
+                    mode_select_input,
+                    mode_select_text_system.after(mode_select_input),
+                )
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::ModeSelect)),
+            );
+    }
+}
+
+fn init_mode_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    mode: Res<GameMode>,
+) {
+    let selected = items()
+        .iter()
+        .position(|item| *item == ModeSelectItem::Mode(*mode))
+        .unwrap_or(0);
+    commands
+        .spawn(MenuNavigation::new(items().len()).with_selected(selected))
+        .insert(ModeSelectEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(ModeSelectEntity);
+
+    let font = game_assets.font.clone();
+    for (index, item) in items().into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::CenterLeft,
+                transform: Transform::from_xyz(-100.0, 130.0 - index as f32 * 32.0, 0.1),
+                ..default()
+            })
+            .insert(ModeSelectItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(ModeSelectEntity);
+    }
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font,
+                    font_size: 16.0,
+                    color: Color::GRAY,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -170.0, 0.1),
+            ..default()
+        })
+        .insert(ModeDescriptionText)
+        .insert(ModeSelectEntity);
+}
+
+fn mode_select_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut mode: ResMut<GameMode>,
+    mut modifiers: ResMut<Modifiers>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.back {
+        next_state.set(AppState::Title);
+    } else if input.ok {
+        match items()[menu.selected] {
+            ModeSelectItem::Mode(selected) => {
+                *mode = selected;
+                next_state.set(AppState::ShipSelect);
+            }
+            ModeSelectItem::Modifier(modifier) => modifiers.toggle(modifier),
+            ModeSelectItem::Back => next_state.set(AppState::Title),
+        }
+    }
+}
+
+fn mode_select_text_system(
+    menu_query: Query<&MenuNavigation>,
+    localization: Res<Localization>,
+    modifiers: Res<Modifiers>,
+    mut items_query: Query<(&ModeSelectItemText, &mut Text), Without<ModeDescriptionText>>,
+    mut description_query: Query<&mut Text, With<ModeDescriptionText>>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for (item_text, mut text) in items_query.iter_mut() {
+        let label = match item_text.item {
+            ModeSelectItem::Mode(mode) => localization.get(mode.name()).to_string(),
+            ModeSelectItem::Modifier(modifier) => {
+                let check = if modifiers.contains(modifier) {
+                    "x"
+                } else {
+                    " "
+                };
+                format!("[{check}] {}", localization.get(modifier.name()))
+            }
+            ModeSelectItem::Back => localization.get("Back").to_string(),
+        };
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label
+        };
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+        if selected {
+            for mut description in description_query.iter_mut() {
+                description.sections[0].value = match item_text.item {
+                    ModeSelectItem::Mode(mode) => localization.get(mode.description()).to_string(),
+                    ModeSelectItem::Modifier(modifier) => {
+                        localization.get(modifier.description()).to_string()
+                    }
+                    ModeSelectItem::Back => String::new(),
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,870 @@
+use crate::constants::*;
+use crate::input::{key_name, parse_key, Action, InputState, KeyBindings};
+use crate::plugins::localization::{Localization, Translation, Translations, SOURCE_LANGUAGE};
+use crate::plugins::{MenuEntry, MenuNavigation, MenuNavigationSet};
+use crate::resources::GameAssets;
+use crate::AppState;
+use bevy::{
+    prelude::*,
+    sprite::Anchor,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode, WindowPosition},
+};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+/// Player preferences, kept across runs in `settings.txt`
+#[derive(Resource)]
+pub struct Settings {
+    pub language: String,
+    /// Most cosmetic particles alive at once, new ones over it are skipped
+    pub max_particles: usize,
+    /// Most ship projectiles alive at once, the oldest ones make room for new ones
+    pub max_projectiles: usize,
+    /// Most notifications shown at once, the oldest ones make room for new ones
+    pub max_notifications: usize,
+    pub display_mode: DisplayMode,
+    /// Window size in logical pixels, one of `RESOLUTIONS`
+    pub resolution: (u32, u32),
+    /// Index of the monitor to show the game on
+    pub monitor: usize,
+    pub vsync: VSync,
+    /// Most frames drawn per second, `None` for no limit
+    pub frame_rate_cap: Option<u32>,
+    pub screen_shake: bool,
+    pub zoom_punch: bool,
+    /// Camera drifting slightly towards the ship
+    pub camera_follow: bool,
+    /// Tones down explosions, blinking and screen shake for photosensitive players
+    pub reduced_flashing: bool,
+    /// Menu selections and important events passed on to screen readers
+    pub narration: bool,
+    /// Assist running the world at `ASSIST_WORLD_SPEED`
+    pub assist_slow_world: bool,
+    /// Assist starting every life with a shield
+    pub assist_start_shield: bool,
+    /// Assist leaving out powerups that take lives away
+    pub assist_no_lose_life: bool,
+    /// Multiplier on the ship's turn rate
+    pub turn_sensitivity: f32,
+    /// Throttle latching on with a tap and off with another instead of being held
+    pub toggle_throttle: bool,
+    /// Corner of the screen for the score and lives, the rest of the HUD mirrors along
+    pub hud_corner: HudCorner,
+    /// Shorter HUD text leaving out minerals and hyperspace jumps
+    pub hud_compact: bool,
+    /// One of `HUD_OPACITIES`
+    pub hud_opacity: f32,
+    pub bindings: KeyBindings,
+    pub muted: bool,
+}
+
+/// How finished frames are presented to the screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum VSync {
+    #[default]
+    On,
+    Off,
+}
+
+impl VSync {
+    const ALL: [VSync; 2] = [VSync::On, VSync::Off];
+    pub fn name(&self) -> &'static str {
+        match self {
+            VSync::On => "On",
+            VSync::Off => "Off",
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            VSync::On => "on",
+            VSync::Off => "off",
+        }
+    }
+    fn present_mode(&self) -> PresentMode {
+        // The Auto modes fall back to what the platform supports instead of panicking,
+        // AutoNoVsync picks mailbox where immediate presentation isn't available
+        match self {
+            VSync::On => PresentMode::AutoVsync,
+            VSync::Off => PresentMode::AutoNoVsync,
+        }
+    }
+}
+
+/// Frame rate caps to choose from
+pub const FRAME_RATE_CAPS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+
+/// How the game window is shown
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    const ALL: [DisplayMode; 3] = [
+        DisplayMode::Windowed,
+        DisplayMode::Borderless,
+        DisplayMode::Fullscreen,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "windowed",
+            DisplayMode::Borderless => "borderless",
+            DisplayMode::Fullscreen => "fullscreen",
+        }
+    }
+    fn window_mode(&self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            // Sized so that the chosen resolution is used instead of the monitor's largest
+            DisplayMode::Fullscreen => WindowMode::SizedFullscreen,
+        }
+    }
+}
+
+/// Where on the screen the HUD text goes
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HudCorner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudCorner {
+    const ALL: [HudCorner; 4] = [
+        HudCorner::TopLeft,
+        HudCorner::TopRight,
+        HudCorner::BottomLeft,
+        HudCorner::BottomRight,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            HudCorner::TopLeft => "Top left",
+            HudCorner::TopRight => "Top right",
+            HudCorner::BottomLeft => "Bottom left",
+            HudCorner::BottomRight => "Bottom right",
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            HudCorner::TopLeft => "top_left",
+            HudCorner::TopRight => "top_right",
+            HudCorner::BottomLeft => "bottom_left",
+            HudCorner::BottomRight => "bottom_right",
+        }
+    }
+    /// Signs flipping the top left layout of the HUD over to this corner
+    pub fn mirror(&self) -> Vec2 {
+        match self {
+            HudCorner::TopLeft => Vec2::new(1.0, 1.0),
+            HudCorner::TopRight => Vec2::new(-1.0, 1.0),
+            HudCorner::BottomLeft => Vec2::new(1.0, -1.0),
+            HudCorner::BottomRight => Vec2::new(-1.0, -1.0),
+        }
+    }
+}
+
+/// HUD opacities to choose from
+pub const HUD_OPACITIES: [f32; 4] = [1.0, 0.75, 0.5, 0.25];
+
+/// Window sizes to choose from, the first one is the default
+pub const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (800, 600),
+];
+
+/// Monitors to cycle through in the options. Picking one that isn't connected leaves
+/// the window where it is.
+const MAX_MONITORS: usize = 4;
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: SOURCE_LANGUAGE.to_string(),
+            max_particles: MAX_PARTICLES,
+            max_projectiles: MAX_PROJECTILES,
+            max_notifications: MAX_NOTIFICATIONS,
+            display_mode: DisplayMode::default(),
+            resolution: RESOLUTIONS[0],
+            monitor: 0,
+            vsync: VSync::default(),
+            frame_rate_cap: None,
+            screen_shake: true,
+            zoom_punch: true,
+            camera_follow: true,
+            reduced_flashing: false,
+            narration: false,
+            assist_slow_world: false,
+            assist_start_shield: false,
+            assist_no_lose_life: false,
+            turn_sensitivity: 1.0,
+            toggle_throttle: false,
+            hud_corner: HudCorner::default(),
+            hud_compact: false,
+            hud_opacity: HUD_OPACITIES[0],
+            bindings: KeyBindings::default(),
+            muted: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Whether any assist is on, which marks runs as assisted on the high score table
+    pub fn assisted(&self) -> bool {
+        self.assist_slow_world || self.assist_start_shield || self.assist_no_lose_life
+    }
+    /// Speed of the world when nothing else is slowing it down
+    pub fn world_speed(&self) -> f32 {
+        if self.assist_slow_world {
+            ASSIST_WORLD_SPEED
+        } else {
+            1.0
+        }
+    }
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut content = format!(
+            "language:{}\nmax_particles:{}\nmax_projectiles:{}\nmax_notifications:{}\n\
+             display_mode:{}\nresolution:{}x{}\nmonitor:{}\nvsync:{}\nframe_rate_cap:{}\n\
+             screen_shake:{}\nzoom_punch:{}\ncamera_follow:{}\nreduced_flashing:{}\n\
+             narration:{}\nassist_slow_world:{}\nassist_start_shield:{}\n\
+             assist_no_lose_life:{}\nturn_sensitivity:{}\ntoggle_throttle:{}\nhud_corner:{}\n\
+             hud_compact:{}\nhud_opacity:{}\nmuted:{}\n",
+            self.language,
+            self.max_particles,
+            self.max_projectiles,
+            self.max_notifications,
+            self.display_mode.key(),
+            self.resolution.0,
+            self.resolution.1,
+            self.monitor,
+            self.vsync.key(),
+            self.frame_rate_cap.unwrap_or(0),
+            self.screen_shake,
+            self.zoom_punch,
+            self.camera_follow,
+            self.reduced_flashing,
+            self.narration,
+            self.assist_slow_world,
+            self.assist_start_shield,
+            self.assist_no_lose_life,
+            self.turn_sensitivity,
+            self.toggle_throttle,
+            self.hud_corner.key(),
+            self.hud_compact,
+            self.hud_opacity,
+            self.muted
+        );
+        for action in Action::ALL {
+            let key = key_name(self.bindings.key(action));
+            content.push_str(&format!("bind_{}:{key}\n", action.key()));
+        }
+        let mut file = File::create("settings.txt")?;
+        file.write_all(content.as_bytes())?;
+        Ok(())
+    }
+    fn load() -> std::io::Result<Self> {
+        let mut file = File::open("settings.txt")?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        let mut settings = Settings::default();
+        for (key, value) in content.lines().filter_map(|line| line.split_once(':')) {
+            match key {
+                "language" => settings.language = value.to_string(),
+                "max_particles" => parse_cap(value, &mut settings.max_particles),
+                "max_projectiles" => parse_cap(value, &mut settings.max_projectiles),
+                "max_notifications" => parse_cap(value, &mut settings.max_notifications),
+                "display_mode" => {
+                    match DisplayMode::ALL
+                        .into_iter()
+                        .find(|mode| mode.key() == value)
+                    {
+                        Some(mode) => settings.display_mode = mode,
+                        None => warn!("Unknown display mode {value}"),
+                    }
+                }
+                "resolution" => {
+                    let resolution = value.split_once('x').and_then(|(width, height)| {
+                        Some((width.parse().ok()?, height.parse().ok()?))
+                    });
+                    match resolution {
+                        Some(resolution) => settings.resolution = resolution,
+                        None => warn!("Invalid resolution {value}"),
+                    }
+                }
+                "monitor" => match value.parse() {
+                    Ok(monitor) => settings.monitor = monitor,
+                    Err(_) => warn!("Invalid monitor {value}"),
+                },
+                "vsync" => match VSync::ALL.into_iter().find(|vsync| vsync.key() == value) {
+                    Some(vsync) => settings.vsync = vsync,
+                    // Settings from when mailbox was a mode of its own
+                    None if value == "mailbox" => settings.vsync = VSync::Off,
+                    None => warn!("Unknown vsync mode {value}"),
+                },
+                // Zero means no cap
+                "frame_rate_cap" => match value.parse() {
+                    Ok(cap) => settings.frame_rate_cap = Some(cap).filter(|cap| *cap > 0),
+                    Err(_) => warn!("Invalid frame rate cap {value}"),
+                },
+                "screen_shake" => parse_toggle(value, &mut settings.screen_shake),
+                "zoom_punch" => parse_toggle(value, &mut settings.zoom_punch),
+                "camera_follow" => parse_toggle(value, &mut settings.camera_follow),
+                "reduced_flashing" => parse_toggle(value, &mut settings.reduced_flashing),
+                "narration" => parse_toggle(value, &mut settings.narration),
+                "assist_slow_world" => parse_toggle(value, &mut settings.assist_slow_world),
+                "assist_start_shield" => parse_toggle(value, &mut settings.assist_start_shield),
+                "assist_no_lose_life" => parse_toggle(value, &mut settings.assist_no_lose_life),
+                "turn_sensitivity" => match value.parse::<f32>() {
+                    Ok(sensitivity) => {
+                        settings.turn_sensitivity =
+                            sensitivity.clamp(TURN_SENSITIVITY_MIN, TURN_SENSITIVITY_MAX)
+                    }
+                    Err(_) => warn!("Invalid turn sensitivity {value}"),
+                },
+                "toggle_throttle" => parse_toggle(value, &mut settings.toggle_throttle),
+                "hud_corner" => match HudCorner::ALL
+                    .into_iter()
+                    .find(|corner| corner.key() == value)
+                {
+                    Some(corner) => settings.hud_corner = corner,
+                    None => warn!("Unknown HUD corner {value}"),
+                },
+                "hud_compact" => parse_toggle(value, &mut settings.hud_compact),
+                "hud_opacity" => match value.parse::<f32>() {
+                    Ok(opacity) => settings.hud_opacity = opacity.clamp(0.0, 1.0),
+                    Err(_) => warn!("Invalid HUD opacity {value}"),
+                },
+                "muted" => parse_toggle(value, &mut settings.muted),
+                _ if key.starts_with("bind_") => {
+                    let action = Action::ALL
+                        .into_iter()
+                        .find(|action| key == format!("bind_{}", action.key()));
+                    match (action, parse_key(value)) {
+                        (Some(action), Some(key)) => settings.bindings.bind(action, key),
+                        (None, _) => warn!("Unknown action {key}"),
+                        (_, None) => warn!("Unknown key {value}"),
+                    }
+                }
+                _ => warn!("Unknown settings key {key}"),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+fn parse_cap(value: &str, cap: &mut usize) {
+    match value.parse() {
+        Ok(value) => *cap = value,
+        Err(_) => warn!("Invalid entity cap {value}"),
+    }
+}
+
+fn parse_toggle(value: &str, toggle: &mut bool) {
+    match value.parse() {
+        Ok(value) => *toggle = value,
+        Err(_) => warn!("Invalid toggle {value}"),
+    }
+}
+
+#[derive(Component)]
+struct OptionsEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OptionsItem {
+    Language,
+    DisplayMode,
+    Resolution,
+    Monitor,
+    VSync,
+    FrameRateCap,
+    ScreenShake,
+    ZoomPunch,
+    CameraFollow,
+    ReducedFlashing,
+    Narration,
+    AssistSlowWorld,
+    AssistStartShield,
+    AssistNoLoseLife,
+    TurnSensitivity,
+    ToggleThrottle,
+    HudCorner,
+    HudCompact,
+    HudOpacity,
+    Controls,
+    Back,
+}
+
+const ITEMS: [OptionsItem; 21] = [
+    OptionsItem::Language,
+    OptionsItem::DisplayMode,
+    OptionsItem::Resolution,
+    OptionsItem::Monitor,
+    OptionsItem::VSync,
+    OptionsItem::FrameRateCap,
+    OptionsItem::ScreenShake,
+    OptionsItem::ZoomPunch,
+    OptionsItem::CameraFollow,
+    OptionsItem::ReducedFlashing,
+    OptionsItem::Narration,
+    OptionsItem::AssistSlowWorld,
+    OptionsItem::AssistStartShield,
+    OptionsItem::AssistNoLoseLife,
+    OptionsItem::TurnSensitivity,
+    OptionsItem::ToggleThrottle,
+    OptionsItem::HudCorner,
+    OptionsItem::HudCompact,
+    OptionsItem::HudOpacity,
+    OptionsItem::Controls,
+    OptionsItem::Back,
+];
+
+/// Items fitting on the screen at once, the rest scroll into view with the selection
+const VISIBLE_ITEMS: usize = 11;
+
+#[derive(Component)]
+struct OptionsItemText {
+    index: usize,
+    item: OptionsItem,
+}
+
+#[derive(Component)]
+struct OptionsTitleText;
+
+pub struct OptionsPlugin;
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = Settings::load().unwrap_or_default();
+        app.insert_resource(settings)
+            .add_systems(OnEnter(AppState::Options), init_options)
+            .add_systems(
+                OnExit(AppState::Options),
+                crate::despawn_tagged::<OptionsEntity>,
+            )
+            .add_systems(
+                Update,
+                (options_input, options_text_system.after(options_input))
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::Options)),
+            )
+            .add_systems(
+                Update,
+                (apply_display_settings, apply_volume).run_if(resource_changed::<Settings>()),
+            )
+            .add_systems(Update, mute_system.after(crate::input::update_input_state))
+            .add_systems(Last, frame_rate_limiter);
+    }
+}
+
+fn init_options(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+) {
+    commands
+        .spawn(MenuNavigation::new(ITEMS.len()))
+        .insert(OptionsEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(OptionsEntity);
+
+    let font = game_assets.font.clone();
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ),
+            text_anchor: Anchor::CenterLeft,
+            transform: Transform::from_xyz(-250.0, 180.0, 0.1),
+            ..default()
+        })
+        .insert(OptionsTitleText)
+        .insert(OptionsEntity);
+    for (index, item) in ITEMS.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::CenterLeft,
+                ..default()
+            })
+            .insert(OptionsItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(OptionsEntity);
+    }
+}
+
+fn options_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut settings: ResMut<Settings>,
+    translations: Res<Translations>,
+    translation_assets: Res<Assets<Translation>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    // Sliders move both ways, left and right instead of cycling on ok
+    let slide = match (input.menu_left, input.menu_right) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+    if input.back {
+        next_state.set(AppState::Title);
+    } else if ITEMS[menu.selected] == OptionsItem::TurnSensitivity {
+        if slide != 0.0 || input.ok {
+            let step = if input.ok { 1.0 } else { slide };
+            let sensitivity = settings.turn_sensitivity + step * TURN_SENSITIVITY_STEP;
+            // Rounded to the step so that the value doesn't drift
+            settings.turn_sensitivity = ((sensitivity / TURN_SENSITIVITY_STEP).round()
+                * TURN_SENSITIVITY_STEP)
+                .clamp(TURN_SENSITIVITY_MIN, TURN_SENSITIVITY_MAX);
+            if let Err(err) = settings.save() {
+                error!("Could not save settings: {err}");
+            }
+        }
+    } else if input.ok {
+        match ITEMS[menu.selected] {
+            OptionsItem::Language => {
+                let languages = translations.languages(&translation_assets);
+                let next = languages
+                    .iter()
+                    .position(|language| *language == settings.language)
+                    .map_or(0, |index| (index + 1) % languages.len());
+                settings.language = languages[next].clone();
+            }
+            OptionsItem::DisplayMode => {
+                let next = DisplayMode::ALL
+                    .iter()
+                    .position(|mode| *mode == settings.display_mode)
+                    .map_or(0, |index| (index + 1) % DisplayMode::ALL.len());
+                settings.display_mode = DisplayMode::ALL[next];
+            }
+            OptionsItem::Resolution => {
+                let next = RESOLUTIONS
+                    .iter()
+                    .position(|resolution| *resolution == settings.resolution)
+                    .map_or(0, |index| (index + 1) % RESOLUTIONS.len());
+                settings.resolution = RESOLUTIONS[next];
+            }
+            OptionsItem::Monitor => settings.monitor = (settings.monitor + 1) % MAX_MONITORS,
+            OptionsItem::VSync => {
+                let next = VSync::ALL
+                    .iter()
+                    .position(|vsync| *vsync == settings.vsync)
+                    .map_or(0, |index| (index + 1) % VSync::ALL.len());
+                settings.vsync = VSync::ALL[next];
+            }
+            OptionsItem::FrameRateCap => {
+                let next = FRAME_RATE_CAPS
+                    .iter()
+                    .position(|cap| *cap == settings.frame_rate_cap)
+                    .map_or(0, |index| (index + 1) % FRAME_RATE_CAPS.len());
+                settings.frame_rate_cap = FRAME_RATE_CAPS[next];
+            }
+            OptionsItem::ScreenShake => settings.screen_shake = !settings.screen_shake,
+            OptionsItem::ZoomPunch => settings.zoom_punch = !settings.zoom_punch,
+            OptionsItem::CameraFollow => settings.camera_follow = !settings.camera_follow,
+            OptionsItem::ReducedFlashing => settings.reduced_flashing = !settings.reduced_flashing,
+            OptionsItem::Narration => settings.narration = !settings.narration,
+            OptionsItem::AssistSlowWorld => {
+                settings.assist_slow_world = !settings.assist_slow_world
+            }
+            OptionsItem::AssistStartShield => {
+                settings.assist_start_shield = !settings.assist_start_shield
+            }
+            OptionsItem::AssistNoLoseLife => {
+                settings.assist_no_lose_life = !settings.assist_no_lose_life
+            }
+            // Adjusted above
+            OptionsItem::TurnSensitivity => return,
+            OptionsItem::ToggleThrottle => settings.toggle_throttle = !settings.toggle_throttle,
+            OptionsItem::HudCorner => {
+                let next = HudCorner::ALL
+                    .iter()
+                    .position(|corner| *corner == settings.hud_corner)
+                    .map_or(0, |index| (index + 1) % HudCorner::ALL.len());
+                settings.hud_corner = HudCorner::ALL[next];
+            }
+            OptionsItem::HudCompact => settings.hud_compact = !settings.hud_compact,
+            OptionsItem::HudOpacity => {
+                let next = HUD_OPACITIES
+                    .iter()
+                    .position(|opacity| *opacity == settings.hud_opacity)
+                    .map_or(0, |index| (index + 1) % HUD_OPACITIES.len());
+                settings.hud_opacity = HUD_OPACITIES[next];
+            }
+            OptionsItem::Controls => {
+                next_state.set(AppState::Controls);
+                return;
+            }
+            OptionsItem::Back => {
+                next_state.set(AppState::Title);
+                return;
+            }
+        }
+        if let Err(err) = settings.save() {
+            error!("Could not save settings: {err}");
+        }
+    }
+}
+
+/// Moves and resizes the game window to match the display settings
+fn apply_display_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut applied: Local<Option<(DisplayMode, (u32, u32), usize, VSync)>>,
+) {
+    // Other settings changing shouldn't snap a moved window back into place
+    let display = (
+        settings.display_mode,
+        settings.resolution,
+        settings.monitor,
+        settings.vsync,
+    );
+    if *applied == Some(display) {
+        return;
+    }
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    *applied = Some(display);
+    let (width, height) = settings.resolution;
+    window.resolution.set(width as f32, height as f32);
+    window.position = WindowPosition::Centered(MonitorSelection::Index(settings.monitor));
+    window.mode = settings.display_mode.window_mode();
+    window.present_mode = settings.vsync.present_mode();
+}
+
+fn mute_system(input: Res<InputState>, mut settings: ResMut<Settings>) {
+    if !input.mute {
+        return;
+    }
+    settings.muted = !settings.muted;
+    if let Err(err) = settings.save() {
+        error!("Could not save settings: {err}");
+    }
+}
+
+/// Silences new and already playing sounds while muted
+fn apply_volume(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    sinks_query: Query<&AudioSink>,
+) {
+    let volume = if settings.muted { 0.0 } else { 1.0 };
+    *global_volume = GlobalVolume::new(volume);
+    for sink in sinks_query.iter() {
+        sink.set_volume(volume);
+    }
+}
+
+/// Sleeps off the rest of the frame when frames are faster than the frame rate cap
+fn frame_rate_limiter(settings: Res<Settings>, mut last_frame: Local<Option<Instant>>) {
+    if let (Some(cap), Some(last_frame)) = (settings.frame_rate_cap, *last_frame) {
+        let frame_time = Duration::from_secs_f64(1.0 / cap as f64);
+        let elapsed = last_frame.elapsed();
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
+    }
+    *last_frame = Some(Instant::now());
+}
+
+fn options_text_system(
+    menu_query: Query<&MenuNavigation>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut title_query: Query<&mut Text, (With<OptionsTitleText>, Without<OptionsItemText>)>,
+    mut items_query: Query<(&OptionsItemText, &mut Text, &mut Transform, &mut Visibility)>,
+    mut scroll: Local<usize>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    *scroll = (*scroll)
+        .min(menu.selected)
+        .max((menu.selected + 1).saturating_sub(VISIBLE_ITEMS));
+    for mut text in title_query.iter_mut() {
+        text.sections[0].value = localization.get("Options").to_string();
+    }
+    for (item_text, mut text, mut transform, mut visibility) in items_query.iter_mut() {
+        let row = item_text.index as f32 - *scroll as f32;
+        *transform = Transform::from_xyz(-250.0, 130.0 - row * 30.0, 0.1);
+        *visibility = if (*scroll..*scroll + VISIBLE_ITEMS).contains(&item_text.index) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        let label = match item_text.item {
+            OptionsItem::Language => {
+                localization.format("Language: {language}", &[("language", &settings.language)])
+            }
+            OptionsItem::DisplayMode => localization.format(
+                "Display: {mode}",
+                &[("mode", &localization.get(settings.display_mode.name()))],
+            ),
+            OptionsItem::Resolution => {
+                let (width, height) = settings.resolution;
+                localization.format(
+                    "Resolution: {width}x{height}",
+                    &[("width", &width), ("height", &height)],
+                )
+            }
+            OptionsItem::Monitor => localization.format(
+                "Monitor: {monitor}",
+                &[("monitor", &(settings.monitor + 1))],
+            ),
+            OptionsItem::VSync => localization.format(
+                "VSync: {vsync}",
+                &[("vsync", &localization.get(settings.vsync.name()))],
+            ),
+            OptionsItem::FrameRateCap => match settings.frame_rate_cap {
+                Some(cap) => localization.format("Frame rate cap: {fps}", &[("fps", &cap)]),
+                None => localization.get("Frame rate cap: off").to_string(),
+            },
+            OptionsItem::ScreenShake => localization.format(
+                "Screen shake: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.screen_shake)),
+                )],
+            ),
+            OptionsItem::ZoomPunch => localization.format(
+                "Zoom punch: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.zoom_punch)),
+                )],
+            ),
+            OptionsItem::CameraFollow => localization.format(
+                "Camera follow: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.camera_follow)),
+                )],
+            ),
+            OptionsItem::ReducedFlashing => localization.format(
+                "Reduced flashing: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.reduced_flashing)),
+                )],
+            ),
+            OptionsItem::Narration => localization.format(
+                "Narration: {toggle}",
+                &[("toggle", &localization.get(toggle_name(settings.narration)))],
+            ),
+            OptionsItem::AssistSlowWorld => localization.format(
+                "Assist, slower world: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.assist_slow_world)),
+                )],
+            ),
+            OptionsItem::AssistStartShield => localization.format(
+                "Assist, starting shield: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.assist_start_shield)),
+                )],
+            ),
+            OptionsItem::AssistNoLoseLife => localization.format(
+                "Assist, no -1up drops: {toggle}",
+                &[(
+                    "toggle",
+                    &localization.get(toggle_name(settings.assist_no_lose_life)),
+                )],
+            ),
+            OptionsItem::TurnSensitivity => localization.format(
+                "Turn sensitivity: < {sensitivity}% >",
+                &[(
+                    "sensitivity",
+                    &((settings.turn_sensitivity * 100.0).round() as u32),
+                )],
+            ),
+            OptionsItem::ToggleThrottle => localization.format(
+                "Throttle: {mode}",
+                &[(
+                    "mode",
+                    &localization.get(if settings.toggle_throttle {
+                        "Toggle"
+                    } else {
+                        "Hold"
+                    }),
+                )],
+            ),
+            OptionsItem::HudCorner => localization.format(
+                "HUD corner: {corner}",
+                &[("corner", &localization.get(settings.hud_corner.name()))],
+            ),
+            OptionsItem::HudCompact => localization.format(
+                "HUD layout: {layout}",
+                &[(
+                    "layout",
+                    &localization.get(if settings.hud_compact {
+                        "Compact"
+                    } else {
+                        "Full"
+                    }),
+                )],
+            ),
+            OptionsItem::HudOpacity => localization.format(
+                "HUD opacity: {opacity}%",
+                &[("opacity", &((settings.hud_opacity * 100.0).round() as u32))],
+            ),
+            OptionsItem::Controls => localization.get("Controls").to_string(),
+            OptionsItem::Back => localization.get("Back").to_string(),
+        };
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label
+        };
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn toggle_name(enabled: bool) -> &'static str {
+    if enabled {
+        "On"
+    } else {
+        "Off"
+    }
+}
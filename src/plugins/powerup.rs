@@ -0,0 +1,388 @@
+use crate::{
+    bundles::*,
+    collision::CollisionEvent,
+    components::*,
+    constants::*,
+    plugins::{ActiveLevelDefinition, Died, LevelDefinition, Localization, Settings},
+    resources::*,
+    utils::lerp,
+    AppState, GameSet,
+};
+use bevy::prelude::*;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    random, thread_rng,
+};
+
+#[derive(Component)]
+struct BulletTimeTint;
+
+pub struct PowerupPlugin;
+impl Plugin for PowerupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                mineral_magnet_system,
+                timed_effect_system,
+                bullet_time_system.after(timed_effect_system),
+                double_score_system.after(timed_effect_system),
+            )
+                .in_set(GameSet::Simulation)
+                .run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(Update, drop_table_system.run_if(in_state(AppState::InGame)))
+        .add_systems(OnExit(AppState::InGame), reset_powerup_effects)
+        .add_systems(
+            Update,
+            (ship_powerup_collision_system, ship_mineral_collision_system)
+                .in_set(GameSet::Resolution)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Picks a powerup to drop, weighted by how useful it would be right now.
+/// The level definition can override the base weights.
+fn random_powerup(
+    level: u32,
+    ship: &Ship,
+    health: &Health,
+    definition: Option<&LevelDefinition>,
+    lose_life: bool,
+) -> Powerup {
+    let weights = Powerup::ALL.map(|powerup| {
+        let weight = definition
+            .and_then(|definition| definition.powerup_weights.get(&powerup).copied())
+            .unwrap_or_else(|| powerup.drop_weight());
+        let adjustment = match powerup {
+            // Extra lives matter less the more of them there already are
+            Powerup::ExtraLife => 1.0 / ship.lives.max(1) as f32,
+            Powerup::LoseLife if level <= 1 || !lose_life => 0.0,
+            Powerup::PlasmaAmmo if ship.weapon_plasma_level == 0 => 0.0,
+            Powerup::Drone if ship.drones >= MAX_DRONES => 0.0,
+            _ => 1.0,
+        };
+        let bonus = match powerup {
+            Powerup::Repair => (1.0 - health.fraction()) * REPAIR_DAMAGED_WEIGHT,
+            _ => 0.0,
+        };
+        (weight * adjustment + bonus).max(0.0)
+    });
+    match WeightedIndex::new(weights) {
+        Ok(distribution) => Powerup::ALL[distribution.sample(&mut thread_rng())],
+        // Everything weighted out by the level definition
+        Err(_) => Powerup::Shield,
+    }
+}
+
+/// Spawns the drops of anything with a `DropTable` as it dies
+fn drop_table_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    droppers_query: Query<(&DropTable, &Transform)>,
+    ships_query: Query<(&Ship, &Health)>,
+    active_level_definition: Res<ActiveLevelDefinition>,
+    level: Res<Level>,
+    sprite_sheets: Res<SpriteSheets>,
+    settings: Res<Settings>,
+) {
+    for event in died_events.iter() {
+        let Ok((drop_table, transform)) = droppers_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        let speed = lerp(30.0, 80.0, random());
+        let velocity = Vec2::from_angle(random::<f32>() * std::f32::consts::TAU) * speed;
+        let powerup = match drop_table.pick() {
+            Some(DropEntry::Powerup(Powerup::LoseLife)) if settings.assist_no_lose_life => None,
+            Some(DropEntry::Powerup(powerup)) => Some(powerup),
+            // Without a ship there is nothing to pick for
+            Some(DropEntry::AnyPowerup) => ships_query.get_single().ok().map(|(ship, health)| {
+                random_powerup(
+                    level.number(),
+                    ship,
+                    health,
+                    active_level_definition.0.as_ref(),
+                    !settings.assist_no_lose_life,
+                )
+            }),
+            None => None,
+        };
+        if let Some(powerup) = powerup {
+            commands.spawn(PowerupBundle::new(
+                powerup,
+                position,
+                velocity,
+                5.0,
+                &sprite_sheets.powerup,
+            ));
+        }
+        if drop_table.minerals > 0 {
+            commands.spawn(MineralBundle::new(
+                sprite_sheets.as_ref(),
+                level.asteroid_variant(),
+                drop_table.minerals,
+                position,
+                -velocity,
+            ));
+        }
+    }
+}
+
+fn ship_powerup_collision_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ships_query: Query<(&mut Ship, &mut Health, &mut ShieldEnergy, &Transform)>,
+    powerups_query: Query<&Powerup>,
+    mut effects_query: Query<&mut TimedEffect>,
+    mut run_stats: ResMut<RunStats>,
+    game_assets: Res<GameAssets>,
+    sprite_sheets: Res<SpriteSheets>,
+    localization: Res<Localization>,
+) {
+    for event in collision_events.iter() {
+        let Ok(&powerup) = powerups_query.get(event.b) else {
+            continue;
+        };
+        let Ok((mut ship, mut health, mut shield, transform)) = ships_query.get_mut(event.a) else {
+            continue;
+        };
+        let text = match powerup {
+            Powerup::Laser => {
+                ship.weapon_rapid_level = (ship.weapon_rapid_level + 1).min(8);
+                "Laser +1"
+            }
+            Powerup::Spread => {
+                ship.weapon_spread_level = (ship.weapon_spread_level + 1).min(8);
+                "Spread +1"
+            }
+            Powerup::Beam => {
+                ship.weapon_beam_level = (ship.weapon_beam_level + 1).min(8);
+                "Beam +1"
+            }
+            Powerup::Plasma => {
+                ship.weapon_plasma_level = (ship.weapon_plasma_level + 1).min(8);
+                ship.plasma_ammo = (ship.plasma_ammo + PLASMA_AMMO_PICKUP).min(PLASMA_MAX_AMMO);
+                "Plasma +1"
+            }
+            Powerup::PlasmaAmmo => {
+                ship.plasma_ammo = (ship.plasma_ammo + PLASMA_AMMO_PICKUP).min(PLASMA_MAX_AMMO);
+                "Plasma ammo"
+            }
+            Powerup::Charge => {
+                ship.weapon_charge_level = (ship.weapon_charge_level + 1).min(8);
+                "Charge +1"
+            }
+            Powerup::Railgun => {
+                ship.weapon_railgun_level = (ship.weapon_railgun_level + 1).min(8);
+                "Railgun +1"
+            }
+            Powerup::Drone => {
+                ship.drones = (ship.drones + 1).min(MAX_DRONES);
+                "Drone"
+            }
+            Powerup::ExtraLife => {
+                ship.lives += 1;
+                "1up"
+            }
+            Powerup::LoseLife => {
+                ship.lives = ship.lives.max(1) - 1;
+                "-1up"
+            }
+            Powerup::Shield => {
+                shield.increase_max(SHIELD_PIP_ENERGY);
+                "Shield +1"
+            }
+            Powerup::BulletTime => {
+                start_timed_effect(
+                    &mut commands,
+                    &mut effects_query,
+                    powerup,
+                    BULLET_TIME_DURATION,
+                );
+                "Bullet time"
+            }
+            Powerup::DoubleScore => {
+                start_timed_effect(
+                    &mut commands,
+                    &mut effects_query,
+                    powerup,
+                    DOUBLE_SCORE_DURATION,
+                );
+                "x2 score"
+            }
+            Powerup::Overdrive => {
+                start_timed_effect(
+                    &mut commands,
+                    &mut effects_query,
+                    powerup,
+                    OVERDRIVE_DURATION,
+                );
+                "Overdrive"
+            }
+            Powerup::Repair => {
+                health.restore();
+                ship.cleanse();
+                "Repaired"
+            }
+        };
+        run_stats.powerups_collected += 1;
+        commands.entity(event.b).despawn();
+        let position = transform.translation.truncate();
+        commands.spawn(GameNotificationBundle::new(
+            localization.get(text).to_owned(),
+            game_assets.font.clone(),
+            position,
+            20.0,
+            1.0,
+        ));
+        commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
+    }
+}
+
+fn mineral_magnet_system(
+    ships_query: Query<(&Ship, &Transform)>,
+    mut minerals_query: Query<(&Transform, &mut Moving), (With<Mineral>, Without<Ship>)>,
+) {
+    for (ship, ship_transform) in ships_query.iter() {
+        if ship.respawn_delay > 0.0 || ship.lives == 0 {
+            continue;
+        }
+        let ship_position = ship_transform.translation.truncate();
+        for (mineral_transform, mut mineral_moving) in minerals_query.iter_mut() {
+            let diff = ship_position - mineral_transform.translation.truncate();
+            let distance = diff.length();
+            if distance < MINERAL_MAGNET_RADIUS && distance > 0.0 {
+                let pull = 1.0 - distance / MINERAL_MAGNET_RADIUS;
+                mineral_moving.velocity = diff / distance * lerp(50.0, MINERAL_MAGNET_SPEED, pull);
+            }
+        }
+    }
+}
+
+fn ship_mineral_collision_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    ships_query: Query<(&Ship, &Transform)>,
+    minerals_query: Query<&Mineral>,
+    mut minerals: ResMut<Minerals>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in collision_events.iter() {
+        let Ok(mineral) = minerals_query.get(event.b) else {
+            continue;
+        };
+        let Ok((ship, transform)) = ships_query.get(event.a) else {
+            continue;
+        };
+        if ship.respawn_delay > 0.0 || ship.lives == 0 {
+            continue;
+        }
+        minerals.increase(mineral.amount);
+        commands.entity(event.b).despawn();
+        commands.spawn(GameNotificationBundle::new(
+            format!("+{}", mineral.amount),
+            game_assets.font.clone(),
+            transform.translation.truncate(),
+            16.0,
+            0.5,
+        ));
+    }
+}
+
+/// Starts an effect, or restarts it if it's already active
+fn start_timed_effect(
+    commands: &mut Commands,
+    effects_query: &mut Query<&mut TimedEffect>,
+    powerup: Powerup,
+    duration: f32,
+) {
+    if let Some(mut effect) = effects_query
+        .iter_mut()
+        .find(|effect| effect.powerup == powerup)
+    {
+        effect.duration = duration;
+        effect.remaining = duration;
+    } else {
+        // Spatial so that the HUD can show it
+        commands
+            .spawn(TimedEffect {
+                powerup,
+                duration,
+                remaining: duration,
+            })
+            .insert(SpatialBundle::default())
+            .insert(LevelEntity);
+    }
+}
+
+fn timed_effect_system(
+    mut commands: Commands,
+    mut effects_query: Query<(Entity, &mut TimedEffect)>,
+    time: Res<Time>,
+) {
+    for (entity, mut effect) in effects_query.iter_mut() {
+        // Counts down in real time, so bullet time doesn't stretch itself
+        effect.remaining -= time.delta_seconds();
+        if effect.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn effect_active(effects_query: &Query<&TimedEffect>, powerup: Powerup) -> bool {
+    effects_query
+        .iter()
+        .any(|effect| effect.powerup == powerup && effect.remaining > 0.0)
+}
+
+fn bullet_time_system(
+    mut commands: Commands,
+    effects_query: Query<&TimedEffect>,
+    tint_query: Query<Entity, With<BulletTimeTint>>,
+    mut time_scale: ResMut<TimeScale>,
+    settings: Res<Settings>,
+) {
+    let active = effect_active(&effects_query, Powerup::BulletTime);
+    time_scale.0 = settings.world_speed() * if active { BULLET_TIME_SCALE } else { 1.0 };
+
+    if active && tint_query.is_empty() {
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.4, 0.3, 1.0, 0.15),
+                    custom_size: Some(Vec2::new(GAME_WIDTH as f32, GAME_HEIGHT as f32)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 5.0),
+                ..default()
+            })
+            .insert(BulletTimeTint)
+            .insert(LevelEntity);
+    } else if !active {
+        for entity in tint_query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn double_score_system(
+    effects_query: Query<&TimedEffect>,
+    mut score_multiplier: ResMut<ScoreMultiplier>,
+) {
+    score_multiplier.0 = if effect_active(&effects_query, Powerup::DoubleScore) {
+        2
+    } else {
+        1
+    };
+}
+
+fn reset_powerup_effects(
+    mut time_scale: ResMut<TimeScale>,
+    mut score_multiplier: ResMut<ScoreMultiplier>,
+    settings: Res<Settings>,
+) {
+    time_scale.0 = settings.world_speed();
+    score_multiplier.0 = 1;
+}
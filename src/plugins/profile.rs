@@ -0,0 +1,444 @@
+use crate::bundles::GameNotificationBundle;
+use crate::components::ShipWeapon;
+use crate::plugins::Localization;
+use crate::resources::{GameAssets, Level, RunStats, Score, ShotStats};
+use crate::utils::crypt;
+use crate::AppState;
+use bevy::{prelude::*, utils::HashMap};
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+
+#[derive(Component)]
+struct ProfileEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PermanentBonus {
+    StartingShield,
+    ExtraLife,
+    StartingMinerals,
+}
+
+impl PermanentBonus {
+    pub const ALL: [PermanentBonus; 3] = [
+        PermanentBonus::StartingShield,
+        PermanentBonus::ExtraLife,
+        PermanentBonus::StartingMinerals,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            PermanentBonus::StartingShield => "Starting shield",
+            PermanentBonus::ExtraLife => "Extra starting life",
+            PermanentBonus::StartingMinerals => "Starting minerals",
+        }
+    }
+    pub fn cost(&self) -> u32 {
+        match self {
+            PermanentBonus::StartingShield => 5,
+            PermanentBonus::ExtraLife => 10,
+            PermanentBonus::StartingMinerals => 3,
+        }
+    }
+    pub fn max_level(&self) -> u8 {
+        match self {
+            PermanentBonus::StartingShield => 3,
+            PermanentBonus::ExtraLife => 2,
+            PermanentBonus::StartingMinerals => 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Unlock {
+    Veteran,
+    UfoHunter,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 2] = [Unlock::Veteran, Unlock::UfoHunter];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unlock::Veteran => "Veteran",
+            Unlock::UfoHunter => "UFO Hunter",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Unlock::Veteran => "Reach level 10",
+            Unlock::UfoHunter => "Destroy 5 UFOs in one run",
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            Unlock::Veteran => "veteran",
+            Unlock::UfoHunter => "ufo_hunter",
+        }
+    }
+    fn from_key(key: &str) -> Option<Self> {
+        Unlock::ALL.into_iter().find(|unlock| unlock.key() == key)
+    }
+}
+
+/// Tint of the player ship's sprite
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ShipColor {
+    #[default]
+    Classic,
+    Veteran,
+    Hunter,
+}
+
+impl ShipColor {
+    pub const ALL: [ShipColor; 3] = [ShipColor::Classic, ShipColor::Veteran, ShipColor::Hunter];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShipColor::Classic => "Classic",
+            ShipColor::Veteran => "Veteran",
+            ShipColor::Hunter => "Hunter",
+        }
+    }
+    pub fn color(&self) -> Color {
+        match self {
+            ShipColor::Classic => Color::WHITE,
+            ShipColor::Veteran => Color::rgb(1.0, 0.85, 0.5),
+            ShipColor::Hunter => Color::rgb(1.0, 0.55, 0.55),
+        }
+    }
+    pub fn required_unlock(&self) -> Option<Unlock> {
+        match self {
+            ShipColor::Classic => None,
+            ShipColor::Veteran => Some(Unlock::Veteran),
+            ShipColor::Hunter => Some(Unlock::UfoHunter),
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            ShipColor::Classic => "classic",
+            ShipColor::Veteran => "veteran",
+            ShipColor::Hunter => "hunter",
+        }
+    }
+    fn from_key(key: &str) -> Option<Self> {
+        ShipColor::ALL.into_iter().find(|color| color.key() == key)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ProjectilePalette {
+    #[default]
+    Classic,
+    Gold,
+    Crimson,
+}
+
+impl ProjectilePalette {
+    pub const ALL: [ProjectilePalette; 3] = [
+        ProjectilePalette::Classic,
+        ProjectilePalette::Gold,
+        ProjectilePalette::Crimson,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProjectilePalette::Classic => "Classic",
+            ProjectilePalette::Gold => "Gold",
+            ProjectilePalette::Crimson => "Crimson",
+        }
+    }
+    pub fn color(&self) -> Color {
+        match self {
+            ProjectilePalette::Classic => Color::WHITE,
+            ProjectilePalette::Gold => Color::rgb(1.0, 0.8, 0.3),
+            ProjectilePalette::Crimson => Color::rgb(1.0, 0.3, 0.3),
+        }
+    }
+    pub fn required_unlock(&self) -> Option<Unlock> {
+        match self {
+            ProjectilePalette::Classic => None,
+            ProjectilePalette::Gold => Some(Unlock::Veteran),
+            ProjectilePalette::Crimson => Some(Unlock::UfoHunter),
+        }
+    }
+    fn key(&self) -> &'static str {
+        match self {
+            ProjectilePalette::Classic => "classic",
+            ProjectilePalette::Gold => "gold",
+            ProjectilePalette::Crimson => "crimson",
+        }
+    }
+    fn from_key(key: &str) -> Option<Self> {
+        ProjectilePalette::ALL
+            .into_iter()
+            .find(|palette| palette.key() == key)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Profile {
+    pub unlock_points: u32,
+    pub starting_shield: u8,
+    pub extra_lives: u8,
+    pub starting_minerals: u8,
+    pub unlocks: Vec<Unlock>,
+    pub ship_color: ShipColor,
+    pub projectile_palette: ProjectilePalette,
+    /// Shots fired and hit over all runs, by weapon
+    pub weapon_stats: HashMap<ShipWeapon, ShotStats>,
+    pub runs_played: u32,
+    pub asteroids_destroyed: u32,
+    /// Seconds spent in game over all runs
+    pub play_time: f32,
+    pub highest_level: u32,
+}
+
+pub struct ProfilePlugin;
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let profile = match Profile::load() {
+            Ok(profile) => profile,
+            Err(err) if err.kind() == ErrorKind::NotFound => Profile::default(),
+            Err(err) => {
+                warn!("Could not load the profile, starting a new one: {err}");
+                Profile::default()
+            }
+        };
+        app.insert_resource(profile)
+            .add_systems(
+                OnEnter(AppState::HighScoreEntry),
+                (record_lifetime_stats, award_unlock_points).chain(),
+            )
+            .add_systems(Update, unlock_system.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                OnExit(AppState::HighScoreEntry),
+                crate::despawn_tagged::<ProfileEntity>,
+            );
+    }
+}
+
+/// Unlock points earned for a finished run
+pub fn run_unlock_points(level: &Level, score: &Score) -> u32 {
+    level.0 + score.value() / 1000
+}
+
+fn record_lifetime_stats(
+    mut profile: ResMut<Profile>,
+    run_stats: Res<RunStats>,
+    level: Res<Level>,
+) {
+    profile.runs_played += 1;
+    profile.asteroids_destroyed += run_stats.asteroids_destroyed.iter().sum::<u32>();
+    profile.play_time += run_stats.time_played;
+    profile.highest_level = profile.highest_level.max(level.number());
+    for (weapon, stats) in run_stats.weapons.iter() {
+        let lifetime = profile.weapon_stats.entry(*weapon).or_default();
+        lifetime.fired += stats.fired;
+        lifetime.hit += stats.hit;
+    }
+    if let Err(err) = profile.save() {
+        error!("Could not save profile: {err}");
+    }
+}
+
+fn award_unlock_points(
+    mut commands: Commands,
+    mut profile: ResMut<Profile>,
+    level: Res<Level>,
+    score: Res<Score>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    let points = run_unlock_points(&level, &score);
+    if points == 0 {
+        return;
+    }
+    profile.unlock_points += points;
+    if let Err(err) = profile.save() {
+        error!("Could not save profile: {err}");
+    }
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                localization.format("+{points} unlock points", &[("points", &points)]),
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -200.0, 0.01),
+            ..default()
+        })
+        .insert(ProfileEntity);
+}
+
+fn unlock_system(
+    mut commands: Commands,
+    mut profile: ResMut<Profile>,
+    level: Res<Level>,
+    run_stats: Res<RunStats>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    for unlock in Unlock::ALL {
+        let achieved = match unlock {
+            Unlock::Veteran => level.number() >= 10,
+            Unlock::UfoHunter => run_stats.ufos_destroyed >= 5,
+        };
+        if achieved && !profile.is_unlocked(unlock) {
+            profile.unlocks.push(unlock);
+            if let Err(err) = profile.save() {
+                error!("Could not save profile: {err}");
+            }
+            commands.spawn(GameNotificationBundle::new(
+                localization.format(
+                    "Unlocked: {unlock}",
+                    &[("unlock", &localization.get(unlock.name()))],
+                ),
+                game_assets.font.clone(),
+                Vec2::new(0.0, 100.0),
+                30.0,
+                3.0,
+            ));
+        }
+    }
+}
+
+impl Profile {
+    pub fn is_unlocked(&self, unlock: Unlock) -> bool {
+        self.unlocks.contains(&unlock)
+    }
+    pub fn color_available(&self, color: ShipColor) -> bool {
+        color
+            .required_unlock()
+            .map_or(true, |unlock| self.is_unlocked(unlock))
+    }
+    pub fn palette_available(&self, palette: ProjectilePalette) -> bool {
+        palette
+            .required_unlock()
+            .map_or(true, |unlock| self.is_unlocked(unlock))
+    }
+    /// Shots fired and hit over all runs and weapons
+    pub fn lifetime_shots(&self) -> ShotStats {
+        self.weapon_stats
+            .values()
+            .fold(ShotStats::default(), |total, stats| ShotStats {
+                fired: total.fired + stats.fired,
+                hit: total.hit + stats.hit,
+            })
+    }
+    /// The weapon with the most shots fired over all runs
+    pub fn favorite_weapon(&self) -> Option<ShipWeapon> {
+        self.weapon_stats
+            .iter()
+            .max_by_key(|(_, stats)| stats.fired)
+            .map(|(weapon, _)| *weapon)
+    }
+    pub fn bonus_level(&self, bonus: PermanentBonus) -> u8 {
+        match bonus {
+            PermanentBonus::StartingShield => self.starting_shield,
+            PermanentBonus::ExtraLife => self.extra_lives,
+            PermanentBonus::StartingMinerals => self.starting_minerals,
+        }
+    }
+    pub fn can_buy(&self, bonus: PermanentBonus) -> bool {
+        self.bonus_level(bonus) < bonus.max_level() && self.unlock_points >= bonus.cost()
+    }
+    pub fn buy(&mut self, bonus: PermanentBonus) -> bool {
+        if !self.can_buy(bonus) {
+            return false;
+        }
+        self.unlock_points -= bonus.cost();
+        match bonus {
+            PermanentBonus::StartingShield => self.starting_shield += 1,
+            PermanentBonus::ExtraLife => self.extra_lives += 1,
+            PermanentBonus::StartingMinerals => self.starting_minerals += 1,
+        }
+        true
+    }
+    pub fn save(&self) -> std::io::Result<()> {
+        let unlocks: Vec<_> = self.unlocks.iter().map(Unlock::key).collect();
+        let weapon_stats: Vec<_> = self
+            .weapon_stats
+            .iter()
+            .map(|(weapon, stats)| format!("{}={}/{}", weapon_key(*weapon), stats.fired, stats.hit))
+            .collect();
+        let content = format!(
+            "unlock_points:{}\nstarting_shield:{}\nextra_lives:{}\nstarting_minerals:{}\n\
+             unlocks:{}\nship_color:{}\nprojectile_palette:{}\nweapon_stats:{}\n\
+             runs_played:{}\nasteroids_destroyed:{}\nplay_time:{}\nhighest_level:{}\n",
+            self.unlock_points,
+            self.starting_shield,
+            self.extra_lives,
+            self.starting_minerals,
+            unlocks.join(","),
+            self.ship_color.key(),
+            self.projectile_palette.key(),
+            weapon_stats.join(","),
+            self.runs_played,
+            self.asteroids_destroyed,
+            self.play_time,
+            self.highest_level,
+        );
+        let encoded = crypt(content.as_bytes());
+        let mut file = File::create("profile.enc")?;
+        file.write_all(&encoded)?;
+        Ok(())
+    }
+    fn load() -> std::io::Result<Self> {
+        let mut file = File::open("profile.enc")?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        let decoded = crypt(&content);
+        let mut profile = Profile::default();
+        let content = std::str::from_utf8(&decoded)
+            .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?;
+        for (key, value) in content.lines().filter_map(|line| line.split_once(':')) {
+            match key {
+                "unlock_points" => profile.unlock_points = value.parse().unwrap_or(0),
+                "starting_shield" => profile.starting_shield = value.parse().unwrap_or(0),
+                "extra_lives" => profile.extra_lives = value.parse().unwrap_or(0),
+                "starting_minerals" => profile.starting_minerals = value.parse().unwrap_or(0),
+                "unlocks" => {
+                    profile.unlocks = value.split(',').filter_map(Unlock::from_key).collect()
+                }
+                "ship_color" => profile.ship_color = ShipColor::from_key(value).unwrap_or_default(),
+                "projectile_palette" => {
+                    profile.projectile_palette =
+                        ProjectilePalette::from_key(value).unwrap_or_default()
+                }
+                "weapon_stats" => {
+                    profile.weapon_stats = value.split(',').filter_map(parse_weapon_stats).collect()
+                }
+                "runs_played" => profile.runs_played = value.parse().unwrap_or(0),
+                "asteroids_destroyed" => profile.asteroids_destroyed = value.parse().unwrap_or(0),
+                "play_time" => profile.play_time = value.parse().unwrap_or(0.0),
+                "highest_level" => profile.highest_level = value.parse().unwrap_or(0),
+                _ => warn!("Unknown profile key {key}"),
+            }
+        }
+        Ok(profile)
+    }
+}
+
+fn weapon_key(weapon: ShipWeapon) -> &'static str {
+    match weapon {
+        ShipWeapon::Rapid => "rapid",
+        ShipWeapon::Spread => "spread",
+        ShipWeapon::Beam => "beam",
+        ShipWeapon::Plasma => "plasma",
+        ShipWeapon::Charge => "charge",
+        ShipWeapon::Railgun => "railgun",
+    }
+}
+
+/// Parses a `weapon=fired/hit` entry of the profile file
+fn parse_weapon_stats(entry: &str) -> Option<(ShipWeapon, ShotStats)> {
+    let (key, counts) = entry.split_once('=')?;
+    let (fired, hit) = counts.split_once('/')?;
+    let weapon = ShipWeapon::ALL
+        .into_iter()
+        .find(|weapon| weapon_key(*weapon) == key)?;
+    let stats = ShotStats {
+        fired: fired.parse().ok()?,
+        hit: hit.parse().ok()?,
+    };
+    Some((weapon, stats))
+}
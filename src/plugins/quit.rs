@@ -0,0 +1,212 @@
+use crate::constants::*;
+use crate::input::InputState;
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet};
+use crate::resources::GameAssets;
+use crate::{AppState, GameSet};
+use bevy::{app::AppExit, prelude::*};
+
+/// Whether the game is frozen behind the quit menu, alongside `AppState::InGame`
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    QuitMenu,
+}
+
+/// Set when the player chose to quit to desktop, so the game exits once the run
+/// has been recorded and any high score entered
+#[derive(Default, Resource)]
+pub struct QuitToDesktop(pub bool);
+
+#[derive(Component)]
+struct QuitMenuEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum QuitItem {
+    Resume,
+    QuitToTitle,
+    QuitToDesktop,
+}
+
+const ITEMS: [QuitItem; 3] = [
+    QuitItem::Resume,
+    QuitItem::QuitToTitle,
+    QuitItem::QuitToDesktop,
+];
+
+#[derive(Component)]
+struct QuitItemText {
+    index: usize,
+    item: QuitItem,
+}
+
+pub struct QuitPlugin;
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PauseState>()
+            .init_resource::<QuitToDesktop>()
+            .configure_sets(
+                Update,
+                (
+                    GameSet::Control.run_if(in_state(PauseState::Running)),
+                    GameSet::Simulation.run_if(in_state(PauseState::Running)),
+                    GameSet::Collision.run_if(in_state(PauseState::Running)),
+                    GameSet::Resolution.run_if(in_state(PauseState::Running)),
+                ),
+            )
+            .add_systems(
+                Update,
+                open_quit_menu
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(OnEnter(PauseState::QuitMenu), init_quit_menu)
+            .add_systems(
+                OnExit(PauseState::QuitMenu),
+                (resume_time, crate::despawn_tagged::<QuitMenuEntity>),
+            )
+            .add_systems(
+                Update,
+                (
+                    quit_menu_input,
+                    quit_menu_text_system.after(quit_menu_input),
+                )
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(PauseState::QuitMenu)),
+            )
+            .add_systems(OnExit(AppState::InGame), close_quit_menu)
+            .add_systems(OnExit(AppState::HighScoreEntry), exit_if_quitting);
+    }
+}
+
+fn open_quit_menu(input: Res<InputState>, mut next_state: ResMut<NextState<PauseState>>) {
+    if input.pause {
+        next_state.set(PauseState::QuitMenu);
+    }
+}
+
+fn close_quit_menu(mut next_state: ResMut<NextState<PauseState>>) {
+    next_state.set(PauseState::Running);
+}
+
+fn init_quit_menu(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+    mut time: ResMut<Time>,
+) {
+    // Stops everything timed while the menu is open, including what runs outside `GameSet`
+    time.pause();
+
+    commands
+        .spawn(MenuNavigation::new(ITEMS.len()))
+        .insert(QuitMenuEntity);
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.7),
+                custom_size: Some(Vec2::new(GAME_WIDTH as f32, GAME_HEIGHT as f32)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 20.0),
+            ..default()
+        })
+        .insert(QuitMenuEntity);
+
+    let font = game_assets.font.clone();
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                localization.get("Quit the game?"),
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, 60.0, 20.1),
+            ..default()
+        })
+        .insert(QuitMenuEntity);
+    for (index, item) in ITEMS.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, -index as f32 * 36.0, 20.1),
+                ..default()
+            })
+            .insert(QuitItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(QuitMenuEntity);
+    }
+}
+
+fn resume_time(mut time: ResMut<Time>) {
+    time.unpause();
+}
+
+fn quit_menu_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut quit_to_desktop: ResMut<QuitToDesktop>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.back {
+        pause_state.set(PauseState::Running);
+    } else if input.ok {
+        match ITEMS[menu.selected] {
+            QuitItem::Resume => pause_state.set(PauseState::Running),
+            // Ending the run the usual way records its stats and offers a high score
+            QuitItem::QuitToTitle => app_state.set(AppState::GameOver),
+            QuitItem::QuitToDesktop => {
+                quit_to_desktop.0 = true;
+                app_state.set(AppState::HighScoreEntry);
+            }
+        }
+    }
+}
+
+fn quit_menu_text_system(
+    menu_query: Query<&MenuNavigation>,
+    localization: Res<Localization>,
+    mut items_query: Query<(&QuitItemText, &mut Text)>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for (item_text, mut text) in items_query.iter_mut() {
+        let label = localization.get(match item_text.item {
+            QuitItem::Resume => "Resume",
+            QuitItem::QuitToTitle => "Quit to title",
+            QuitItem::QuitToDesktop => "Quit to desktop",
+        });
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label.to_string()
+        };
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn exit_if_quitting(quit_to_desktop: Res<QuitToDesktop>, mut app_exit: EventWriter<AppExit>) {
+    if quit_to_desktop.0 {
+        app_exit.send(AppExit);
+    }
+}
@@ -0,0 +1,472 @@
+use crate::{
+    bundles::*,
+    collision::CollisionEvent,
+    components::*,
+    constants::*,
+    input::InputState,
+    plugins::{DamageCause, DamageEvent, Died, Localization, Profile, Settings},
+    resources::*,
+    utils::*,
+    AppState, GameSet,
+};
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+pub struct ShipPlugin;
+impl Plugin for ShipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (ship_control_system, ship_physics.after(ship_control_system))
+                .in_set(GameSet::Control)
+                .run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(
+            Update,
+            (
+                ship_sprite,
+                ship_respawn_system,
+                assist_shield_system,
+                hyperspace_system,
+                shield_sprite,
+                shield_regen_system,
+            )
+                .in_set(GameSet::Simulation)
+                .run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(
+            Update,
+            (
+                ship_asteroid_collision_system,
+                ship_debris_collision_system,
+                ship_death_system,
+            )
+                .in_set(GameSet::Resolution)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+fn ship_respawn_system(
+    mut ships_query: Query<(
+        &mut Ship,
+        &mut Health,
+        &mut ShieldEnergy,
+        &mut Transform,
+        &mut Moving,
+        &mut Visibility,
+    )>,
+    settings: Res<Settings>,
+    modifiers: Res<Modifiers>,
+    time: Res<Time>,
+) {
+    for (mut ship, mut health, mut shield, mut transform, mut moving, mut visibility) in
+        ships_query.iter_mut()
+    {
+        if ship.lives > 0 && ship.respawn_delay > 0.0 {
+            ship.respawn_delay -= time.delta_seconds();
+            if ship.respawn_delay > 0.0 {
+                *visibility = Visibility::Hidden;
+                ship.invulnerability = 100.0;
+            } else {
+                *visibility = Visibility::Visible;
+                ship.invulnerability = SHIP_INVULNERABILITY;
+                health.restore();
+                give_assist_shield(&mut shield, &settings, &modifiers);
+                transform.translation = Vec3::ZERO;
+                moving.velocity = Vec2::ZERO;
+            }
+        } else if ship.lives <= 0 {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Gives the first life of a new ship the assist shield, respawns get theirs on respawning
+fn assist_shield_system(
+    mut ships_query: Query<&mut ShieldEnergy, Added<Ship>>,
+    settings: Res<Settings>,
+    modifiers: Res<Modifiers>,
+) {
+    for mut shield in ships_query.iter_mut() {
+        give_assist_shield(&mut shield, &settings, &modifiers);
+    }
+}
+
+fn give_assist_shield(shield: &mut ShieldEnergy, settings: &Settings, modifiers: &Modifiers) {
+    if settings.assist_start_shield && !modifiers.contains(Modifier::NoShields) {
+        shield.ensure(ASSIST_START_SHIELD);
+    }
+}
+
+fn ship_control_system(
+    mut ship_query: Query<(&mut Ship, Option<&InputState>)>,
+    player_input: Res<InputState>,
+    modifiers: Res<Modifiers>,
+    settings: Res<Settings>,
+) {
+    for (mut ship, ship_input) in ship_query.iter_mut() {
+        let input = ship_input.unwrap_or(&player_input);
+        // Bots hold their throttle whatever the player prefers
+        let toggle_throttle = settings.toggle_throttle && ship_input.is_none();
+        if ship.respawn_delay > 0.0 {
+            if toggle_throttle {
+                ship.throttle = false;
+            }
+            ship.fire = false;
+            ship.secondary_fire = false;
+            ship.hyperspace = false;
+            continue;
+        }
+        if !toggle_throttle {
+            ship.throttle = input.throttle;
+        } else if input.throttle_toggle {
+            ship.throttle = !ship.throttle;
+        }
+        ship.turn = match (input.left, input.right) {
+            (true, false) => ShipTurn::Left,
+            (false, true) => ShipTurn::Right,
+            _ => ShipTurn::Neutral,
+        };
+        ship.turn_amount = input.analog_turn.unwrap_or(1.0);
+        ship.fire = input.fire;
+        ship.secondary_fire = input.secondary_fire;
+        ship.hyperspace = input.hyperspace;
+        if input.weapon_1 {
+            ship.weapon = ShipWeapon::Rapid;
+        } else if input.weapon_2 {
+            ship.weapon = ShipWeapon::Spread;
+        } else if input.weapon_3 {
+            ship.weapon = ShipWeapon::Beam;
+        } else if input.weapon_4 && ship.has_ammo(ShipWeapon::Plasma) {
+            ship.weapon = ShipWeapon::Plasma;
+        } else if input.weapon_5 {
+            ship.weapon = ShipWeapon::Charge;
+        } else if input.weapon_6 {
+            ship.weapon = ShipWeapon::Railgun;
+        }
+
+        if input.weapon_next {
+            ship.next_weapon();
+        }
+        if input.weapon_prev {
+            ship.prev_weapon();
+        }
+        if !ship.has_ammo(ship.weapon) {
+            ship.next_weapon();
+        }
+        if modifiers.contains(Modifier::BeamOnly) {
+            ship.weapon = ShipWeapon::Beam;
+        }
+    }
+}
+
+fn ship_physics(
+    mut ship_query: Query<(&mut Ship, &mut Moving, &mut Transform)>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let time_delta = time.delta().as_secs_f32();
+
+    for (mut ship, mut moving, mut transform) in ship_query.iter_mut() {
+        ship.invulnerability = (ship.invulnerability - time_delta).max(0.);
+        let turn_rate = SHIP_TURN_RATE * ship.turn_amount * settings.turn_sensitivity;
+        let angular_velocity = match ship.turn {
+            ShipTurn::Neutral => 0.0,
+            ShipTurn::Left => turn_rate,
+            ShipTurn::Right => -turn_rate,
+        };
+        let acceleration = if ship.throttle { 50.0 } else { 0.0 };
+        transform.rotation *= Quat::from_rotation_z(angular_velocity * time_delta);
+        moving.acceleration = (transform.rotation * Vec3::Y * acceleration).truncate();
+    }
+}
+
+fn hyperspace_system(
+    mut commands: Commands,
+    mut ships_query: Query<(Entity, &mut Ship, &mut Transform, &mut Moving)>,
+    asteroids_query: Query<&Transform, (With<Asteroid>, Without<Ship>)>,
+    game_assets: Res<GameAssets>,
+    sprite_sheets: Res<SpriteSheets>,
+    localization: Res<Localization>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (ship_entity, mut ship, mut transform, mut moving) in ships_query.iter_mut() {
+        if !ship.hyperspace || ship.hyperspace_jumps == 0 || ship.respawn_delay > 0.0 {
+            continue;
+        }
+        ship.hyperspace = false;
+        ship.hyperspace_jumps -= 1;
+
+        // Try a few random spots and keep the one furthest from any asteroid
+        let mut rng = thread_rng();
+        let half_width = GAME_WIDTH as f32 / 2.0;
+        let half_height = GAME_HEIGHT as f32 / 2.0;
+        let clearance = |position: Vec2| {
+            asteroids_query
+                .iter()
+                .map(|asteroid| asteroid.translation.truncate().distance(position))
+                .fold(f32::MAX, f32::min)
+        };
+        let origin = transform.translation.truncate();
+        let destination = (0..HYPERSPACE_ATTEMPTS)
+            .map(|_| {
+                Vec2::new(
+                    rng.gen_range(-half_width..half_width),
+                    rng.gen_range(-half_height..half_height),
+                )
+            })
+            .max_by(|a, b| clearance(*a).total_cmp(&clearance(*b)))
+            .unwrap_or(origin);
+
+        commands.spawn(RingParticleBundle::new(origin, &sprite_sheets.particles));
+        commands.spawn(WaveParticleBundle::new(
+            destination,
+            &sprite_sheets.particles,
+        ));
+        transform.translation = destination.extend(transform.translation.z);
+        moving.velocity = Vec2::ZERO;
+
+        if rng.gen::<f32>() < HYPERSPACE_MALFUNCTION_CHANCE {
+            commands.spawn(GameNotificationBundle::new(
+                localization.get("Hyperspace malfunction!").to_owned(),
+                game_assets.font.clone(),
+                destination,
+                20.0,
+                1.0,
+            ));
+            damage_events.send(DamageEvent {
+                target: ship_entity,
+                source: None,
+                amount: HYPERSPACE_MALFUNCTION_DAMAGE,
+                cause: DamageCause::Malfunction,
+            });
+        }
+    }
+}
+
+fn ship_sprite(
+    mut ship_query: Query<(&Ship, &mut TextureAtlasSprite)>,
+    sprite_sheets: Res<SpriteSheets>,
+    profile: Res<Profile>,
+) {
+    for (ship, mut sprite) in ship_query.iter_mut() {
+        sprite.index = sprite_sheets.ship.choose(&ship);
+        let alpha = if ship.invulnerability > 0.0 { 0.5 } else { 1.0 };
+        sprite.color = profile.ship_color.color().with_a(alpha);
+    }
+}
+
+fn shield_sprite(
+    mut shield_query: Query<(&Parent, &mut Visibility, &mut TextureAtlasSprite), With<ShipShield>>,
+    ship_query: Query<&ShieldEnergy, With<Ship>>,
+) {
+    for (parent, mut visibility, mut sprite) in shield_query.iter_mut() {
+        let shield = ship_query
+            .get(parent.get())
+            .expect("ShipShield should have a Ship parent");
+        if shield.is_up() {
+            *visibility = Visibility::Visible;
+            sprite.color.set_a(lerp(0.25, 1.0, shield.fraction()));
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+fn shield_regen_system(
+    mut ship_query: Query<(&Ship, &mut ShieldEnergy)>,
+    time: Res<Time>,
+    modifiers: Res<Modifiers>,
+) {
+    for (ship, mut shield) in ship_query.iter_mut() {
+        // Shield upgrades still raise the maximum, but it never charges
+        if modifiers.contains(Modifier::NoShields) {
+            shield.current = 0.0;
+        } else if ship.respawn_delay <= 0.0 {
+            shield.regenerate(time.delta_seconds());
+        }
+    }
+}
+
+fn ship_asteroid_collision_system(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ships_query: Query<(&mut Ship, &Transform, &mut Moving, &Mass)>,
+    mut asteroids_query: Query<(&Asteroid, &Transform, &mut Moving, &Mass), Without<Ship>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for event in collision_events.iter() {
+        let Ok((asteroid, asteroid_transform, mut asteroid_moving, asteroid_mass)) =
+            asteroids_query.get_mut(event.b)
+        else {
+            continue;
+        };
+        let Ok((mut ship, ship_transform, mut ship_moving, ship_mass)) =
+            ships_query.get_mut(event.a)
+        else {
+            continue;
+        };
+        if ship.invulnerability > 0.0 {
+            continue;
+        }
+        let ship_position = ship_transform.translation.truncate();
+        let asteroid_position = asteroid_transform.translation.truncate();
+        let diff = ship_position - asteroid_position;
+        let epsilon = (ship_moving.velocity - asteroid_moving.velocity) * 0.01;
+        if diff.length_squared() < (diff + epsilon).length_squared() {
+            continue;
+        }
+        let relative_speed = (ship_moving.velocity - asteroid_moving.velocity).length();
+        let damage =
+            (relative_speed * asteroid.size.collision_damage_factor() * COLLISION_DAMAGE_SCALE)
+                .max(COLLISION_MIN_DAMAGE);
+        ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+        damage_events.send(DamageEvent {
+            target: event.a,
+            source: Some(event.b),
+            amount: damage,
+            cause: DamageCause::Collision,
+        });
+        let impulse = collision_impulse(
+            event.normal,
+            ship_moving.velocity - asteroid_moving.velocity,
+            ship_mass.0,
+            asteroid_mass.0,
+            COLLISION_RESTITUTION,
+        );
+        ship_moving.velocity += impulse / ship_mass.0;
+        asteroid_moving.velocity -= impulse / asteroid_mass.0;
+    }
+}
+
+fn ship_debris_collision_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    ships_query: Query<&Ship>,
+    debris_query: Query<(), With<Debris>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for event in collision_events.iter() {
+        if debris_query.get(event.b).is_err() {
+            continue;
+        }
+        let Ok(ship) = ships_query.get(event.a) else {
+            continue;
+        };
+        if ship.invulnerability > 0.0 {
+            continue;
+        }
+        commands.entity(event.b).despawn();
+        // Too small to make the ship invulnerable for a while
+        damage_events.send(DamageEvent {
+            target: event.a,
+            source: Some(event.b),
+            amount: DEBRIS_DAMAGE,
+            cause: DamageCause::Collision,
+        });
+    }
+}
+
+fn ship_death_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mut ships_query: Query<(&mut Ship, &mut ShieldEnergy, &Transform)>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for event in died_events.iter() {
+        let Ok((mut ship, mut shield, transform)) = ships_query.get_mut(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        ship.die();
+        shield.deplete();
+        commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        commands.spawn(WaveParticleBundle::new(position, &sprite_sheets.particles));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::utils::{Duration, Instant};
+
+    /// Steps the respawn system once, `delta` seconds after the previous frame
+    fn run_respawn(app: &mut App, delta: f32) {
+        let mut time = app.world.resource_mut::<Time>();
+        let last = time.last_update().unwrap_or_else(Instant::now);
+        time.update_with_instant(last);
+        time.update_with_instant(last + Duration::from_secs_f32(delta));
+        app.update();
+    }
+
+    fn respawn_app(ship: Ship) -> (App, Entity) {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<Settings>()
+            .init_resource::<Modifiers>()
+            .add_systems(Update, ship_respawn_system);
+        let mut health = Health::new(100.0);
+        health.damage(100.0);
+        let entity = app
+            .world
+            .spawn((
+                ship,
+                health,
+                ShieldEnergy::default(),
+                Transform::from_xyz(50.0, -20.0, 0.0),
+                Moving {
+                    velocity: Vec2::new(30.0, 10.0),
+                    ..Default::default()
+                },
+                Visibility::Hidden,
+            ))
+            .id();
+        (app, entity)
+    }
+
+    #[test]
+    fn ship_respawns_at_origin_after_delay() {
+        let (mut app, entity) = respawn_app(Ship {
+            lives: 2,
+            respawn_delay: 0.5,
+            ..Default::default()
+        });
+
+        run_respawn(&mut app, 0.25);
+        assert_eq!(
+            app.world.get::<Visibility>(entity),
+            Some(&Visibility::Hidden)
+        );
+
+        run_respawn(&mut app, 0.5);
+        let entity = app.world.entity(entity);
+        assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Visible));
+        assert_eq!(entity.get::<Transform>().unwrap().translation, Vec3::ZERO);
+        assert_eq!(entity.get::<Moving>().unwrap().velocity, Vec2::ZERO);
+        let health = entity.get::<Health>().unwrap();
+        assert_eq!(health.current, health.max);
+        assert_eq!(
+            entity.get::<Ship>().unwrap().invulnerability,
+            SHIP_INVULNERABILITY
+        );
+    }
+
+    #[test]
+    fn ship_without_lives_stays_hidden() {
+        let (mut app, entity) = respawn_app(Ship {
+            lives: 0,
+            respawn_delay: 0.5,
+            ..Default::default()
+        });
+
+        run_respawn(&mut app, 1.0);
+        assert_eq!(
+            app.world.get::<Visibility>(entity),
+            Some(&Visibility::Hidden)
+        );
+        assert_eq!(
+            app.world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(50.0, -20.0, 0.0)
+        );
+    }
+}
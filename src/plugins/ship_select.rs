@@ -0,0 +1,245 @@
+use crate::input::InputState;
+use crate::plugins::profile::{ProjectilePalette, ShipColor, Unlock};
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet, Profile};
+use crate::resources::{GameAssets, SpriteSheets};
+use crate::AppState;
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct ShipSelectEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ShipSelectItem {
+    Color,
+    Palette,
+    Launch,
+}
+
+const ITEMS: [ShipSelectItem; 3] = [
+    ShipSelectItem::Color,
+    ShipSelectItem::Palette,
+    ShipSelectItem::Launch,
+];
+
+#[derive(Component)]
+struct ShipSelectItemText {
+    index: usize,
+    item: ShipSelectItem,
+}
+
+#[derive(Component)]
+struct ShipPreview;
+
+#[derive(Component)]
+struct ProjectilePreview;
+
+pub struct ShipSelectPlugin;
+impl Plugin for ShipSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::ShipSelect), init_ship_select)
+            .add_systems(
+                OnExit(AppState::ShipSelect),
+                crate::despawn_tagged::<ShipSelectEntity>,
+            )
+            .add_systems(
+                Update,
+                (
+                    ship_select_input,
+                    ship_select_text_system.after(ship_select_input),
+                    ship_select_preview_system.after(ship_select_input),
+                )
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::ShipSelect)),
+            );
+    }
+}
+
+fn init_ship_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    sprite_sheets: Res<SpriteSheets>,
+    profile: Res<Profile>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn(MenuNavigation::new(ITEMS.len()).with_selected(ITEMS.len() - 1))
+        .insert(ShipSelectEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(ShipSelectEntity);
+    commands
+        .spawn(SpriteSheetBundle {
+            texture_atlas: sprite_sheets.ship.atlas.clone(),
+            transform: Transform::from_xyz(-200.0, 0.0, 0.1).with_scale(Vec3::splat(3.0)),
+            sprite: TextureAtlasSprite {
+                index: sprite_sheets.ship.rapid,
+                color: profile.ship_color.color(),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ShipPreview)
+        .insert(ShipSelectEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: game_assets.laser.clone(),
+            transform: Transform::from_xyz(-200.0, 100.0, 0.1).with_scale(Vec3::splat(2.0)),
+            sprite: Sprite {
+                color: profile.projectile_palette.color(),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ProjectilePreview)
+        .insert(ShipSelectEntity);
+
+    let font = game_assets.font.clone();
+    for (index, item) in ITEMS.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::CenterLeft,
+                transform: Transform::from_xyz(-50.0, 60.0 - index as f32 * 50.0, 0.1),
+                ..default()
+            })
+            .insert(ShipSelectItemText { index, item })
+            .insert(MenuEntry(index))
+            .insert(ShipSelectEntity);
+    }
+
+    let locked: Vec<_> = Unlock::ALL
+        .into_iter()
+        .filter(|unlock| !profile.is_unlocked(*unlock))
+        .map(|unlock| {
+            format!(
+                "{}: {}",
+                localization.get(unlock.name()),
+                localization.get(unlock.description())
+            )
+        })
+        .collect();
+    if !locked.is_empty() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    format!("{}\n{}", localization.get("Locked"), locked.join("\n")),
+                    TextStyle {
+                        font,
+                        font_size: 16.0,
+                        color: Color::GRAY,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, -170.0, 0.1),
+                ..default()
+            })
+            .insert(ShipSelectEntity);
+    }
+}
+
+fn next_available<T: Copy + PartialEq>(all: &[T], current: T, available: impl Fn(T) -> bool) -> T {
+    all.iter()
+        .copied()
+        .cycle()
+        .skip_while(|item| *item != current)
+        .skip(1)
+        .take(all.len())
+        .find(|item| available(*item))
+        .unwrap_or(current)
+}
+
+fn ship_select_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.back {
+        next_state.set(AppState::ModeSelect);
+    } else if input.ok {
+        match ITEMS[menu.selected] {
+            ShipSelectItem::Color => {
+                profile.ship_color = next_available(&ShipColor::ALL, profile.ship_color, |color| {
+                    profile.color_available(color)
+                });
+            }
+            ShipSelectItem::Palette => {
+                profile.projectile_palette = next_available(
+                    &ProjectilePalette::ALL,
+                    profile.projectile_palette,
+                    |palette| profile.palette_available(palette),
+                );
+            }
+            ShipSelectItem::Launch => {
+                if let Err(err) = profile.save() {
+                    error!("Could not save profile: {err}");
+                }
+                next_state.set(AppState::NewGame);
+            }
+        }
+    }
+}
+
+fn ship_select_text_system(
+    menu_query: Query<&MenuNavigation>,
+    profile: Res<Profile>,
+    localization: Res<Localization>,
+    mut items_query: Query<(&ShipSelectItemText, &mut Text)>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for (item_text, mut text) in items_query.iter_mut() {
+        let label = match item_text.item {
+            ShipSelectItem::Color => localization.format(
+                "Ship color: {color}",
+                &[("color", &localization.get(profile.ship_color.name()))],
+            ),
+            ShipSelectItem::Palette => localization.format(
+                "Weapons: {palette}",
+                &[(
+                    "palette",
+                    &localization.get(profile.projectile_palette.name()),
+                )],
+            ),
+            ShipSelectItem::Launch => localization.get("Launch").to_string(),
+        };
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label
+        };
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn ship_select_preview_system(
+    profile: Res<Profile>,
+    mut ship_preview_query: Query<&mut TextureAtlasSprite, With<ShipPreview>>,
+    mut projectile_preview_query: Query<&mut Sprite, With<ProjectilePreview>>,
+) {
+    for mut sprite in ship_preview_query.iter_mut() {
+        sprite.color = profile.ship_color.color();
+    }
+    for mut sprite in projectile_preview_query.iter_mut() {
+        sprite.color = profile.projectile_palette.color();
+    }
+}
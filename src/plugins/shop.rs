@@ -0,0 +1,219 @@
+use crate::{
+    components::*,
+    constants::*,
+    input::InputState,
+    plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet},
+    resources::*,
+    AppState,
+};
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct ShopEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ShopItem {
+    Upgrade(WeaponUpgrade),
+    Continue,
+}
+
+#[derive(Component)]
+struct ShopItemText {
+    index: usize,
+    prefix: &'static str,
+    item: ShopItem,
+}
+
+#[derive(Component)]
+struct ShopMineralsText;
+
+pub struct ShopPlugin;
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Shop), init_shop)
+            .add_systems(OnExit(AppState::Shop), crate::despawn_tagged::<ShopEntity>)
+            .add_systems(
+                Update,
+                (shop_input, shop_text_system.after(shop_input))
+                    .after(MenuNavigationSet)
+                    .run_if(in_state(AppState::Shop)),
+            );
+    }
+}
+
+fn shop_items() -> Vec<ShopItem> {
+    WeaponUpgrade::ALL
+        .into_iter()
+        .map(ShopItem::Upgrade)
+        .chain(std::iter::once(ShopItem::Continue))
+        .collect()
+}
+
+fn shop_text(text: &str, font: Handle<Font>, position: Vec2, size: f32) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(
+            text,
+            TextStyle {
+                font,
+                font_size: size,
+                color: Color::WHITE,
+            },
+        ),
+        text_anchor: Anchor::CenterLeft,
+        transform: Transform::from_translation(position.extend(0.6)),
+        ..default()
+    }
+}
+
+fn init_shop(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn(MenuNavigation::new(shop_items().len()))
+        .insert(ShopEntity);
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            transform: Transform::from_xyz(0.0, 0.0, 0.5),
+            ..default()
+        })
+        .insert(ShopEntity);
+
+    let font = game_assets.font.clone();
+    let left = -200.0;
+    commands
+        .spawn(shop_text(
+            localization.get("Upgrades"),
+            font.clone(),
+            Vec2::new(left, 210.0),
+            32.0,
+        ))
+        .insert(ShopEntity);
+    commands
+        .spawn(shop_text(
+            "",
+            font.clone(),
+            Vec2::new(left + 250.0, 210.0),
+            20.0,
+        ))
+        .insert(ShopMineralsText)
+        .insert(ShopEntity);
+
+    let mut y = 170.0;
+    let mut previous_weapon = None;
+    let items = shop_items();
+    for (index, item) in items.iter().enumerate() {
+        let prefix = match item {
+            ShopItem::Upgrade(upgrade) => {
+                let weapon = upgrade.weapon();
+                if previous_weapon != Some(weapon) {
+                    commands
+                        .spawn(shop_text(
+                            localization.get(weapon.name()),
+                            font.clone(),
+                            Vec2::new(left, y),
+                            20.0,
+                        ))
+                        .insert(ShopEntity);
+                    y -= 24.0;
+                    previous_weapon = Some(weapon);
+                }
+                let last_branch = items.get(index + 1).map_or(true, |next| match next {
+                    ShopItem::Upgrade(next) => next.weapon() != weapon,
+                    ShopItem::Continue => true,
+                });
+                if last_branch {
+                    "  └ "
+                } else {
+                    "  ├ "
+                }
+            }
+            ShopItem::Continue => {
+                y -= 12.0;
+                ""
+            }
+        };
+        commands
+            .spawn(shop_text("", font.clone(), Vec2::new(left, y), 20.0))
+            .insert(ShopItemText {
+                index,
+                prefix,
+                item: *item,
+            })
+            .insert(MenuEntry(index))
+            .insert(ShopEntity);
+        y -= 24.0;
+    }
+}
+
+fn shop_input(
+    input: Res<InputState>,
+    menu_query: Query<&MenuNavigation>,
+    mut ships_query: Query<&mut Ship>,
+    mut minerals: ResMut<Minerals>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let items = shop_items();
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.ok {
+        match items[menu.selected] {
+            ShopItem::Upgrade(upgrade) => {
+                let mut ship = ships_query.single_mut();
+                if ship.can_upgrade(upgrade) && minerals.spend(UPGRADE_COST) {
+                    ship.upgrades.push(upgrade);
+                }
+            }
+            ShopItem::Continue => next_state.set(AppState::LoadLevel),
+        }
+    }
+}
+
+fn shop_text_system(
+    menu_query: Query<&MenuNavigation>,
+    minerals: Res<Minerals>,
+    ships_query: Query<&Ship>,
+    localization: Res<Localization>,
+    mut items_query: Query<(&ShopItemText, &mut Text)>,
+    mut minerals_query: Query<&mut Text, (With<ShopMineralsText>, Without<ShopItemText>)>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    let ship = ships_query.single();
+    for mut text in minerals_query.iter_mut() {
+        text.sections[0].value =
+            localization.format("Minerals: {minerals}", &[("minerals", &minerals.value())]);
+    }
+    for (item_text, mut text) in items_query.iter_mut() {
+        let (label, color) = match item_text.item {
+            ShopItem::Upgrade(upgrade) => {
+                let cost = || localization.format("{cost} minerals", &[("cost", &UPGRADE_COST)]);
+                let (status, color) = if ship.has_upgrade(upgrade) {
+                    (localization.get("owned").to_string(), Color::GREEN)
+                } else if ship.has_upgrade(upgrade.sibling()) {
+                    (localization.get("locked").to_string(), Color::GRAY)
+                } else if ship.weapon_level(upgrade.weapon()) == 0 {
+                    (localization.get("weapon required").to_string(), Color::GRAY)
+                } else if minerals.value() < UPGRADE_COST {
+                    (cost(), Color::GRAY)
+                } else {
+                    (cost(), Color::WHITE)
+                };
+                (
+                    format!("{} - {}", localization.get(upgrade.name()), status),
+                    color,
+                )
+            }
+            ShopItem::Continue => (localization.get("Continue").to_string(), Color::WHITE),
+        };
+        let selected = menu.is_selected(item_text.index);
+        let marker = if selected { "> " } else { "" };
+        text.sections[0].value = format!("{}{}{}", item_text.prefix, marker, label);
+        text.sections[0].style.color = if selected { Color::YELLOW } else { color };
+    }
+}
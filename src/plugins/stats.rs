@@ -0,0 +1,105 @@
+use crate::input::InputState;
+use crate::plugins::profile::Profile;
+use crate::plugins::Localization;
+use crate::resources::GameAssets;
+use crate::AppState;
+use bevy::{prelude::*, sprite::Anchor};
+
+#[derive(Component)]
+struct StatsEntity;
+
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Stats), init_stats)
+            .add_systems(
+                OnExit(AppState::Stats),
+                crate::despawn_tagged::<StatsEntity>,
+            )
+            .add_systems(Update, stats_input.run_if(in_state(AppState::Stats)));
+    }
+}
+
+fn init_stats(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    profile: Res<Profile>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("img/title-background.png"),
+            ..default()
+        })
+        .insert(StatsEntity);
+
+    let play_time = profile.play_time as u32;
+    let shots = profile.lifetime_shots();
+    let favorite_weapon = profile
+        .favorite_weapon()
+        .map_or("-", |weapon| localization.get(weapon.name()));
+    let lines = [
+        localization.get("Lifetime statistics").to_string(),
+        localization.format("Runs played: {runs}", &[("runs", &profile.runs_played)]),
+        localization.format(
+            "Asteroids destroyed: {asteroids}",
+            &[("asteroids", &profile.asteroids_destroyed)],
+        ),
+        localization.format(
+            "Total play time: {time}",
+            &[(
+                "time",
+                &format!(
+                    "{}:{:02}:{:02}",
+                    play_time / 3600,
+                    play_time / 60 % 60,
+                    play_time % 60
+                ),
+            )],
+        ),
+        localization.format(
+            "Highest level: {level}",
+            &[("level", &profile.highest_level)],
+        ),
+        localization.format("Favorite weapon: {weapon}", &[("weapon", &favorite_weapon)]),
+        localization.format(
+            "Accuracy: {accuracy}% ({hit}/{fired})",
+            &[
+                ("accuracy", &format!("{:.0}", shots.accuracy() * 100.0)),
+                ("hit", &shots.hit),
+                ("fired", &shots.fired),
+            ],
+        ),
+    ];
+
+    let font = game_assets.font.clone();
+    let left = -250.0;
+    for (i, line) in lines.into_iter().enumerate() {
+        let (y, size) = match i {
+            0 => (180.0, 32.0),
+            _ => (140.0 - i as f32 * 32.0, 20.0),
+        };
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    line,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: size,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::CenterLeft,
+                transform: Transform::from_xyz(left, y, 0.1),
+                ..default()
+            })
+            .insert(StatsEntity);
+    }
+}
+
+fn stats_input(input: Res<InputState>, mut next_state: ResMut<NextState<AppState>>) {
+    if input.ok || input.back {
+        next_state.set(AppState::Title);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    plugins::{spawn_ufo, Localization, Ufo, UfoKind},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use rand::{random, thread_rng, Rng};
+
+/// Phases of a survival run, running alongside `AppState::InGame`
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum SurvivalState {
+    #[default]
+    Inactive,
+    Wave,
+    Intermission,
+}
+
+/// Number of the current or last started wave
+#[derive(Default, Resource)]
+pub struct SurvivalWave(pub u32);
+
+#[derive(Resource)]
+struct IntermissionTimer(Timer);
+
+pub struct SurvivalPlugin;
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SurvivalState>()
+            .init_resource::<SurvivalWave>()
+            .add_systems(OnEnter(AppState::NewGame), reset_survival_wave)
+            .add_systems(
+                OnEnter(AppState::InGame),
+                start_survival.run_if(resource_equals(GameMode::Survival)),
+            )
+            .add_systems(OnExit(AppState::InGame), stop_survival)
+            .add_systems(OnEnter(SurvivalState::Wave), spawn_wave)
+            .add_systems(OnEnter(SurvivalState::Intermission), start_intermission)
+            .add_systems(
+                Update,
+                wave_cleared_system
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(SurvivalState::Wave)),
+            )
+            .add_systems(
+                Update,
+                intermission_system
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(SurvivalState::Intermission)),
+            );
+    }
+}
+
+fn reset_survival_wave(mut wave: ResMut<SurvivalWave>) {
+    *wave = SurvivalWave::default();
+}
+
+fn start_survival(
+    state: Res<State<SurvivalState>>,
+    mut next_state: ResMut<NextState<SurvivalState>>,
+) {
+    if *state.get() == SurvivalState::Inactive {
+        next_state.set(SurvivalState::Wave);
+    }
+}
+
+fn stop_survival(mut next_state: ResMut<NextState<SurvivalState>>) {
+    next_state.set(SurvivalState::Inactive);
+}
+
+fn spawn_wave(
+    mut commands: Commands,
+    mut wave: ResMut<SurvivalWave>,
+    mut level: ResMut<Level>,
+    modifiers: Res<Modifiers>,
+    score: Res<Score>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    wave.0 += 1;
+    // Waves escalate like levels do, only faster
+    *level = Level((wave.0 - 1) * 2);
+
+    let mut rng = thread_rng();
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    for size in level.asteroids() {
+        let d = random::<f32>() * span * 2.0 - span;
+        let position = match rng.gen_range(0..4) {
+            0 => Vec2::new(d.x, span.y),
+            1 => Vec2::new(span.x, d.y),
+            2 => Vec2::new(d.x, -span.y),
+            _ => Vec2::new(-span.x, d.y),
+        };
+        let heading = random::<f32>() * std::f32::consts::TAU;
+        let speed = rng.gen_range(level.asteroid_speed_bounds()) * modifiers.asteroid_speed();
+        commands.spawn(AsteroidBundle::new(
+            sprite_sheets.as_ref(),
+            level.asteroid_variant(),
+            size,
+            position,
+            Vec2::from_angle(heading) * speed,
+            random::<f32>() - 0.5,
+        ));
+    }
+    for _ in 0..(wave.0 / 2).min(SURVIVAL_MAX_UFOS) {
+        spawn_ufo(
+            &mut commands,
+            &sprite_sheets,
+            &level,
+            UfoKind::for_score(score.value()),
+            1.0,
+        );
+    }
+
+    commands.spawn(GameNotificationBundle::new(
+        localization.format("Wave {wave}", &[("wave", &wave.0)]),
+        game_assets.font.clone(),
+        Vec2::new(0.0, 50.0),
+        40.0,
+        2.0,
+    ));
+}
+
+fn wave_cleared_system(
+    asteroids_query: Query<(), With<Asteroid>>,
+    ufos_query: Query<(), With<Ufo>>,
+    mut next_state: ResMut<NextState<SurvivalState>>,
+) {
+    if asteroids_query.is_empty() && ufos_query.is_empty() {
+        next_state.set(SurvivalState::Intermission);
+    }
+}
+
+fn start_intermission(
+    mut commands: Commands,
+    wave: Res<SurvivalWave>,
+    mut run_stats: ResMut<RunStats>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
+    run_stats.levels_cleared += 1;
+    commands.insert_resource(IntermissionTimer(Timer::from_seconds(
+        SURVIVAL_INTERMISSION,
+        TimerMode::Once,
+    )));
+    commands.spawn(GameNotificationBundle::new(
+        localization.format("Wave {wave} cleared!", &[("wave", &wave.0)]),
+        game_assets.font.clone(),
+        Vec2::new(0.0, 50.0),
+        30.0,
+        SURVIVAL_INTERMISSION / 2.0,
+    ));
+}
+
+fn intermission_system(
+    mut timer: ResMut<IntermissionTimer>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<SurvivalState>>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        next_state.set(SurvivalState::Wave);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    bundles::GameNotificationBundle, components::*, constants::*, plugins::Died, resources::*,
+    utils::format_time, AppState,
+};
+use bevy::{prelude::*, sprite::Anchor};
+
+/// Time taken by the current time attack run, including death penalties
+#[derive(Default, Resource)]
+pub struct TimeAttackClock {
+    pub time: f32,
+}
+
+impl TimeAttackClock {
+    /// Centiseconds, the unit best times are stored in
+    pub fn hundredths(&self) -> u32 {
+        (self.time * 100.0) as u32
+    }
+}
+
+/// Whether the run cleared every time attack level
+pub fn time_attack_finished(run_stats: &RunStats) -> bool {
+    run_stats.levels_cleared >= TIME_ATTACK_LEVELS
+}
+
+#[derive(Component)]
+struct TimeAttackTimerText;
+
+pub struct TimeAttackPlugin;
+impl Plugin for TimeAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeAttackClock>()
+            .add_systems(OnEnter(AppState::NewGame), reset_time_attack_clock)
+            .add_systems(
+                Update,
+                (
+                    time_attack_clock_system,
+                    time_attack_penalty_system,
+                    time_attack_timer_text_system,
+                )
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_equals(GameMode::TimeAttack)),
+            );
+    }
+}
+
+fn reset_time_attack_clock(mut clock: ResMut<TimeAttackClock>) {
+    *clock = TimeAttackClock::default();
+}
+
+fn time_attack_clock_system(mut clock: ResMut<TimeAttackClock>, time: Res<Time>) {
+    clock.time += time.delta_seconds();
+}
+
+fn time_attack_penalty_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    ships_query: Query<&Transform, With<Ship>>,
+    mut clock: ResMut<TimeAttackClock>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = ships_query.get(event.entity) else {
+            continue;
+        };
+        clock.time += TIME_ATTACK_DEATH_PENALTY;
+        commands.spawn(GameNotificationBundle::new(
+            format!("+{}s", TIME_ATTACK_DEATH_PENALTY),
+            game_assets.font.clone(),
+            transform.translation.truncate(),
+            24.0,
+            1.5,
+        ));
+    }
+}
+
+fn time_attack_timer_text_system(
+    mut commands: Commands,
+    clock: Res<TimeAttackClock>,
+    mut text_query: Query<&mut Text, With<TimeAttackTimerText>>,
+    game_assets: Res<GameAssets>,
+) {
+    let value = format_time(clock.time);
+    if text_query.is_empty() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font: game_assets.font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                text_anchor: Anchor::BottomRight,
+                transform: Transform::from_xyz(
+                    GAME_WIDTH as f32 / 2.0 - 10.0,
+                    -(GAME_HEIGHT as f32) / 2.0 + 10.0,
+                    0.5,
+                ),
+                ..default()
+            })
+            .insert(TimeAttackTimerText)
+            .insert(LevelEntity);
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
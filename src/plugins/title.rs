@@ -1,5 +1,8 @@
+use crate::input::InputState;
+use crate::plugins::{Localization, MenuEntry, MenuNavigation, MenuNavigationSet};
+use crate::resources::GameAssets;
 use crate::AppState;
-use bevy::prelude::*;
+use bevy::{app::AppExit, prelude::*};
 
 #[derive(Component)]
 pub struct TitleEntity;
@@ -12,14 +15,42 @@ pub struct TitleText {
     duration: f32,
     elapsed: f32,
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TitleItem {
+    StartGame,
+    ModeSelect,
+    Options,
+    HighScores,
+    Credits,
+    Quit,
+}
+
+const ITEMS: [TitleItem; 6] = [
+    TitleItem::StartGame,
+    TitleItem::ModeSelect,
+    TitleItem::Options,
+    TitleItem::HighScores,
+    TitleItem::Credits,
+    TitleItem::Quit,
+];
+
+/// Seconds from entering the title until the menu appears, after the title has flown in
+const TITLE_MENU_AT: f32 = 2.8;
+
 #[derive(Component)]
-pub struct TitleStart {
-    at: f32,
-    blink: f32,
+pub struct TitleItemText {
+    index: usize,
+    item: TitleItem,
     elapsed: f32,
 }
 
-fn init_title(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn init_title(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    localization: Res<Localization>,
+) {
     let background = asset_server.load("img/title-background.png");
     commands
         .spawn(SpriteBundle {
@@ -73,24 +104,71 @@ fn init_title(mut commands: Commands, asset_server: Res<AssetServer>) {
             duration: 0.3,
             elapsed: 0.0,
         });
-    let start = asset_server.load("img/title-start.png");
     commands
-        .spawn(SpriteBundle {
-            texture: start,
-            transform: Transform::from_xyz(0.0, -200.0, 0.01),
-            visibility: Visibility::Hidden,
+        .spawn(MenuNavigation::new(ITEMS.len()))
+        .insert(TitleEntity);
+    let font = game_assets.font.clone();
+    for (index, item) in ITEMS.into_iter().enumerate() {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, -100.0 - index as f32 * 22.0, 0.01),
+                visibility: Visibility::Hidden,
+                ..default()
+            })
+            .insert(TitleItemText {
+                index,
+                item,
+                elapsed: 0.0,
+            })
+            .insert(MenuEntry(index))
+            .insert(TitleEntity);
+    }
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                localization.get("H - Hangar | S - Stats"),
+                TextStyle {
+                    font,
+                    font_size: 14.0,
+                    color: Color::GRAY,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -230.0, 0.01),
             ..default()
         })
-        .insert(TitleEntity)
-        .insert(TitleStart {
-            at: 2.8,
-            blink: 0.2,
-            elapsed: 0.0,
-        });
+        .insert(TitleEntity);
 }
-fn title_input(keyboard_input: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        next_state.set(AppState::NewGame)
+fn title_input(
+    input: Res<InputState>,
+    keyboard_input: Res<Input<KeyCode>>,
+    menu_query: Query<&MenuNavigation>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    if input.ok {
+        match ITEMS[menu.selected] {
+            TitleItem::StartGame => next_state.set(AppState::ShipSelect),
+            TitleItem::ModeSelect => next_state.set(AppState::ModeSelect),
+            TitleItem::Options => next_state.set(AppState::Options),
+            TitleItem::HighScores => next_state.set(AppState::HighScore),
+            TitleItem::Credits => next_state.set(AppState::Credits),
+            TitleItem::Quit => app_exit.send(AppExit),
+        }
+    } else if keyboard_input.just_pressed(KeyCode::H) {
+        next_state.set(AppState::Hangar)
+    } else if keyboard_input.just_pressed(KeyCode::S) {
+        next_state.set(AppState::Stats)
     }
 }
 fn title_text_system(
@@ -108,21 +186,41 @@ fn title_text_system(
         transform.translation = text.from.lerp(text.to, t).extend(0.01);
     }
 }
-fn title_start_system(
-    mut title_start_query: Query<(&mut TitleStart, &mut Visibility)>,
+fn title_menu_system(
+    menu_query: Query<&MenuNavigation>,
+    localization: Res<Localization>,
+    mut items_query: Query<(&mut TitleItemText, &mut Text, &mut Visibility)>,
     time: Res<Time>,
 ) {
-    for (mut start, mut visibility) in title_start_query.iter_mut() {
-        start.elapsed += time.delta_seconds();
-        *visibility = if (start.elapsed - start.at)
-            .max(0.0)
-            .rem_euclid(start.blink * 2.0)
-            >= start.blink
-        {
+    let Ok(menu) = menu_query.get_single() else {
+        return;
+    };
+    for (mut item_text, mut text, mut visibility) in items_query.iter_mut() {
+        item_text.elapsed += time.delta_seconds();
+        *visibility = if item_text.elapsed >= TITLE_MENU_AT {
             Visibility::Visible
         } else {
             Visibility::Hidden
         };
+        let label = localization.get(match item_text.item {
+            TitleItem::StartGame => "Start Game",
+            TitleItem::ModeSelect => "Mode Select",
+            TitleItem::Options => "Options",
+            TitleItem::HighScores => "High Scores",
+            TitleItem::Credits => "Credits",
+            TitleItem::Quit => "Quit",
+        });
+        let selected = menu.is_selected(item_text.index);
+        text.sections[0].value = if selected {
+            format!("> {label}")
+        } else {
+            label.to_string()
+        };
+        text.sections[0].style.color = if selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
     }
 }
 pub struct TitleScreenPlugin;
@@ -135,7 +233,8 @@ impl Plugin for TitleScreenPlugin {
             )
             .add_systems(
                 Update,
-                (title_input, title_text_system, title_start_system)
+                (title_input, title_text_system, title_menu_system)
+                    .after(MenuNavigationSet)
                     .run_if(in_state(AppState::Title)),
             );
     }
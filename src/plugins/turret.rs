@@ -0,0 +1,196 @@
+use crate::{
+    bundles::*,
+    components::*,
+    constants::*,
+    plugins::{Died, TurretPlacement, UfoLaserBundle},
+    resources::*,
+    AppState,
+};
+use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+pub struct TurretPlugin;
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                turret_aim_system,
+                turret_shoot_system.after(turret_aim_system),
+                turret_shield_system,
+                turret_destroy_system,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Satellite gun placed by the level, shielded until worn down
+#[derive(Component)]
+pub struct Turret {
+    angle: f32,
+    shoot_delay: f32,
+}
+
+#[derive(Component)]
+struct TurretShield;
+
+pub fn spawn_turret(
+    commands: &mut Commands,
+    sprite_sheets: &SpriteSheets,
+    placement: &TurretPlacement,
+) {
+    let transform = Transform::from_translation(Vec2::from(placement.position).extend(0.0));
+    commands
+        .spawn((
+            SpriteSheetBundle {
+                texture_atlas: sprite_sheets.ufo.atlas.clone(),
+                transform,
+                sprite: TextureAtlasSprite {
+                    index: sprite_sheets.ufo.ship[0],
+                    color: Color::rgb(0.6, 0.7, 0.8),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Turret {
+                angle: 0.0,
+                shoot_delay: TURRET_SHOOT_INTERVAL,
+            },
+            Enemy,
+            Health::new(TURRET_HEALTH),
+            ShieldEnergy {
+                regen_rate: TURRET_SHIELD_REGEN_RATE,
+                ..ShieldEnergy::full(placement.shield)
+            },
+            LevelEntity,
+            CollisionShape::new(
+                Shape::Circle {
+                    center: Vec2::ZERO,
+                    radius: TURRET_RADIUS,
+                },
+                transform,
+            ),
+        ))
+        .with_children(|turret| {
+            turret.spawn((
+                SpriteSheetBundle {
+                    texture_atlas: sprite_sheets.ship.atlas.clone(),
+                    sprite: TextureAtlasSprite::new(sprite_sheets.ship.shield),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.01).with_scale(Vec3::splat(1.5)),
+                    ..Default::default()
+                },
+                TurretShield,
+            ));
+        });
+}
+
+/// Turns the turret towards the ship at a limited rate
+fn turret_aim_system(
+    mut turrets_query: Query<(&mut Turret, &mut Transform)>,
+    ships_query: Query<&Transform, (With<Ship>, Without<Turret>)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let Ok(ship_transform) = ships_query.get_single() else {
+        return;
+    };
+    let delta = time.delta_seconds() * time_scale.0;
+    for (mut turret, mut transform) in turrets_query.iter_mut() {
+        let to_ship = (ship_transform.translation - transform.translation).truncate();
+        if to_ship == Vec2::ZERO {
+            continue;
+        }
+        let turn = (Vec2::Y.angle_between(to_ship) - turret.angle + PI).rem_euclid(TAU) - PI;
+        let max_turn = TURRET_TURN_RATE * delta;
+        turret.angle += turn.clamp(-max_turn, max_turn);
+        transform.rotation = Quat::from_rotation_z(turret.angle);
+    }
+}
+
+fn turret_shoot_system(
+    mut commands: Commands,
+    mut turrets_query: Query<(&mut Turret, &Transform)>,
+    ships_query: Query<(&Ship, &Transform), Without<Turret>>,
+    sprite_sheets: Res<SpriteSheets>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let target = ships_query
+        .get_single()
+        .ok()
+        .filter(|(ship, _)| ship.respawn_delay <= 0.0 && ship.lives > 0);
+    for (mut turret, transform) in turrets_query.iter_mut() {
+        turret.shoot_delay -= time.delta_seconds() * time_scale.0;
+        let Some((_, ship_transform)) = target else {
+            continue;
+        };
+        let heading = Vec2::from_angle(turret.angle).rotate(Vec2::Y);
+        let to_ship = (ship_transform.translation - transform.translation).truncate();
+        if turret.shoot_delay > 0.0 || heading.angle_between(to_ship).abs() > TURRET_FIRE_ANGLE {
+            continue;
+        }
+        turret.shoot_delay = TURRET_SHOOT_INTERVAL;
+        commands.spawn(UfoLaserBundle::new(
+            &sprite_sheets.ufo,
+            transform.translation.truncate() + heading * TURRET_RADIUS,
+            turret.angle,
+            heading * TURRET_SHOT_SPEED,
+            2.0,
+        ));
+    }
+}
+
+/// Regenerates turret shields and shows them while they have any energy left
+fn turret_shield_system(
+    mut turrets_query: Query<&mut ShieldEnergy, With<Turret>>,
+    mut shield_query: Query<
+        (&Parent, &mut Visibility, &mut TextureAtlasSprite),
+        With<TurretShield>,
+    >,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for mut shield in turrets_query.iter_mut() {
+        shield.regenerate(time.delta_seconds() * time_scale.0);
+    }
+    for (parent, mut visibility, mut sprite) in shield_query.iter_mut() {
+        let Ok(shield) = turrets_query.get(parent.get()) else {
+            continue;
+        };
+        *visibility = if shield.is_up() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        sprite.color.set_a(0.3 + 0.7 * shield.fraction());
+    }
+}
+
+fn turret_destroy_system(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    turrets_query: Query<&Transform, With<Turret>>,
+    mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    sprite_sheets: Res<SpriteSheets>,
+    game_assets: Res<GameAssets>,
+) {
+    for event in died_events.iter() {
+        let Ok(transform) = turrets_query.get(event.entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
+        commands.spawn(WaveParticleBundle::new(position, &sprite_sheets.particles));
+        let score_delta = TURRET_SCORE * score_multiplier.0;
+        score.increase(score_delta);
+        commands.spawn(ScorePopupBundle::new(
+            score_delta,
+            game_assets.font.clone(),
+            position,
+            20.0,
+        ));
+        commands.entity(event.entity).despawn_recursive();
+    }
+}
@@ -1,4 +1,14 @@
-use crate::{bundles::*, components::*, constants::*, lerp, resources::*, AppState};
+use crate::{
+    bundles::*,
+    collision::CollisionEvent,
+    components::*,
+    constants::*,
+    lerp,
+    plugins::{ActiveLevelDefinition, DamageCause, DamageEvent, Died},
+    resources::*,
+    utils::collision_impulse,
+    AppState, GameSet,
+};
 use bevy::prelude::*;
 use rand::random;
 
@@ -6,41 +16,148 @@ pub struct UfoPlugin;
 impl Plugin for UfoPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(NextUfoScore::new())
+            .init_resource::<ScheduledUfos>()
             .add_systems(
                 Update,
                 (
                     ufo_spawn_system,
+                    ufo_evasion_system.before(ufo_movement_system),
                     ufo_movement_system,
                     ufo_animation_system,
+                    ufo_cloak_system,
                     ufo_shoot_system,
-                    ship_projectile_ufo_hit_system,
-                    ship_ufo_collision_system,
-                    ship_ufo_laser_collision_system,
+                    ship_projectile_ufo_hit_system.in_set(GameSet::Resolution),
+                    ship_ufo_collision_system.in_set(GameSet::Resolution),
+                    ship_ufo_laser_collision_system.in_set(GameSet::Resolution),
                     ufo_destroy_system,
                 )
                     .run_if(in_state(AppState::InGame)),
             )
-            .add_systems(OnEnter(AppState::NewGame), reset_next_ufo_score);
+            .add_systems(OnEnter(AppState::NewGame), reset_next_ufo_score)
+            .add_systems(OnEnter(AppState::LoadLevel), reset_scheduled_ufos);
     }
 }
+/// Like in the original Asteroids, small UFOs are faster, better shots and worth more.
+/// Stealth UFOs keep cloaking, giving themselves away only by shooting.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UfoKind {
+    Large,
+    Small,
+    Stealth,
+}
+
+impl UfoKind {
+    /// Small UFOs start turning up at `SMALL_UFO_MIN_SCORE` and get more common from there
+    pub fn for_score(score: u32) -> Self {
+        if score >= STEALTH_UFO_MIN_SCORE && random::<f32>() < STEALTH_UFO_CHANCE {
+            return UfoKind::Stealth;
+        }
+        if score < SMALL_UFO_MIN_SCORE {
+            return UfoKind::Large;
+        }
+        let t = (score - SMALL_UFO_MIN_SCORE) as f32
+            / (SMALL_UFO_MAX_CHANCE_SCORE - SMALL_UFO_MIN_SCORE) as f32;
+        if random::<f32>() < lerp(0.3, 0.8, t) {
+            UfoKind::Small
+        } else {
+            UfoKind::Large
+        }
+    }
+    pub fn score(&self) -> u32 {
+        match self {
+            UfoKind::Large => 100,
+            UfoKind::Small => 500,
+            UfoKind::Stealth => 300,
+        }
+    }
+    pub fn scale(&self) -> f32 {
+        match self {
+            UfoKind::Large => 1.0,
+            UfoKind::Small => 0.6,
+            UfoKind::Stealth => 0.8,
+        }
+    }
+    pub fn color(&self) -> Color {
+        match self {
+            UfoKind::Large => Color::WHITE,
+            UfoKind::Small => Color::rgb(1.0, 0.6, 0.6),
+            UfoKind::Stealth => Color::rgb(0.6, 1.0, 0.8),
+        }
+    }
+    /// Multiplier on the time it takes to cross the screen
+    pub fn duration(&self) -> f32 {
+        match self {
+            UfoKind::Large | UfoKind::Stealth => 1.0,
+            UfoKind::Small => 0.7,
+        }
+    }
+    pub fn shoot_accuracy(&self, level_accuracy: f32) -> f32 {
+        match self {
+            UfoKind::Large | UfoKind::Stealth => level_accuracy,
+            UfoKind::Small => lerp(level_accuracy, 1.0, 0.7),
+        }
+    }
+    pub fn health(&self) -> f32 {
+        match self {
+            UfoKind::Large => UFO_HEALTH,
+            UfoKind::Small | UfoKind::Stealth => UFO_HEALTH / 2.0,
+        }
+    }
+}
+
 #[derive(Component)]
-struct Ufo {
+pub struct Ufo {
+    pub kind: UfoKind,
     pub start_position: Vec2,
     pub end_position: Vec2,
     pub frequency: f32,
     pub amplitude: f32,
+    /// Offset into the sine path, in cycles, so that squadrons weave in turn
+    pub phase: f32,
     pub duration: f32,
     pub time: f32,
     pub shoot_delay: f32,
     pub shoot_accuracy: f32,
-    pub life: i32,
+    /// Chance to dodge a shot on a collision course
+    pub evasion_skill: f32,
+    /// Sideways offset from the path built up by dodging
+    pub dodge: f32,
+    pub dodge_velocity: f32,
+    pub dodge_time: f32,
+    /// Time until the UFO reacts to shots again
+    pub evasion_cooldown: f32,
 }
 #[derive(Component)]
 struct UfoLaser;
 
+/// Fades a stealth UFO in and out of sight
+#[derive(Component, Default)]
+struct Cloak {
+    time: f32,
+    reveal: f32,
+}
+
+impl Cloak {
+    /// How visible the UFO is, from `CLOAK_MIN_ALPHA` to 1
+    fn alpha(&self) -> f32 {
+        let t = self.time % (CLOAK_VISIBLE_TIME + CLOAK_HIDDEN_TIME);
+        let fade = if t < CLOAK_VISIBLE_TIME {
+            1.0 - (t - (CLOAK_VISIBLE_TIME - CLOAK_FADE_TIME)) / CLOAK_FADE_TIME
+        } else {
+            (t - (CLOAK_VISIBLE_TIME + CLOAK_HIDDEN_TIME - CLOAK_FADE_TIME)) / CLOAK_FADE_TIME
+        };
+        let reveal = self.reveal / CLOAK_REVEAL_TIME;
+        lerp(CLOAK_MIN_ALPHA, 1.0, fade.max(reveal).clamp(0.0, 1.0))
+    }
+}
+
 #[derive(Default, Resource)]
 struct NextUfoScore(pub u32);
 
+/// Number of UFOs spawned so far from the level's UFO schedule
+#[derive(Default, Resource)]
+struct ScheduledUfos(usize);
+
 impl NextUfoScore {
     pub fn new() -> Self {
         Self(random_ufo_interval())
@@ -65,37 +182,118 @@ fn random_ufo_interval() -> u32 {
 fn reset_next_ufo_score(mut next_ufo_score: ResMut<NextUfoScore>) {
     *next_ufo_score = NextUfoScore::new();
 }
+fn reset_scheduled_ufos(mut scheduled_ufos: ResMut<ScheduledUfos>) {
+    *scheduled_ufos = ScheduledUfos::default();
+}
 fn ufo_spawn_system(
     mut commands: Commands,
     mut next_ufo_score: ResMut<NextUfoScore>,
+    mut scheduled_ufos: ResMut<ScheduledUfos>,
     level: Res<Level>,
     score: Res<Score>,
+    run_stats: Res<RunStats>,
+    level_start_stats: Res<LevelStartStats>,
+    active_level_definition: Res<ActiveLevelDefinition>,
+    mode: Res<GameMode>,
     sprite_sheets: Res<SpriteSheets>,
 ) {
-    if next_ufo_score.bump(score.value()) {
-        let horizontal: bool = random();
-        let direction: bool = random();
-        let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
-        let d = random::<f32>() * span * 2.0;
-        let position = match (horizontal, direction) {
-            (false, false) => Vec2::new(d.x, span.y),
-            (true, false) => Vec2::new(span.x, d.y),
-            (false, true) => Vec2::new(d.x, -span.y),
-            (true, true) => Vec2::new(-span.x, d.y),
-        };
+    // Survival waves bring their own UFOs
+    if *mode == GameMode::Survival {
+        return;
+    }
+    let definition = active_level_definition.0.as_ref();
+    let spawn = match definition.and_then(|definition| definition.ufo_schedule.as_ref()) {
+        Some(schedule) => {
+            let level_time = run_stats.time_played - level_start_stats.0.time_played;
+            let due = schedule
+                .get(scheduled_ufos.0)
+                .is_some_and(|&at| level_time >= at);
+            if due {
+                scheduled_ufos.0 += 1;
+            }
+            due
+        }
+        None => next_ufo_score.bump(score.value()),
+    };
+    if spawn {
+        let shoot_delay_modifier =
+            definition.map_or(1.0, |definition| definition.modifiers.ufo_shoot_delay);
+        spawn_ufo_squadron(
+            &mut commands,
+            &sprite_sheets,
+            &level,
+            UfoKind::for_score(score.value()),
+            level.ufo_squadron_size(),
+            shoot_delay_modifier,
+        );
+    }
+}
+
+/// Spawns a UFO crossing the screen from a random point on the edge
+pub fn spawn_ufo(
+    commands: &mut Commands,
+    sprite_sheets: &SpriteSheets,
+    level: &Level,
+    kind: UfoKind,
+    shoot_delay_modifier: f32,
+) {
+    spawn_ufo_squadron(
+        commands,
+        sprite_sheets,
+        level,
+        kind,
+        1,
+        shoot_delay_modifier,
+    );
+}
+
+/// Spawns `count` UFOs flying side by side along the same path
+pub fn spawn_ufo_squadron(
+    commands: &mut Commands,
+    sprite_sheets: &SpriteSheets,
+    level: &Level,
+    kind: UfoKind,
+    count: u32,
+    shoot_delay_modifier: f32,
+) {
+    let horizontal: bool = random();
+    let direction: bool = random();
+    let span = Vec2::new(GAME_WIDTH as f32 / 2.0, GAME_HEIGHT as f32 / 2.0);
+    let d = random::<f32>() * span * 2.0;
+    let position = match (horizontal, direction) {
+        (false, false) => Vec2::new(d.x, span.y),
+        (true, false) => Vec2::new(span.x, d.y),
+        (false, true) => Vec2::new(d.x, -span.y),
+        (true, true) => Vec2::new(-span.x, d.y),
+    };
 
+    let frequency = random::<f32>() * 5.0;
+    let amplitude = random::<f32>() * 90.0 + 10.0;
+    let side = (-position).normalize_or_zero().perp();
+    for i in 0..count {
+        let offset = side * (i as f32 - (count - 1) as f32 / 2.0) * UFO_SQUADRON_SPACING;
         let ufo = Ufo {
-            start_position: position,
-            end_position: -position,
-            frequency: random::<f32>() * 5.0,
-            amplitude: random::<f32>() * 90.0 + 10.0,
-            duration: level.ufo_duration(),
+            kind,
+            start_position: position + offset,
+            end_position: -position + offset,
+            frequency,
+            amplitude,
+            phase: i as f32 * 0.25,
+            duration: level.ufo_duration() * kind.duration(),
             time: 0.0,
-            shoot_delay: level.ufo_shoot_delay(),
-            shoot_accuracy: level.ufo_shoot_accuracy(),
-            life: 20,
+            // Staggered so that the squadron doesn't fire in a single volley
+            shoot_delay: level.ufo_shoot_delay() * shoot_delay_modifier + i as f32 * 0.3,
+            shoot_accuracy: kind.shoot_accuracy(level.ufo_shoot_accuracy()),
+            evasion_skill: level.ufo_evasion_skill(),
+            dodge: 0.0,
+            dodge_velocity: 0.0,
+            dodge_time: 0.0,
+            evasion_cooldown: 0.0,
         };
-        commands.spawn(UfoBundle::new(&sprite_sheets.ufo, ufo));
+        let mut ufo_commands = commands.spawn(UfoBundle::new(&sprite_sheets.ufo, ufo));
+        if kind == UfoKind::Stealth {
+            ufo_commands.insert(Cloak::default());
+        }
     }
 }
 
@@ -103,44 +301,121 @@ fn ufo_movement_system(
     mut commands: Commands,
     mut ufos_query: Query<(Entity, &mut Ufo, &mut Transform)>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
 ) {
     for (entity, mut ufo, mut transform) in ufos_query.iter_mut() {
-        ufo.time += time.delta_seconds();
+        let delta = time.delta_seconds() * time_scale.0;
+        ufo.time += delta;
+        if ufo.dodge_time > 0.0 {
+            ufo.dodge_time -= delta;
+            ufo.dodge += ufo.dodge_velocity * delta;
+        }
         let t = ufo.time / ufo.duration;
         let journey = ufo.end_position - ufo.start_position;
-        let deviation = ufo.amplitude * f32::sin(ufo.frequency * std::f32::consts::TAU * t);
+        let deviation = ufo.dodge
+            + ufo.amplitude * f32::sin((ufo.frequency * t + ufo.phase) * std::f32::consts::TAU);
         let position = ufo.start_position + journey * t + journey.normalize().perp() * deviation;
         let angle = 10.0 * std::f32::consts::TAU * t;
         let rotation = Quat::from_rotation_z(angle);
-        *transform = Transform::from_rotation(rotation).with_translation(position.extend(0.));
+        *transform = Transform::from_rotation(rotation)
+            .with_translation(position.extend(0.))
+            .with_scale(Vec3::splat(ufo.kind.scale()));
 
         if ufo.time >= ufo.duration {
             commands.entity(entity).despawn();
         }
     }
 }
+/// Sidesteps shots that would hit the UFO, as often as the level's evasion skill allows
+fn ufo_evasion_system(
+    mut ufos_query: Query<(&mut Ufo, &Transform)>,
+    projectiles_query: Query<(&Transform, &Moving), With<ShipProjectile>>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    for (mut ufo, ufo_transform) in ufos_query.iter_mut() {
+        ufo.evasion_cooldown -= time.delta_seconds() * time_scale.0;
+        if ufo.evasion_cooldown > 0.0 || ufo.dodge_time > 0.0 {
+            continue;
+        }
+        let position = ufo_transform.translation.truncate();
+        let radius = UFO_RADIUS * ufo.kind.scale();
+        let threat = projectiles_query.iter().find_map(|(transform, moving)| {
+            let relative = position - transform.translation.truncate();
+            let speed_squared = moving.velocity.length_squared();
+            if speed_squared == 0.0 {
+                return None;
+            }
+            // Closest approach along the shot's path within the lookahead
+            let t = relative.dot(moving.velocity) / speed_squared;
+            let miss = relative - moving.velocity * t;
+            (t > 0.0 && t < UFO_EVASION_LOOKAHEAD && miss.length() < radius * 1.5)
+                .then_some((moving.velocity, miss))
+        });
+        let Some((shot_velocity, miss)) = threat else {
+            continue;
+        };
+        ufo.evasion_cooldown = UFO_EVASION_COOLDOWN;
+        if random::<f32>() >= ufo.evasion_skill {
+            continue;
+        }
+        // Dodge to whichever side of the shot's path the UFO already leans
+        let away = if miss == Vec2::ZERO {
+            shot_velocity.perp().normalize()
+        } else {
+            miss.normalize()
+        };
+        let side = (ufo.end_position - ufo.start_position).normalize().perp();
+        ufo.dodge_velocity = UFO_DODGE_SPEED * away.dot(side).signum();
+        ufo.dodge_time = UFO_DODGE_DURATION;
+    }
+}
 fn ufo_animation_system(
-    mut ufos_query: Query<(&Ufo, &mut Handle<Image>)>,
+    mut ufos_query: Query<(&Ufo, &mut TextureAtlasSprite)>,
     sprite_sheets: Res<SpriteSheets>,
 ) {
     let frame_duration = 1. / 5.;
-    for (ufo, mut image) in ufos_query.iter_mut() {
+    for (ufo, mut sprite) in ufos_query.iter_mut() {
         let frame = (ufo.time / frame_duration) as usize % sprite_sheets.ufo.ship.len();
-        *image = sprite_sheets.ufo.ship[frame].clone();
+        sprite.index = sprite_sheets.ufo.ship[frame];
+    }
+}
+fn ufo_cloak_system(
+    mut ufos_query: Query<(&mut Cloak, &mut TextureAtlasSprite)>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+) {
+    let delta = time.delta_seconds() * time_scale.0;
+    for (mut cloak, mut sprite) in ufos_query.iter_mut() {
+        cloak.time += delta;
+        cloak.reveal = (cloak.reveal - delta).max(0.0);
+        sprite.color.set_a(cloak.alpha());
     }
 }
 fn ufo_shoot_system(
     mut commands: Commands,
-    mut ufos_query: Query<(&mut Ufo, &Transform)>,
-    ships_query: Query<&Transform, With<Ship>>,
+    mut ufos_query: Query<(&mut Ufo, &Transform, Option<&mut Cloak>)>,
+    ships_query: Query<(&Ship, &Transform)>,
     sprite_sheets: Res<SpriteSheets>,
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
 ) {
-    let ship_transform = ships_query.single();
-    for (mut ufo, ufo_transform) in ufos_query.iter_mut() {
-        ufo.shoot_delay -= time.delta_seconds();
+    // Nothing to shoot at while the ship is gone
+    let target = ships_query
+        .get_single()
+        .ok()
+        .filter(|(ship, _)| ship.respawn_delay <= 0.0 && ship.lives > 0);
+    for (mut ufo, ufo_transform, cloak) in ufos_query.iter_mut() {
+        ufo.shoot_delay -= time.delta_seconds() * time_scale.0;
+        let Some((_, ship_transform)) = target else {
+            continue;
+        };
         if ufo.shoot_delay <= 0.0 {
             ufo.shoot_delay = 2.0; // FIXME
+                                   // Shooting gives a cloaked UFO away
+            if let Some(mut cloak) = cloak {
+                cloak.reveal = CLOAK_REVEAL_TIME;
+            }
             let target = (ship_transform.translation - ufo_transform.translation)
                 .truncate()
                 .normalize();
@@ -162,147 +437,172 @@ fn ufo_shoot_system(
     }
 }
 fn ship_ufo_collision_system(
-    mut commands: Commands,
-    sprite_sheets: Res<SpriteSheets>,
-    mut ships_query: Query<(&mut Ship, &Transform, &CollisionShape)>,
-    mut ufo_query: Query<(&mut Ufo, &CollisionShape), (With<Ufo>, Without<Ship>)>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ships_query: Query<&mut Ship>,
+    ufo_query: Query<(&Health, Option<&ContactDamage>), (With<Enemy>, Without<Ship>)>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
-    for (mut ship, ship_transform, ship_shape) in ships_query.iter_mut() {
-        if ship.invulnerability > 0.0 {
+    for event in collision_events.iter() {
+        let Ok((ufo_health, contact_damage)) = ufo_query.get(event.b) else {
+            continue;
+        };
+        let Ok(mut ship) = ships_query.get_mut(event.a) else {
+            continue;
+        };
+        if ship.invulnerability > 0.0 || ufo_health.current <= 0.0 {
             continue;
         }
-        let ship_position = ship_transform.translation.truncate();
-        for (mut ufo, ufo_shape) in ufo_query.iter_mut() {
-            if ufo.life <= 0 {
-                continue;
-            }
-            if ship_shape.intersects(ufo_shape) {
-                if ship.shield_level > 0 {
-                    ship.shield_level -= 1;
-                    ufo.life = 0;
-                } else {
-                    ship.die();
-                    commands.spawn(ExplosionBundle::new(
-                        &sprite_sheets.explosion,
-                        ship_position,
-                    ));
-                    commands.spawn(WaveParticleBundle::new(
-                        ship_position,
-                        &sprite_sheets.particles,
-                    ));
-                }
-            }
-        }
+        let amount = contact_damage.map_or(UFO_COLLISION_DAMAGE, |damage| damage.0);
+        ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+        damage_events.send(DamageEvent {
+            target: event.a,
+            source: Some(event.b),
+            amount,
+            cause: DamageCause::Collision,
+        });
+        // Ramming hits the enemy just as hard, which is enough to destroy a UFO
+        damage_events.send(DamageEvent {
+            target: event.b,
+            source: Some(event.a),
+            amount,
+            cause: DamageCause::Collision,
+        });
     }
 }
 
 fn ship_ufo_laser_collision_system(
     mut commands: Commands,
-    mut ships_query: Query<(&mut Ship, &Transform, &mut Moving, &CollisionShape)>,
-    ufo_laser_query: Query<(Entity, &Moving, &CollisionShape), (With<UfoLaser>, Without<Ship>)>,
-    sprite_sheets: Res<SpriteSheets>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut ships_query: Query<(&mut Ship, &mut Moving, &Mass)>,
+    ufo_laser_query: Query<(&Moving, &Mass), (With<UfoLaser>, Without<Ship>)>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
-    for (mut ship, ship_transform, mut ship_moving, ship_collision_shape) in ships_query.iter_mut()
-    {
+    for event in collision_events.iter() {
+        let Ok((laser_moving, laser_mass)) = ufo_laser_query.get(event.b) else {
+            continue;
+        };
+        let Ok((mut ship, mut ship_moving, ship_mass)) = ships_query.get_mut(event.a) else {
+            continue;
+        };
         if ship.invulnerability > 0.0 {
             continue;
         }
-        let ship_position = ship_transform.translation.truncate();
-        for (laser_entity, laser_moving, laser_collision_shape) in ufo_laser_query.iter() {
-            if ship_collision_shape.intersects(laser_collision_shape) {
-                commands.entity(laser_entity).despawn();
-                if ship.shield_level > 0 {
-                    ship.shield_level -= 1;
-                    ship_moving.velocity += laser_moving.velocity * 0.1;
-                } else {
-                    ship.die();
-                    commands.spawn(ExplosionBundle::new(
-                        &sprite_sheets.explosion,
-                        ship_position,
-                    ));
-                    commands.spawn(WaveParticleBundle::new(
-                        ship_position,
-                        &sprite_sheets.particles,
-                    ));
-                }
-            }
-        }
+        commands.entity(event.b).despawn();
+        ship.invulnerability = SHIP_HIT_INVULNERABILITY;
+        damage_events.send(DamageEvent {
+            target: event.a,
+            source: Some(event.b),
+            amount: UFO_LASER_DAMAGE,
+            cause: DamageCause::Shot,
+        });
+        let impulse = collision_impulse(
+            event.normal,
+            ship_moving.velocity - laser_moving.velocity,
+            ship_mass.0,
+            laser_mass.0,
+            COLLISION_RESTITUTION,
+        );
+        ship_moving.velocity += impulse / ship_mass.0;
     }
 }
 fn ship_projectile_ufo_hit_system(
     mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
     mut projectiles: Query<(
-        Entity,
         &mut ShipProjectile,
         &mut Transform,
         &mut CollisionShape,
-        Option<&mut Beam>,
+        Option<&mut Piercing>,
+        Option<&mut RailgunShot>,
     )>,
-    mut ufos: Query<(&mut Ufo, &Transform, &CollisionShape), Without<ShipProjectile>>,
+    ufos: Query<(&Health, &Transform, &CollisionShape), (With<Enemy>, Without<ShipProjectile>)>,
+    mut damage_events: EventWriter<DamageEvent>,
     sprite_sheets: Res<SpriteSheets>,
 ) {
-    for (
-        projectile_entity,
-        projectile,
-        mut projectile_transform,
-        mut projectile_shape,
-        mut maybe_beam,
-    ) in projectiles.iter_mut()
-    {
-        for (mut ufo, ufo_transform, ufo_shape) in ufos.iter_mut() {
-            if ufo.life > 0 && projectile_shape.intersects(ufo_shape) {
-                match *projectile {
-                    ShipProjectile::Rapid | ShipProjectile::Spread => {
-                        commands.entity(projectile_entity).despawn();
-                        if ufo.life > 0 {
-                            ufo.life -= 1;
-                        }
-                    }
-                    ShipProjectile::Plasma { mut power } => {
-                        let overlap = -projectile_shape.distance(ufo_shape).min(0.0);
-                        let effect = overlap.min(ufo.life as f32);
-                        power -= effect;
-                        *projectile_shape = CollisionShape::new(
-                            Shape::Circle {
-                                center: Vec2::ZERO,
-                                radius: power,
-                            },
-                            *projectile_transform,
-                        );
-                        if power <= 0.0 {
-                            commands.entity(projectile_entity).despawn();
-                        } else {
-                            projectile_transform.scale = Vec3::splat(power / 16.0);
+    for event in collision_events.iter() {
+        let (projectile_entity, ufo_entity) = (event.a, event.b);
+        let Ok((health, ufo_transform, ufo_shape)) = ufos.get(ufo_entity) else {
+            continue;
+        };
+        let Ok((
+            projectile,
+            mut projectile_transform,
+            mut projectile_shape,
+            mut maybe_piercing,
+            mut maybe_railgun,
+        )) = projectiles.get_mut(projectile_entity)
+        else {
+            continue;
+        };
+        let hit = |amount: f32, cause: DamageCause| DamageEvent {
+            target: ufo_entity,
+            source: Some(projectile_entity),
+            amount,
+            cause,
+        };
+        // Still checked, as a plasma ball may have shrunk out of reach on an earlier hit
+        if health.current > 0.0 && projectile_shape.intersects(ufo_shape) {
+            match *projectile {
+                ShipProjectile::Rapid | ShipProjectile::Spread => {
+                    let cause = match maybe_piercing {
+                        Some(_) => DamageCause::Sweep,
+                        None => DamageCause::Shot,
+                    };
+                    match maybe_piercing {
+                        Some(ref piercing) if piercing.last_hit == Some(ufo_entity) => {
+                            continue;
                         }
-                        if ufo.life > 0 {
-                            ufo.life -= effect.ceil() as i32;
+                        Some(ref mut piercing) if piercing.remaining > 0 => {
+                            piercing.remaining -= 1;
+                            piercing.last_hit = Some(ufo_entity);
                         }
+                        _ => commands.entity(projectile_entity).despawn(),
                     }
-                    ShipProjectile::Beam { .. } => {
-                        if let Some(ref mut beam) = maybe_beam {
-                            beam.length = projectile_shape.distance(ufo_shape);
-                            if beam.cooldown <= 0.0 {
-                                ufo.life -= BEAM_DAMAGE_PER_HIT;
-                                beam.cooldown = BEAM_HIT_INTERVAL;
-                            }
+                    damage_events.send(hit(1.0, cause));
+                }
+                ShipProjectile::Plasma { mut power } => {
+                    let overlap = -projectile_shape.distance(ufo_shape).min(0.0);
+                    let effect = overlap.min(health.current);
+                    power -= effect;
+                    *projectile_shape = CollisionShape::new(
+                        Shape::Circle {
+                            center: Vec2::ZERO,
+                            radius: power,
+                        },
+                        *projectile_transform,
+                    );
+                    if power <= 0.0 {
+                        commands.entity(projectile_entity).despawn();
+                    } else {
+                        projectile_transform.scale = Vec3::splat(power / 16.0);
+                    }
+                    damage_events.send(hit(effect.ceil(), DamageCause::Sweep));
+                }
+                // Beams only hit the nearest target, see `beam_hit_system`
+                ShipProjectile::Beam { .. } => continue,
+                ShipProjectile::Railgun { damage } => {
+                    if let Some(ref mut railgun) = maybe_railgun {
+                        if railgun.hits.contains(&ufo_entity) {
+                            continue;
                         }
+                        railgun.hits.push(ufo_entity);
                     }
+                    damage_events.send(hit(damage as f32, DamageCause::Sweep));
                 }
+            }
 
-                let point = projectile_shape.collision_point(ufo_shape);
-                let direction = (point - ufo_transform.translation.truncate()).normalize();
-                for _ in 0..10 {
-                    let speed = lerp(10.0, 100.0, random());
-                    let velocity =
-                        (direction + (direction.perp() * lerp(-0.5, 0.5, random()))) * speed;
-                    let acceleration = Vec2::ZERO;
-                    commands.spawn(SparkParticleBundle::new(
-                        point,
-                        velocity,
-                        acceleration,
-                        &sprite_sheets.particles,
-                    ));
-                }
+            let point = projectile_shape.collision_point(ufo_shape);
+            let direction = (point - ufo_transform.translation.truncate()).normalize();
+            for _ in 0..10 {
+                let speed = lerp(10.0, 100.0, random());
+                let velocity = (direction + (direction.perp() * lerp(-0.5, 0.5, random()))) * speed;
+                let acceleration = Vec2::ZERO;
+                commands.spawn(SparkParticleBundle::new(
+                    point,
+                    velocity,
+                    acceleration,
+                    &sprite_sheets.particles,
+                ));
             }
         }
     }
@@ -310,41 +610,43 @@ fn ship_projectile_ufo_hit_system(
 
 fn ufo_destroy_system(
     mut commands: Commands,
-    ufos_query: Query<(Entity, &Ufo, &Transform)>,
+    mut died_events: EventReader<Died>,
+    ufos_query: Query<(&Ufo, &Transform)>,
     mut score: ResMut<Score>,
+    score_multiplier: Res<ScoreMultiplier>,
+    mut run_stats: ResMut<RunStats>,
+    level: Res<Level>,
     sprite_sheets: Res<SpriteSheets>,
-    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
 ) {
-    for (ufo_entity, ufo, ufo_transform) in ufos_query.iter() {
-        if ufo.life <= 0 {
-            let speed = lerp(30.0, 80.0, random());
-            let velocity = Vec2::from_angle(random::<f32>() * std::f32::consts::TAU) * speed;
+    for event in died_events.iter() {
+        if let Ok((ufo, ufo_transform)) = ufos_query.get(event.entity) {
+            run_stats.ufos_destroyed += 1;
             let position = ufo_transform.translation.truncate();
-            commands.spawn(PowerupBundle::new(
-                random(),
-                position,
-                velocity,
-                5.0,
-                &sprite_sheets.powerup,
-            ));
             commands.spawn(ExplosionBundle::new(&sprite_sheets.explosion, position));
             commands.spawn(WaveParticleBundle::new(position, &sprite_sheets.particles));
-            score.increase(100);
-            commands.spawn(GameNotificationBundle::new(
-                format!("{}", score.value()),
-                asset_server.load("fonts/DejaVuSans.ttf"),
+            for debris in DebrisBundle::burst(&sprite_sheets, level.asteroid_variant(), position) {
+                commands.spawn(debris);
+            }
+            let score_delta = ufo.kind.score() * score_multiplier.0;
+            score.increase(score_delta);
+            commands.spawn(ScorePopupBundle::new(
+                score_delta,
+                game_assets.font.clone(),
                 position,
                 20.0,
-                1.0,
             ));
-            commands.entity(ufo_entity).despawn();
+            commands.entity(event.entity).despawn();
         }
     }
 }
 #[derive(Bundle)]
 struct UfoBundle {
-    sprite_bundle: SpriteBundle,
+    sprite_bundle: SpriteSheetBundle,
     ufo: Ufo,
+    enemy: Enemy,
+    health: Health,
+    drop_table: DropTable,
     level_entity: LevelEntity,
     collision_shape: CollisionShape,
 }
@@ -352,17 +654,31 @@ impl UfoBundle {
     pub fn new(ufo_images: &UfoImages, ufo: Ufo) -> Self {
         let center = ufo.start_position.clone();
         UfoBundle {
-            sprite_bundle: SpriteBundle {
-                texture: ufo_images.ship[0].clone(),
-                transform: Transform::from_translation(ufo.start_position.extend(0.)),
+            sprite_bundle: SpriteSheetBundle {
+                texture_atlas: ufo_images.atlas.clone(),
+                transform: Transform::from_translation(ufo.start_position.extend(0.))
+                    .with_scale(Vec3::splat(ufo.kind.scale())),
+                sprite: TextureAtlasSprite {
+                    index: ufo_images.ship[0],
+                    color: ufo.kind.color(),
+                    ..Default::default()
+                },
                 ..Default::default()
             },
+            health: Health::new(ufo.kind.health()),
             ufo,
+            enemy: Enemy,
+            // UFOs always leave a powerup behind
+            drop_table: DropTable {
+                entries: vec![(DropEntry::AnyPowerup, 1.0)],
+                nothing: 0.0,
+                minerals: 5,
+            },
             level_entity: LevelEntity,
             collision_shape: CollisionShape::new(
                 Shape::Circle {
                     center: Vec2::ZERO,
-                    radius: 16.0,
+                    radius: UFO_RADIUS,
                 },
                 Transform::from_translation(center.extend(0.)),
             ),
@@ -370,11 +686,13 @@ impl UfoBundle {
     }
 }
 
+/// Enemy laser shot, fired by UFOs and fighters alike
 #[derive(Bundle)]
-struct UfoLaserBundle {
+pub struct UfoLaserBundle {
     sprite_bundle: SpriteBundle,
     ufo_laser: UfoLaser,
     moving: Moving,
+    mass: Mass,
     expiring: Expiring,
     collision_shape: CollisionShape,
 }
@@ -398,6 +716,7 @@ impl UfoLaserBundle {
                 velocity,
                 ..Default::default()
             },
+            mass: Mass(UFO_LASER_MASS),
             expiring: Expiring { life },
             collision_shape: CollisionShape::new(
                 Shape::Circle {
@@ -0,0 +1,1008 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    bundles::*,
+    collision::CollisionEvent,
+    components::*,
+    constants::*,
+    plugins::{DamageCause, DamageEvent, Profile},
+    resources::*,
+    utils::*,
+    AppState, GameSet,
+};
+use bevy::{prelude::*, utils::HashMap};
+use rand::random;
+
+pub struct WeaponPlugin;
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeaponRegistry>()
+            .add_systems(
+                Update,
+                (
+                    ship_weapon_system,
+                    beam_sprite_system.after(ship_weapon_system),
+                )
+                    .in_set(GameSet::Simulation)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (ship_projectile_asteroid_hit_system, beam_hit_system)
+                    .in_set(GameSet::Resolution)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                accuracy_tracking_system.run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+/// How a weapon fires while the trigger is held
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FireMode {
+    /// A volley each time the cooldown allows
+    Volley,
+    /// Builds up charge while held and fires a volley on release
+    Charge,
+    /// A continuous beam extending from the ship while held
+    Beam,
+}
+
+/// Number of shots in a volley, growing with the weapon level
+#[derive(Clone, Copy)]
+pub struct ShotCount {
+    pub base: u32,
+    pub per_level: u32,
+}
+
+impl ShotCount {
+    pub const fn fixed(shots: u32) -> Self {
+        ShotCount {
+            base: shots,
+            per_level: 0,
+        }
+    }
+    fn at(&self, level: u8) -> u32 {
+        self.base + self.per_level * level as u32
+    }
+}
+
+/// Layout of the projectiles in a volley
+#[derive(Clone, Copy)]
+pub enum SpawnPattern {
+    /// One shot per turret, each given as (angle around the nose, divergence)
+    Turrets(&'static [(f32, f32)]),
+    /// Shots spread evenly over an arc that widens from the first to the second value
+    Fan { arc: (f32, f32), shots: ShotCount },
+    /// Shots evenly in every direction
+    Ring { shots: ShotCount },
+    /// Shots one after another out of the nose
+    Column { shots: u32, spacing: f32 },
+    /// Shots side by side across the nose
+    Row { shots: ShotCount, spacing: f32 },
+}
+
+impl SpawnPattern {
+    /// Offset from the ship and angle from the nose of each shot
+    fn shots(&self, level: f32, level_number: u8, arc_scale: f32, extra: u32) -> Vec<(Vec2, f32)> {
+        match *self {
+            SpawnPattern::Turrets(turrets) => turrets
+                .iter()
+                .map(|&(turret_angle, divergence)| {
+                    (
+                        Vec2::from_angle(turret_angle).rotate(Vec2::Y) * 8.0,
+                        divergence,
+                    )
+                })
+                .collect(),
+            SpawnPattern::Fan { arc, shots } => {
+                let arc = lerp(arc.0, arc.1, level) * arc_scale;
+                let shots = shots.at(level_number) + extra;
+                if shots <= 1 {
+                    return vec![(Vec2::ZERO, 0.0)];
+                }
+                (0..shots)
+                    .map(|i| (Vec2::ZERO, arc * i as f32 / (shots - 1) as f32 - arc / 2.0))
+                    .collect()
+            }
+            SpawnPattern::Ring { shots } => {
+                let shots = shots.at(level_number) + extra;
+                (0..shots)
+                    .map(|i| (Vec2::ZERO, TAU * i as f32 / shots as f32))
+                    .collect()
+            }
+            SpawnPattern::Column { shots, spacing } => (0..shots + extra)
+                .map(|i| (Vec2::Y * spacing * i as f32, 0.0))
+                .collect(),
+            SpawnPattern::Row { shots, spacing } => {
+                let shots = shots.at(level_number) + extra;
+                let middle = (shots as f32 - 1.0) / 2.0;
+                (0..shots)
+                    .map(|i| (Vec2::X * spacing * (i as f32 - middle), 0.0))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// How hard a projectile hits, as plasma power or railgun damage
+#[derive(Clone, Copy)]
+pub enum ProjectilePower {
+    /// Ranges from level 1 to the max level
+    Level(f32, f32),
+    /// A share of the power built up by holding fire
+    Charge(f32),
+}
+
+#[derive(Clone)]
+pub struct ProjectileSpec {
+    pub kind: ShipProjectile,
+    pub texture: Handle<Image>,
+    pub speed: f32,
+    pub life: f32,
+    pub power: ProjectilePower,
+}
+
+/// Changes an upgrade makes to its weapon's primary fire, as multipliers unless noted
+#[derive(Clone)]
+pub struct UpgradeEffect {
+    pub cooldown: f32,
+    pub power: f32,
+    pub life: f32,
+    pub arc: f32,
+    pub beam_width: f32,
+    /// Shots added to each volley
+    pub extra_shots: u32,
+    /// Targets each shot goes through before stopping
+    pub pierce: u32,
+    /// Layout used instead of the weapon's own
+    pub pattern: Option<SpawnPattern>,
+}
+
+impl Default for UpgradeEffect {
+    fn default() -> Self {
+        UpgradeEffect {
+            cooldown: 1.0,
+            power: 1.0,
+            life: 1.0,
+            arc: 1.0,
+            beam_width: 1.0,
+            extra_shots: 0,
+            pierce: 0,
+            pattern: None,
+        }
+    }
+}
+
+/// Alternate fire of a weapon, at the weapon's current level
+#[derive(Clone)]
+pub struct SecondarySpec {
+    pub cooldown: f32,
+    pub projectile: ProjectileSpec,
+    pub pattern: SpawnPattern,
+    /// Plasma ammo used each time, it doesn't fire without enough
+    pub ammo: u32,
+}
+
+/// Definition of a weapon. Ranges are given as (level 1, max level).
+#[derive(Clone)]
+pub struct WeaponSpec {
+    pub cooldown: (f32, f32),
+    pub mode: FireMode,
+    pub projectile: ProjectileSpec,
+    pub pattern: SpawnPattern,
+    /// Plasma ammo used by each volley
+    pub ammo: u32,
+    pub upgrades: Vec<(WeaponUpgrade, UpgradeEffect)>,
+    pub secondary: Option<SecondarySpec>,
+}
+
+impl WeaponSpec {
+    pub fn cooldown(&self, level: f32) -> f32 {
+        lerp(self.cooldown.0, self.cooldown.1, level)
+    }
+    /// The effects of the upgrades the ship has for this weapon, combined
+    fn upgrade_effect(&self, ship: &Ship) -> UpgradeEffect {
+        self.upgrades
+            .iter()
+            .filter(|(upgrade, _)| ship.has_upgrade(*upgrade))
+            .fold(UpgradeEffect::default(), |total, (_, effect)| {
+                UpgradeEffect {
+                    cooldown: total.cooldown * effect.cooldown,
+                    power: total.power * effect.power,
+                    life: total.life * effect.life,
+                    arc: total.arc * effect.arc,
+                    beam_width: total.beam_width * effect.beam_width,
+                    extra_shots: total.extra_shots + effect.extra_shots,
+                    pierce: total.pierce + effect.pierce,
+                    pattern: effect.pattern.or(total.pattern),
+                }
+            })
+    }
+}
+
+#[derive(Resource)]
+pub struct WeaponRegistry(HashMap<ShipWeapon, WeaponSpec>);
+
+impl WeaponRegistry {
+    pub fn get(&self, weapon: ShipWeapon) -> &WeaponSpec {
+        &self.0[&weapon]
+    }
+    pub fn register(&mut self, weapon: ShipWeapon, spec: WeaponSpec) {
+        self.0.insert(weapon, spec);
+    }
+}
+
+impl FromWorld for WeaponRegistry {
+    fn from_world(world: &mut World) -> Self {
+        let game_assets = world.resource::<GameAssets>();
+        let mut registry = WeaponRegistry(HashMap::default());
+        registry.register(
+            ShipWeapon::Rapid,
+            WeaponSpec {
+                cooldown: (0.3, 0.05),
+                mode: FireMode::Volley,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Rapid,
+                    texture: game_assets.laser.clone(),
+                    speed: 1200.0,
+                    life: 0.25,
+                    power: ProjectilePower::Level(1.0, 1.0),
+                },
+                pattern: SpawnPattern::Turrets(&[(1.55, 0.0), (-1.55, 0.0)]),
+                ammo: 0,
+                upgrades: vec![
+                    (
+                        WeaponUpgrade::RapidPiercing,
+                        UpgradeEffect {
+                            pierce: RAPID_PIERCE_COUNT,
+                            ..default()
+                        },
+                    ),
+                    (
+                        WeaponUpgrade::RapidDualStream,
+                        UpgradeEffect {
+                            pattern: Some(SpawnPattern::Turrets(&[
+                                (1.55, 0.05),
+                                (0.8, 0.0),
+                                (-0.8, 0.0),
+                                (-1.55, -0.05),
+                            ])),
+                            ..default()
+                        },
+                    ),
+                ],
+                // Focused burst: a tight column of shots from the nose
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_RAPID,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Rapid,
+                        texture: game_assets.laser.clone(),
+                        speed: 1400.0,
+                        life: 0.3,
+                        power: ProjectilePower::Level(1.0, 1.0),
+                    },
+                    pattern: SpawnPattern::Column {
+                        shots: 5,
+                        spacing: 10.0,
+                    },
+                    ammo: 0,
+                }),
+            },
+        );
+        registry.register(
+            ShipWeapon::Spread,
+            WeaponSpec {
+                cooldown: (0.8, 0.3),
+                mode: FireMode::Volley,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Spread,
+                    texture: game_assets.shot.clone(),
+                    speed: 1200.0,
+                    life: 0.2,
+                    power: ProjectilePower::Level(1.0, 1.0),
+                },
+                pattern: SpawnPattern::Fan {
+                    arc: (0.314, 3.0),
+                    shots: ShotCount {
+                        base: 1,
+                        per_level: 2,
+                    },
+                },
+                ammo: 0,
+                upgrades: vec![
+                    (
+                        WeaponUpgrade::SpreadFocused,
+                        UpgradeEffect {
+                            arc: 0.5,
+                            life: 1.5,
+                            ..default()
+                        },
+                    ),
+                    (
+                        WeaponUpgrade::SpreadScatter,
+                        UpgradeEffect {
+                            extra_shots: 2,
+                            ..default()
+                        },
+                    ),
+                ],
+                // Ring shot: shots in every direction
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_SPREAD,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Spread,
+                        texture: game_assets.shot.clone(),
+                        speed: 800.0,
+                        life: 0.25,
+                        power: ProjectilePower::Level(1.0, 1.0),
+                    },
+                    pattern: SpawnPattern::Ring {
+                        shots: ShotCount {
+                            base: 8,
+                            per_level: 2,
+                        },
+                    },
+                    ammo: 0,
+                }),
+            },
+        );
+        registry.register(
+            ShipWeapon::Plasma,
+            WeaponSpec {
+                cooldown: (1.2, 0.8),
+                mode: FireMode::Volley,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Plasma { power: 0.0 },
+                    texture: game_assets.plasma.clone(),
+                    speed: 1000.0,
+                    life: 0.5,
+                    power: ProjectilePower::Level(4.0, 20.0),
+                },
+                pattern: SpawnPattern::Fan {
+                    arc: (0.0, 0.0),
+                    shots: ShotCount::fixed(1),
+                },
+                ammo: 1,
+                upgrades: vec![
+                    (
+                        WeaponUpgrade::PlasmaRepeater,
+                        UpgradeEffect {
+                            cooldown: 0.6,
+                            ..default()
+                        },
+                    ),
+                    (
+                        WeaponUpgrade::PlasmaHeavy,
+                        UpgradeEffect {
+                            power: 1.5,
+                            ..default()
+                        },
+                    ),
+                ],
+                // Cluster: several small plasma balls in a fan
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_PLASMA,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Plasma { power: 0.0 },
+                        texture: game_assets.plasma.clone(),
+                        speed: 800.0,
+                        life: 0.4,
+                        power: ProjectilePower::Level(4.0 / 3.0, 20.0 / 3.0),
+                    },
+                    pattern: SpawnPattern::Fan {
+                        arc: (0.6, 0.6),
+                        shots: ShotCount::fixed(5),
+                    },
+                    ammo: PLASMA_CLUSTER_AMMO_COST,
+                }),
+            },
+        );
+        registry.register(
+            ShipWeapon::Charge,
+            WeaponSpec {
+                cooldown: (0.3, 0.3),
+                mode: FireMode::Charge,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Plasma { power: 0.0 },
+                    texture: game_assets.plasma.clone(),
+                    speed: 1000.0,
+                    life: 0.6,
+                    power: ProjectilePower::Charge(1.0),
+                },
+                pattern: SpawnPattern::Fan {
+                    arc: (0.0, 0.0),
+                    shots: ShotCount::fixed(1),
+                },
+                ammo: 0,
+                upgrades: Vec::new(),
+                // Scatter release: the current charge split into a fan of three shots
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_PLASMA,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Plasma { power: 0.0 },
+                        texture: game_assets.plasma.clone(),
+                        speed: 1000.0,
+                        life: 0.5,
+                        power: ProjectilePower::Charge(0.5),
+                    },
+                    pattern: SpawnPattern::Fan {
+                        arc: (0.5, 0.5),
+                        shots: ShotCount::fixed(3),
+                    },
+                    ammo: 0,
+                }),
+            },
+        );
+        registry.register(
+            ShipWeapon::Railgun,
+            WeaponSpec {
+                cooldown: (2.5, 1.2),
+                mode: FireMode::Volley,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Railgun { damage: 0 },
+                    texture: game_assets.beam.clone(),
+                    speed: 0.0,
+                    life: RAILGUN_TRACER_LIFE,
+                    power: ProjectilePower::Level(4.0, 12.0),
+                },
+                pattern: SpawnPattern::Fan {
+                    arc: (0.0, 0.0),
+                    shots: ShotCount::fixed(1),
+                },
+                ammo: 0,
+                upgrades: Vec::new(),
+                // Fan rail: three weaker rails at once
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_RAILGUN,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Railgun { damage: 0 },
+                        texture: game_assets.beam.clone(),
+                        speed: 0.0,
+                        life: RAILGUN_TRACER_LIFE,
+                        power: ProjectilePower::Level(2.0, 6.0),
+                    },
+                    pattern: SpawnPattern::Fan {
+                        arc: (0.4, 0.4),
+                        shots: ShotCount::fixed(3),
+                    },
+                    ammo: 0,
+                }),
+            },
+        );
+        registry.register(
+            ShipWeapon::Beam,
+            WeaponSpec {
+                cooldown: (0.0, 0.0),
+                mode: FireMode::Beam,
+                projectile: ProjectileSpec {
+                    kind: ShipProjectile::Beam { power: 0.0 },
+                    texture: game_assets.beam.clone(),
+                    speed: 0.0,
+                    life: 0.0,
+                    power: ProjectilePower::Level(
+                        BEAM_DAMAGE_PER_HIT as f32,
+                        BEAM_DAMAGE_PER_HIT as f32,
+                    ),
+                },
+                pattern: SpawnPattern::Fan {
+                    arc: (0.0, 0.0),
+                    shots: ShotCount::fixed(1),
+                },
+                ammo: 0,
+                upgrades: vec![
+                    (
+                        WeaponUpgrade::BeamWide,
+                        UpgradeEffect {
+                            beam_width: 2.0,
+                            ..default()
+                        },
+                    ),
+                    (
+                        WeaponUpgrade::BeamIntense,
+                        UpgradeEffect {
+                            power: 2.0,
+                            ..default()
+                        },
+                    ),
+                ],
+                // Wide pulse: a wall of shots travelling forward
+                secondary: Some(SecondarySpec {
+                    cooldown: SECONDARY_COOLDOWN_BEAM,
+                    projectile: ProjectileSpec {
+                        kind: ShipProjectile::Spread,
+                        texture: game_assets.shot.clone(),
+                        speed: 600.0,
+                        life: 0.4,
+                        power: ProjectilePower::Level(1.0, 1.0),
+                    },
+                    pattern: SpawnPattern::Row {
+                        shots: ShotCount {
+                            base: 9,
+                            per_level: 1,
+                        },
+                        spacing: 6.0,
+                    },
+                    ammo: 0,
+                }),
+            },
+        );
+        registry
+    }
+}
+
+/// Projectiles to spawn for one use of a weapon, with its level and upgrades worked in
+struct Volley<'a> {
+    projectile: &'a ProjectileSpec,
+    pattern: SpawnPattern,
+    /// Level from 0 to 1, as used for ranges
+    level: f32,
+    level_number: u8,
+    effect: UpgradeEffect,
+    /// Primary fire tags its projectiles for the accuracy stats
+    fired_from: Option<ShipWeapon>,
+}
+
+impl Volley<'_> {
+    fn power(&self, ship: &Ship) -> f32 {
+        let power = match self.projectile.power {
+            ProjectilePower::Level(min, max) => lerp(min, max, self.level),
+            ProjectilePower::Charge(share) => charge_power(ship) * share,
+        };
+        power * self.effect.power
+    }
+
+    fn spawn(&self, commands: &mut Commands, ship: &Ship, transform: &Transform, color: Color) {
+        let spec = self.projectile;
+        let power = self.power(ship);
+        let life = spec.life * self.effect.life;
+        let shots = self.pattern.shots(
+            self.level,
+            self.level_number,
+            self.effect.arc,
+            self.effect.extra_shots,
+        );
+        for (offset, angle) in shots {
+            let translation = transform.translation + transform.rotation * offset.extend(0.0);
+            let rotation = transform.rotation * Quat::from_rotation_z(angle);
+            let velocity = (rotation * Vec3::Y).truncate() * spec.speed;
+            let (kind, projectile_transform, power) = match spec.kind {
+                ShipProjectile::Rapid => (
+                    spec.kind,
+                    Transform {
+                        translation,
+                        rotation,
+                        ..default()
+                    },
+                    1.0,
+                ),
+                ShipProjectile::Spread => {
+                    (spec.kind, Transform::from_translation(translation), 1.0)
+                }
+                ShipProjectile::Plasma { .. } => (
+                    ShipProjectile::Plasma { power },
+                    Transform {
+                        translation,
+                        rotation: Quat::from_rotation_z(1.57) * rotation,
+                        scale: Vec3::splat(power / 16.0),
+                    },
+                    power,
+                ),
+                ShipProjectile::Railgun { .. } => {
+                    let shot = spawn_railgun_shot(
+                        commands,
+                        spec.texture.clone(),
+                        translation,
+                        rotation,
+                        power.ceil() as i32,
+                        color,
+                    );
+                    if let Some(weapon) = self.fired_from {
+                        commands.entity(shot).insert(FiredFrom(weapon));
+                    }
+                    continue;
+                }
+                // Beams are spawned with the ship and only switched on and off
+                ShipProjectile::Beam { .. } => continue,
+            };
+            let mut projectile = commands.spawn(ShipProjectileBundle::new(
+                kind,
+                spec.texture.clone(),
+                velocity,
+                projectile_transform,
+                life,
+                power,
+                color,
+            ));
+            if let Some(weapon) = self.fired_from {
+                projectile.insert(FiredFrom(weapon));
+            }
+            if self.effect.pierce > 0 {
+                projectile.insert(Piercing {
+                    remaining: self.effect.pierce,
+                    last_hit: None,
+                });
+            }
+        }
+    }
+}
+
+fn ship_weapon_system(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    mut ship_query: Query<(&mut Ship, &Transform)>,
+    effects_query: Query<&TimedEffect>,
+    mut beam_query: Query<&mut Beam, Without<Ship>>,
+    weapons: Res<WeaponRegistry>,
+    time: Res<Time>,
+) {
+    let time_delta = time.delta().as_secs_f32();
+    let color = profile.projectile_palette.color();
+
+    let overdrive = effects_query
+        .iter()
+        .any(|effect| effect.powerup == Powerup::Overdrive);
+    for (mut ship, transform) in ship_query.iter_mut() {
+        if ship.weapon_cooldown > 0.0 {
+            ship.weapon_cooldown -= time_delta;
+        }
+        if ship.secondary_cooldown > 0.0 {
+            ship.secondary_cooldown -= time_delta;
+        }
+        ship.cool_weapons(time_delta);
+        if ship.weapon != ShipWeapon::Charge {
+            ship.charge = 0.0;
+        }
+
+        let weapon = ship.weapon;
+        let spec = weapons.get(weapon);
+        let level_number = ship.weapon_level(weapon);
+        let level = (level_number.max(1) - 1) as f32 / 8.0;
+        let effect = spec.upgrade_effect(&ship);
+        // Overdrive fires every weapon as if at the top level
+        let primary = Volley {
+            projectile: &spec.projectile,
+            pattern: effect.pattern.unwrap_or(spec.pattern),
+            level: if overdrive { 1.0 } else { level },
+            level_number: if overdrive { 8 } else { level_number },
+            effect,
+            fired_from: Some(weapon),
+        };
+        if ship.fire
+            && ship.weapon_cooldown <= 0.0
+            && !ship.is_overheated(weapon)
+            && ship.has_ammo(weapon)
+        {
+            match spec.mode {
+                FireMode::Volley => {
+                    primary.spawn(&mut commands, &ship, transform, color);
+                    ship.plasma_ammo -= spec.ammo;
+                    let cooldown = spec.cooldown(primary.level) * primary.effect.cooldown;
+                    ship.weapon_cooldown = cooldown;
+                    ship.weapon_cooldown_duration = cooldown;
+                    ship.add_heat(weapon, weapon.heat_per_shot());
+                }
+                // Heats up on release instead
+                FireMode::Charge => {
+                    ship.charge = (ship.charge + time_delta).min(CHARGE_MAX_TIME);
+                }
+                FireMode::Beam => {
+                    for mut beam in beam_query.iter_mut() {
+                        beam.active = true;
+                        beam.width = primary.effect.beam_width;
+                        beam.damage = primary.power(&ship) as i32;
+                        beam.sustained += time_delta;
+                        if overdrive {
+                            beam.max_length = BEAM_BASE_LENGTH + BEAM_LENGTH_PER_LEVEL * 8.0;
+                        } else if beam.sustained > BEAM_EXTEND_TIME {
+                            beam.max_length =
+                                (beam.max_length - time_delta * BEAM_SHRINK_RATE).max(0.);
+                        }
+                        if beam.cooldown <= 0.0 {
+                            beam.length = beam
+                                .max_length
+                                .min(beam.length + beam.max_length * time_delta / BEAM_EXTEND_TIME);
+                        } else {
+                            beam.cooldown -= time_delta;
+                        }
+                    }
+                    ship.add_heat(weapon, weapon.heat_per_shot() * time_delta);
+                }
+            }
+        } else if spec.mode == FireMode::Charge && !ship.fire && ship.charge > 0.0 {
+            primary.spawn(&mut commands, &ship, transform, color);
+            ship.charge = 0.0;
+            ship.weapon_cooldown = spec.cooldown(0.0);
+            ship.weapon_cooldown_duration = ship.weapon_cooldown;
+            ship.add_heat(weapon, weapon.heat_per_shot());
+        } else if spec.mode == FireMode::Beam {
+            for mut beam in beam_query.iter_mut() {
+                beam.active = false;
+                if beam.length > 0.0 {
+                    beam.length = (beam.length - time_delta * BEAM_RETRACT_RATE).max(0.0);
+                } else {
+                    beam.sustained = 0.0;
+                    let max_length =
+                        BEAM_BASE_LENGTH + BEAM_LENGTH_PER_LEVEL * ship.weapon_beam_level as f32;
+                    beam.max_length =
+                        (beam.max_length + time_delta * BEAM_RECHARGE_RATE).min(max_length);
+                }
+            }
+        }
+
+        let Some(secondary) = &spec.secondary else {
+            continue;
+        };
+        let uses_charge = matches!(secondary.projectile.power, ProjectilePower::Charge(_));
+        if ship.secondary_fire
+            && ship.secondary_cooldown <= 0.0
+            && ship.plasma_ammo >= secondary.ammo
+            && (!uses_charge || ship.charge > 0.0)
+        {
+            Volley {
+                projectile: &secondary.projectile,
+                pattern: secondary.pattern,
+                level,
+                level_number,
+                effect: UpgradeEffect::default(),
+                fired_from: None,
+            }
+            .spawn(&mut commands, &ship, transform, color);
+            ship.secondary_cooldown = secondary.cooldown;
+            ship.plasma_ammo -= secondary.ammo;
+            if uses_charge {
+                ship.charge = 0.0;
+            }
+        }
+    }
+}
+
+fn spawn_railgun_shot(
+    commands: &mut Commands,
+    texture: Handle<Image>,
+    translation: Vec3,
+    rotation: Quat,
+    damage: i32,
+    color: Color,
+) -> Entity {
+    let transform = Transform {
+        translation,
+        rotation,
+        ..Default::default()
+    };
+    commands
+        .spawn(RailgunShotBundle::new(damage, transform))
+        .with_children(|shot| {
+            shot.spawn(RailgunTracerBundle::new(texture, color));
+        })
+        .id()
+}
+
+fn charge_power(ship: &Ship) -> f32 {
+    let max_power = lerp(
+        20.0,
+        40.0,
+        (ship.weapon_charge_level.max(1) - 1) as f32 / 8.0,
+    );
+    lerp(CHARGE_MIN_POWER, max_power, ship.charge / CHARGE_MAX_TIME)
+}
+
+fn beam_sprite_system(
+    mut beam_query: Query<(&Beam, &mut Transform, &Children), Without<BeamTip>>,
+    mut tip_query: Query<&mut Transform, With<BeamTip>>,
+) {
+    for (beam, mut transform, children) in beam_query.iter_mut() {
+        transform.scale.x = beam.width;
+        transform.scale.y = beam.length / 128.0;
+        for child in children.iter() {
+            if let Ok(mut tip_transform) = tip_query.get_mut(*child) {
+                tip_transform.scale.y = 1.0 / transform.scale.y;
+            }
+        }
+    }
+}
+
+fn ship_projectile_asteroid_hit_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut projectiles: Query<(
+        &mut ShipProjectile,
+        &mut Transform,
+        &mut CollisionShape,
+        Option<&mut Piercing>,
+        Option<&mut RailgunShot>,
+    )>,
+    asteroids: Query<
+        (&Health, &CollisionShape, &Transform),
+        (With<Asteroid>, Without<ShipProjectile>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    for event in collision_events.iter() {
+        let (projectile_entity, asteroid_entity) = (event.a, event.b);
+        let Ok((health, asteroid_shape, asteroid_transform)) = asteroids.get(asteroid_entity)
+        else {
+            continue;
+        };
+        let Ok((
+            projectile,
+            mut projectile_transform,
+            mut projectile_shape,
+            mut maybe_piercing,
+            mut maybe_railgun,
+        )) = projectiles.get_mut(projectile_entity)
+        else {
+            continue;
+        };
+        let hit = |amount: f32, cause: DamageCause| DamageEvent {
+            target: asteroid_entity,
+            source: Some(projectile_entity),
+            amount,
+            cause,
+        };
+        match *projectile {
+            ShipProjectile::Rapid | ShipProjectile::Spread => {
+                let cause = match maybe_piercing {
+                    Some(_) => DamageCause::Sweep,
+                    None => DamageCause::Shot,
+                };
+                match maybe_piercing {
+                    Some(ref piercing) if piercing.last_hit == Some(asteroid_entity) => {
+                        continue;
+                    }
+                    Some(ref mut piercing) if piercing.remaining > 0 => {
+                        piercing.remaining -= 1;
+                        piercing.last_hit = Some(asteroid_entity);
+                    }
+                    _ => commands.entity(projectile_entity).despawn(),
+                }
+                if health.current > 0.0 {
+                    damage_events.send(hit(1.0, cause));
+                }
+            }
+            ShipProjectile::Plasma { mut power } => {
+                // The ball may have shrunk out of reach on an earlier hit this frame
+                if !projectile_shape.intersects(asteroid_shape) {
+                    continue;
+                }
+                let overlap = -projectile_shape.distance(asteroid_shape).min(0.0);
+                let effect = overlap.min(health.current);
+                power -= effect;
+                *projectile_shape = CollisionShape::new(
+                    Shape::Circle {
+                        center: Vec2::ZERO,
+                        radius: power,
+                    },
+                    *projectile_transform,
+                );
+                if power <= 0.0 {
+                    commands.entity(projectile_entity).despawn();
+                } else {
+                    projectile_transform.scale = Vec3::splat(power / 16.0);
+                }
+                if health.current > 0.0 {
+                    damage_events.send(hit(effect.ceil(), DamageCause::Sweep));
+                }
+            }
+            // Beams only hit the nearest target, see `beam_hit_system`
+            ShipProjectile::Beam { .. } => continue,
+            ShipProjectile::Railgun { damage } => {
+                if let Some(ref mut railgun) = maybe_railgun {
+                    if railgun.hits.contains(&asteroid_entity) {
+                        continue;
+                    }
+                    railgun.hits.push(asteroid_entity);
+                }
+                damage_events.send(hit(damage as f32, DamageCause::Sweep));
+            }
+        }
+        spawn_hit_sparks(
+            &mut commands,
+            projectile_shape.collision_point(asteroid_shape),
+            asteroid_transform.translation.truncate(),
+            &sprite_sheets.particles,
+        );
+    }
+}
+
+/// Stops each beam at the nearest asteroid or enemy in its way, which is the only one it
+/// damages
+fn beam_hit_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut beams: Query<(&mut Beam, &CollisionShape)>,
+    targets: Query<
+        (&Health, &CollisionShape, &Transform),
+        (Or<(With<Asteroid>, With<Enemy>)>, Without<Beam>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    let mut beam_hits: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    for event in collision_events.iter() {
+        if beams.contains(event.a) && targets.contains(event.b) {
+            beam_hits.entry(event.a).or_default().push(event.b);
+        }
+    }
+
+    for (beam_entity, target_entities) in beam_hits {
+        let Ok((mut beam, beam_shape)) = beams.get_mut(beam_entity) else {
+            continue;
+        };
+        if !beam.active {
+            continue;
+        }
+        let nearest = target_entities
+            .into_iter()
+            .filter_map(|entity| targets.get(entity).ok().map(|target| (entity, target)))
+            .filter(|(_, (health, _, _))| health.current > 0.0)
+            .map(|target| (beam_shape.distance(target.1 .1), target))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+        let Some((distance, (target_entity, (_, target_shape, target_transform)))) = nearest else {
+            continue;
+        };
+        beam.length = distance.min(beam.max_length);
+        if beam.cooldown <= 0.0 {
+            damage_events.send(DamageEvent {
+                target: target_entity,
+                source: Some(beam_entity),
+                amount: beam.damage as f32,
+                cause: DamageCause::Sweep,
+            });
+            beam.cooldown = BEAM_HIT_INTERVAL;
+        }
+        spawn_hit_sparks(
+            &mut commands,
+            beam_shape.collision_point(target_shape),
+            target_transform.translation.truncate(),
+            &sprite_sheets.particles,
+        );
+    }
+}
+
+/// Sparks flying off the point of impact, away from the center of what was hit
+fn spawn_hit_sparks(
+    commands: &mut Commands,
+    point: Vec2,
+    center: Vec2,
+    particle_images: &ParticleImages,
+) {
+    let direction = (point - center).normalize();
+    for _ in 0..10 {
+        let speed = lerp(10.0, 100.0, random());
+        let velocity = (direction + (direction.perp() * lerp(-0.5, 0.5, random()))) * speed;
+        commands.spawn(SparkParticleBundle::new(
+            point,
+            velocity,
+            Vec2::ZERO,
+            particle_images,
+        ));
+    }
+}
+
+/// Counts projectiles fired with primary fire and the ones that hit something at least once.
+/// Beams are continuous and left out.
+fn accuracy_tracking_system(
+    mut damage_events: EventReader<DamageEvent>,
+    projectiles: Query<Entity, With<FiredFrom>>,
+    fired: Query<(Entity, &FiredFrom), Added<FiredFrom>>,
+    mut in_flight: Local<HashMap<Entity, ShipWeapon>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    // Projectiles despawned by their hit are gone by now, so check hits before pruning
+    for event in damage_events.iter() {
+        if let Some(weapon) = event.source.and_then(|source| in_flight.remove(&source)) {
+            run_stats.shots_hit += 1;
+            run_stats.weapons.entry(weapon).or_default().hit += 1;
+        }
+    }
+    in_flight.retain(|&entity, _| projectiles.contains(entity));
+    for (entity, &FiredFrom(weapon)) in fired.iter() {
+        in_flight.insert(entity, weapon);
+        run_stats.shots_fired += 1;
+        run_stats.weapons.entry(weapon).or_default().fired += 1;
+    }
+}
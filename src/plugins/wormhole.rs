@@ -0,0 +1,99 @@
+use crate::{
+    bundles::*, components::*, constants::*, plugins::WormholePair, resources::*, AppState, GameSet,
+};
+use bevy::prelude::*;
+
+pub struct WormholePlugin;
+impl Plugin for WormholePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (wormhole_cooldown_system, wormhole_travel_system)
+                .chain()
+                .in_set(GameSet::Simulation)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+#[derive(Component)]
+struct Wormhole {
+    exit: Vec2,
+}
+
+/// Keeps whatever just came through a wormhole from bouncing straight back
+#[derive(Component)]
+struct WormholeCooldown(f32);
+
+/// Spawns both ends of a wormhole pair, with a swirl on each
+pub fn spawn_wormholes(commands: &mut Commands, sprite_sheets: &SpriteSheets, pair: &WormholePair) {
+    let (a, b) = (Vec2::from(pair.0), Vec2::from(pair.1));
+    for (position, exit) in [(a, b), (b, a)] {
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: sprite_sheets.particles.atlas.clone(),
+                transform: Transform::from_translation(position.extend(-0.05)),
+                sprite: TextureAtlasSprite {
+                    index: sprite_sheets.particles.ring,
+                    color: Color::rgba(0.5, 0.3, 1.0, 0.8),
+                    custom_size: Some(Vec2::splat(WORMHOLE_RADIUS * 2.0)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Spinning { speed: 3.0 },
+            Wormhole { exit },
+            LevelEntity,
+        ));
+    }
+}
+
+fn wormhole_cooldown_system(
+    mut commands: Commands,
+    mut cooldowns_query: Query<(Entity, &mut WormholeCooldown)>,
+    time: Res<Time>,
+) {
+    for (entity, mut cooldown) in cooldowns_query.iter_mut() {
+        cooldown.0 -= time.delta_seconds();
+        if cooldown.0 <= 0.0 {
+            commands.entity(entity).remove::<WormholeCooldown>();
+        }
+    }
+}
+
+/// Moves anything that flies into a wormhole out of the other end, keeping its velocity
+fn wormhole_travel_system(
+    mut commands: Commands,
+    wormholes_query: Query<(&Wormhole, &Transform)>,
+    mut travelers_query: Query<
+        (Entity, &mut Transform, &Moving),
+        (
+            With<CollisionShape>,
+            Without<Wormhole>,
+            Without<WormholeCooldown>,
+        ),
+    >,
+    sprite_sheets: Res<SpriteSheets>,
+) {
+    // The cooldown only lands at the end of the frame, so keep track here too
+    let mut travelled = Vec::new();
+    for (wormhole, wormhole_transform) in wormholes_query.iter() {
+        let entrance = wormhole_transform.translation.truncate();
+        for (entity, mut transform, moving) in travelers_query.iter_mut() {
+            if transform.translation.truncate().distance(entrance) > WORMHOLE_RADIUS
+                || travelled.contains(&entity)
+            {
+                continue;
+            }
+            travelled.push(entity);
+            let exit = wormhole.exit + moving.velocity.normalize_or_zero() * WORMHOLE_RADIUS * 1.5;
+            transform.translation = exit.extend(transform.translation.z);
+            commands
+                .entity(entity)
+                .insert(WormholeCooldown(WORMHOLE_COOLDOWN));
+            for position in [entrance, wormhole.exit] {
+                commands.spawn(RingParticleBundle::new(position, &sprite_sheets.particles));
+            }
+        }
+    }
+}
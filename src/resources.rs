@@ -1,53 +1,91 @@
 use crate::{components::*, constants::*, utils::*};
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 
 #[derive(Resource, Default)]
 pub struct LevelStartDelayTimer(pub Timer);
 
+/// Handles to the font and projectile textures, loaded once instead of per spawn
+#[derive(Resource)]
+pub struct GameAssets {
+    pub font: Handle<Font>,
+    pub laser: Handle<Image>,
+    pub shot: Handle<Image>,
+    pub plasma: Handle<Image>,
+    pub beam: Handle<Image>,
+    pub beam_tip: Handle<Image>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self {
+            font: asset_server.load("fonts/DejaVuSans.ttf"),
+            laser: asset_server.load("img/laser.png"),
+            shot: asset_server.load("img/shot.png"),
+            plasma: asset_server.load("img/plasma.png"),
+            beam: asset_server.load("img/continuous_beam.png"),
+            beam_tip: asset_server.load("img/continuous_tip.png"),
+        }
+    }
+}
+
+/// Frames of the player ship in `atlas`
 #[derive(Default)]
 pub struct ShipImages {
-    pub rapid: Handle<Image>,
-    pub rapid_accelerating: Handle<Image>,
-    pub rapid_left: Handle<Image>,
-    pub rapid_left_accelerating: Handle<Image>,
-    pub rapid_right: Handle<Image>,
-    pub rapid_right_accelerating: Handle<Image>,
-    pub spread: Handle<Image>,
-    pub spread_accelerating: Handle<Image>,
-    pub spread_left: Handle<Image>,
-    pub spread_left_accelerating: Handle<Image>,
-    pub spread_right: Handle<Image>,
-    pub spread_right_accelerating: Handle<Image>,
-    pub beam: Handle<Image>,
-    pub beam_accelerating: Handle<Image>,
-    pub beam_left: Handle<Image>,
-    pub beam_left_accelerating: Handle<Image>,
-    pub beam_right: Handle<Image>,
-    pub beam_right_accelerating: Handle<Image>,
-    pub plasma: Handle<Image>,
-    pub plasma_accelerating: Handle<Image>,
-    pub plasma_left: Handle<Image>,
-    pub plasma_left_accelerating: Handle<Image>,
-    pub plasma_right: Handle<Image>,
-    pub plasma_right_accelerating: Handle<Image>,
-    pub shield: Handle<Image>,
+    pub atlas: Handle<TextureAtlas>,
+    pub rapid: usize,
+    pub rapid_accelerating: usize,
+    pub rapid_left: usize,
+    pub rapid_left_accelerating: usize,
+    pub rapid_right: usize,
+    pub rapid_right_accelerating: usize,
+    pub spread: usize,
+    pub spread_accelerating: usize,
+    pub spread_left: usize,
+    pub spread_left_accelerating: usize,
+    pub spread_right: usize,
+    pub spread_right_accelerating: usize,
+    pub beam: usize,
+    pub beam_accelerating: usize,
+    pub beam_left: usize,
+    pub beam_left_accelerating: usize,
+    pub beam_right: usize,
+    pub beam_right_accelerating: usize,
+    pub plasma: usize,
+    pub plasma_accelerating: usize,
+    pub plasma_left: usize,
+    pub plasma_left_accelerating: usize,
+    pub plasma_right: usize,
+    pub plasma_right_accelerating: usize,
+    pub shield: usize,
 }
 
+/// UFO animation frames in `atlas`, and the UFO projectile texture
 #[derive(Default)]
 pub struct UfoImages {
-    pub ship: Vec<Handle<Image>>,
+    pub atlas: Handle<TextureAtlas>,
+    pub ship: Vec<usize>,
     pub laser: Handle<Image>,
 }
 
+/// Powerup icons in `atlas`
 #[derive(Default)]
 pub struct PowerupImages {
-    pub laser: Handle<Image>,
-    pub spread: Handle<Image>,
-    pub beam: Handle<Image>,
-    pub plasma: Handle<Image>,
-    pub extra_life: Handle<Image>,
-    pub lose_life: Handle<Image>,
-    pub shield: Handle<Image>,
+    pub atlas: Handle<TextureAtlas>,
+    pub laser: usize,
+    pub spread: usize,
+    pub beam: usize,
+    pub plasma: usize,
+    pub charge: usize,
+    pub railgun: usize,
+    pub drone: usize,
+    pub plasma_ammo: usize,
+    pub extra_life: usize,
+    pub lose_life: usize,
+    pub shield: usize,
 }
 
 #[derive(Default)]
@@ -55,17 +93,18 @@ pub struct ExplosionImages {
     pub normal: Vec<Handle<Image>>,
 }
 
+/// Particle images in `atlas`
 #[derive(Default)]
 pub struct ParticleImages {
-    pub spark: Handle<Image>,
-    pub corona: Handle<Image>,
-    pub ring: Handle<Image>,
-    pub wave: Handle<Image>,
+    pub atlas: Handle<TextureAtlas>,
+    pub spark: usize,
+    pub corona: usize,
+    pub ring: usize,
+    pub wave: usize,
 }
 #[derive(Default, Resource)]
 pub struct SpriteSheets {
     pub asteroids: Handle<TextureAtlas>,
-    pub images: Vec<HandleUntyped>,
     pub ship: ShipImages,
     pub ufo: UfoImages,
     pub powerup: PowerupImages,
@@ -79,36 +118,218 @@ pub struct Level(pub u32);
 #[derive(Default, Resource)]
 pub struct Score(pub u32);
 
+#[derive(Default, Resource)]
+pub struct Minerals(pub u32);
+
+/// Speed of the world around the ship, multiplied into the time step of anything
+/// that should slow down with it
+#[derive(Resource)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Multiplier on score gained during play
+#[derive(Resource)]
+pub struct ScoreMultiplier(pub u32);
+
+impl Default for ScoreMultiplier {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+#[derive(Default, Resource, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Endless,
+    TimeAttack,
+    Survival,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Classic,
+        GameMode::Endless,
+        GameMode::TimeAttack,
+        GameMode::Survival,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::Endless => "Endless",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Survival => "Survival",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Clear the asteroids level by level",
+            GameMode::Endless => "Survive an endless stream of asteroids",
+            GameMode::TimeAttack => "Clear the first levels as fast as you can",
+            GameMode::Survival => "Fight off waves of asteroids and UFOs",
+        }
+    }
+}
+
+/// Rule change applied to every level of a run
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Modifier {
+    DoubleAsteroidSpeed,
+    NoShields,
+    BeamOnly,
+    LowGravity,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 4] = [
+        Modifier::DoubleAsteroidSpeed,
+        Modifier::NoShields,
+        Modifier::BeamOnly,
+        Modifier::LowGravity,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Modifier::DoubleAsteroidSpeed => "Double asteroid speed",
+            Modifier::NoShields => "No shields",
+            Modifier::BeamOnly => "Beam only",
+            Modifier::LowGravity => "Low gravity",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Modifier::DoubleAsteroidSpeed => "Asteroids fly twice as fast",
+            Modifier::NoShields => "Your shield never charges",
+            Modifier::BeamOnly => "The beam is the only weapon available",
+            Modifier::LowGravity => "Planets pull much more weakly",
+        }
+    }
+    /// Short symbol shown on the HUD while active
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Modifier::DoubleAsteroidSpeed => "»",
+            Modifier::NoShields => "Ø",
+            Modifier::BeamOnly => "|",
+            Modifier::LowGravity => "↓",
+        }
+    }
+}
+
+/// Modifiers picked for the current run
+#[derive(Default, Resource, Clone, Debug)]
+pub struct Modifiers(pub HashSet<Modifier>);
+
+impl Modifiers {
+    pub fn contains(&self, modifier: Modifier) -> bool {
+        self.0.contains(&modifier)
+    }
+    pub fn toggle(&mut self, modifier: Modifier) {
+        if !self.0.remove(&modifier) {
+            self.0.insert(modifier);
+        }
+    }
+    /// Active modifiers in menu order
+    pub fn iter(&self) -> impl Iterator<Item = Modifier> + '_ {
+        Modifier::ALL
+            .into_iter()
+            .filter(|modifier| self.contains(*modifier))
+    }
+    pub fn asteroid_speed(&self) -> f32 {
+        if self.contains(Modifier::DoubleAsteroidSpeed) {
+            2.0
+        } else {
+            1.0
+        }
+    }
+    pub fn gravity(&self) -> f32 {
+        if self.contains(Modifier::LowGravity) {
+            LOW_GRAVITY_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Statistics collected over a single run, reset on new game
+#[derive(Default, Resource, Clone)]
+pub struct RunStats {
+    pub levels_cleared: u32,
+    /// Indexed by `AsteroidSize`
+    pub asteroids_destroyed: [u32; 4],
+    pub ufos_destroyed: u32,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    /// Shots fired and hit, by the weapon equipped when firing
+    pub weapons: HashMap<ShipWeapon, ShotStats>,
+    pub powerups_collected: u32,
+    pub time_played: f32,
+    /// Played with assists on, marked on the high score table
+    pub assisted: bool,
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ShotStats {
+    pub fired: u32,
+    pub hit: u32,
+}
+
+impl ShotStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.fired == 0 {
+            0.0
+        } else {
+            self.hit as f32 / self.fired as f32
+        }
+    }
+}
+
+/// Snapshot of `RunStats` taken when a level starts, for per-level summaries
+#[derive(Default, Resource)]
+pub struct LevelStartStats(pub RunStats);
+
+impl RunStats {
+    pub fn accuracy(&self) -> f32 {
+        ShotStats {
+            fired: self.shots_fired,
+            hit: self.shots_hit,
+        }
+        .accuracy()
+    }
+}
+
 impl ShipImages {
-    pub fn choose(&self, ship: &Ship) -> Handle<Image> {
+    pub fn choose(&self, ship: &Ship) -> usize {
         use {ShipTurn::*, ShipWeapon::*};
         match (&ship.weapon, ship.turn, ship.throttle) {
-            (Rapid, Neutral, false) => &self.rapid,
-            (Rapid, Neutral, true) => &self.rapid_accelerating,
-            (Rapid, Left, false) => &self.rapid_left,
-            (Rapid, Left, true) => &self.rapid_left_accelerating,
-            (Rapid, Right, false) => &self.rapid_right,
-            (Rapid, Right, true) => &self.rapid_right_accelerating,
-            (Spread, Neutral, false) => &self.spread,
-            (Spread, Neutral, true) => &self.spread_accelerating,
-            (Spread, Left, false) => &self.spread_left,
-            (Spread, Left, true) => &self.spread_left_accelerating,
-            (Spread, Right, false) => &self.spread_right,
-            (Spread, Right, true) => &self.spread_right_accelerating,
-            (Beam, Neutral, false) => &self.beam,
-            (Beam, Neutral, true) => &self.beam_accelerating,
-            (Beam, Left, false) => &self.beam_left,
-            (Beam, Left, true) => &self.beam_left_accelerating,
-            (Beam, Right, false) => &self.beam_right,
-            (Beam, Right, true) => &self.beam_right_accelerating,
-            (Plasma, Neutral, false) => &self.plasma,
-            (Plasma, Neutral, true) => &self.plasma_accelerating,
-            (Plasma, Left, false) => &self.plasma_left,
-            (Plasma, Left, true) => &self.plasma_left_accelerating,
-            (Plasma, Right, false) => &self.plasma_right,
-            (Plasma, Right, true) => &self.plasma_right_accelerating,
-        }
-        .clone_weak()
+            (Rapid, Neutral, false) => self.rapid,
+            (Rapid, Neutral, true) => self.rapid_accelerating,
+            (Rapid, Left, false) => self.rapid_left,
+            (Rapid, Left, true) => self.rapid_left_accelerating,
+            (Rapid, Right, false) => self.rapid_right,
+            (Rapid, Right, true) => self.rapid_right_accelerating,
+            (Spread, Neutral, false) => self.spread,
+            (Spread, Neutral, true) => self.spread_accelerating,
+            (Spread, Left, false) => self.spread_left,
+            (Spread, Left, true) => self.spread_left_accelerating,
+            (Spread, Right, false) => self.spread_right,
+            (Spread, Right, true) => self.spread_right_accelerating,
+            (Beam | Railgun, Neutral, false) => self.beam,
+            (Beam | Railgun, Neutral, true) => self.beam_accelerating,
+            (Beam | Railgun, Left, false) => self.beam_left,
+            (Beam | Railgun, Left, true) => self.beam_left_accelerating,
+            (Beam | Railgun, Right, false) => self.beam_right,
+            (Beam | Railgun, Right, true) => self.beam_right_accelerating,
+            (Plasma | Charge, Neutral, false) => self.plasma,
+            (Plasma | Charge, Neutral, true) => self.plasma_accelerating,
+            (Plasma | Charge, Left, false) => self.plasma_left,
+            (Plasma | Charge, Left, true) => self.plasma_left_accelerating,
+            (Plasma | Charge, Right, false) => self.plasma_right,
+            (Plasma | Charge, Right, true) => self.plasma_right_accelerating,
+        }
     }
 }
 
@@ -179,6 +400,48 @@ impl Level {
     pub fn ufo_shoot_accuracy(&self) -> f32 {
         lerp(0.6, 0.9, self.0 as f32 / 60.0)
     }
+    /// Chance of a UFO dodging an incoming shot, from level 4 on
+    pub fn ufo_evasion_skill(&self) -> f32 {
+        lerp(0.0, 0.8, (self.0 as f32 - 2.0) / 40.0)
+    }
+    /// Seconds between enemy fighters, which start chasing the ship on level 6
+    pub fn fighter_spawn_interval(&self) -> Option<f32> {
+        match self.0 {
+            0..=4 => None,
+            n => Some(lerp(30.0, 12.0, (n - 5) as f32 / 40.0)),
+        }
+    }
+    /// Seconds between random events like meteor showers, which start on level 4
+    pub fn random_event_interval(&self) -> Option<std::ops::RangeInclusive<f32>> {
+        match self.0 {
+            0..=2 => None,
+            n => {
+                let min = lerp(40.0, 15.0, (n - 3) as f32 / 40.0);
+                Some(min..=min * 2.0)
+            }
+        }
+    }
+    /// Seconds between kamikazes, which start diving at the ship on level 8
+    pub fn kamikaze_spawn_interval(&self) -> Option<f32> {
+        match self.0 {
+            0..=6 => None,
+            n => Some(lerp(20.0, 6.0, (n - 7) as f32 / 40.0)),
+        }
+    }
+    /// Seconds between carriers, which start turning up on level 12
+    pub fn carrier_spawn_interval(&self) -> Option<f32> {
+        match self.0 {
+            0..=10 => None,
+            n => Some(lerp(60.0, 30.0, (n - 11) as f32 / 40.0)),
+        }
+    }
+    /// UFOs start flying in pairs on level 10, with one more every 8 levels after that
+    pub fn ufo_squadron_size(&self) -> u32 {
+        match self.0 {
+            0..=8 => 1,
+            n => (2 + (n - 9) / 8).min(UFO_MAX_SQUADRON),
+        }
+    }
 }
 
 impl Score {
@@ -189,3 +452,20 @@ impl Score {
         self.0
     }
 }
+
+impl Minerals {
+    pub fn increase(&mut self, amount: u32) {
+        self.0 += amount;
+    }
+    pub fn spend(&mut self, amount: u32) -> bool {
+        if self.0 >= amount {
+            self.0 -= amount;
+            true
+        } else {
+            false
+        }
+    }
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
@@ -1,3 +1,37 @@
+use bevy::prelude::Vec2;
+
 pub fn lerp(start: f32, end: f32, position: f32) -> f32 {
     start + (end - start) * position.clamp(0.0, 1.0)
 }
+
+pub fn crypt(content: &[u8]) -> Vec<u8> {
+    let key = "Space Rocks!".as_bytes().into_iter().cycle();
+    content.iter().zip(key).map(|(a, b)| a ^ b).collect()
+}
+
+/// Impulse applied to body A when it collides with body B along `normal` (pointing from B to A).
+/// Body B receives the opposite impulse. Returns zero if the bodies are already separating.
+pub fn collision_impulse(
+    normal: Vec2,
+    relative_velocity: Vec2,
+    mass_a: f32,
+    mass_b: f32,
+    restitution: f32,
+) -> Vec2 {
+    let approach = relative_velocity.dot(normal);
+    if approach >= 0.0 {
+        return Vec2::ZERO;
+    }
+    normal * -(1.0 + restitution) * approach / (1.0 / mass_a + 1.0 / mass_b)
+}
+
+/// Formats seconds as minutes, seconds and hundredths, e.g. "1:05.25"
+pub fn format_time(seconds: f32) -> String {
+    let hundredths = (seconds * 100.0) as u32;
+    format!(
+        "{}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}